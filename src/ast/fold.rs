@@ -0,0 +1,167 @@
+use std::rc::Rc;
+
+use super::{
+    node::{BlockStatement, Expr, FnExpr, IfExpr, Stmt, TryExpr},
+    Program,
+};
+
+// pass that rebuilds the tree, every method defaults to folding the children
+// so a pass only overrides the nodes it rewrites
+pub trait Folder {
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        fold_stmt(self, stmt)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_expr(self, expr)
+    }
+
+    fn fold_block(&mut self, block: BlockStatement) -> BlockStatement {
+        fold_block(self, block)
+    }
+
+    fn fold_function(&mut self, function: FnExpr) -> FnExpr {
+        fold_function(self, function)
+    }
+}
+
+pub fn fold_program<F: Folder + ?Sized>(folder: &mut F, mut program: Program) -> Program {
+    program.statements = program
+        .statements
+        .into_iter()
+        .map(|stmt| folder.fold_stmt(stmt))
+        .collect();
+    program
+}
+
+pub fn fold_block<F: Folder + ?Sized>(folder: &mut F, block: BlockStatement) -> BlockStatement {
+    let statements = Rc::unwrap_or_clone(block.statements)
+        .into_iter()
+        .map(|stmt| folder.fold_stmt(stmt))
+        .collect();
+    BlockStatement::new(Rc::new(statements))
+}
+
+pub fn fold_stmt<F: Folder + ?Sized>(folder: &mut F, stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Let(mut let_stmt) => {
+            let_stmt.value = folder.fold_expr(let_stmt.value);
+            Stmt::Let(let_stmt)
+        }
+        Stmt::Var(mut var_stmt) => {
+            var_stmt.value = folder.fold_expr(var_stmt.value);
+            Stmt::Var(var_stmt)
+        }
+        Stmt::Ret(mut ret) => {
+            ret.value = ret.value.map(|value| folder.fold_expr(value));
+            Stmt::Ret(ret)
+        }
+        Stmt::Throw(mut throw) => {
+            throw.value = folder.fold_expr(throw.value);
+            Stmt::Throw(throw)
+        }
+        Stmt::Test(mut test) => {
+            test.body = folder.fold_block(test.body);
+            Stmt::Test(test)
+        }
+        Stmt::Export(mut export) => {
+            export.statement = Box::new(folder.fold_stmt(*export.statement));
+            Stmt::Export(export)
+        }
+        Stmt::Expression(mut expr) => {
+            expr.expression = folder.fold_expr(expr.expression);
+            Stmt::Expression(expr)
+        }
+        stmt @ (Stmt::Import(_) | Stmt::Trivia(_)) => stmt,
+    }
+}
+
+pub fn fold_expr<F: Folder + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::If(if_expr) => Expr::If(fold_if(folder, if_expr)),
+        Expr::Call(mut call) => {
+            call.function = Box::new(folder.fold_expr(*call.function));
+            call.arguments = call
+                .arguments
+                .into_iter()
+                .map(|argument| folder.fold_expr(argument))
+                .collect();
+            Expr::Call(call)
+        }
+        Expr::Hash(mut hash) => {
+            for key in hash.keys.iter() {
+                let value = hash.pairs.remove(key).unwrap();
+                hash.pairs.insert(key.clone(), folder.fold_expr(value));
+            }
+            Expr::Hash(hash)
+        }
+        Expr::Array(mut array) => {
+            array.values = array
+                .values
+                .into_iter()
+                .map(|value| folder.fold_expr(value))
+                .collect();
+            Expr::Array(array)
+        }
+        Expr::Index(mut index) => {
+            index.left = Box::new(folder.fold_expr(*index.left));
+            index.index = Box::new(folder.fold_expr(*index.index));
+            Expr::Index(index)
+        }
+        Expr::Infix(mut infix) => {
+            infix.left = Box::new(folder.fold_expr(*infix.left));
+            infix.right = Box::new(folder.fold_expr(*infix.right));
+            Expr::Infix(infix)
+        }
+        Expr::While(mut while_expr) => {
+            while_expr.condition = Box::new(folder.fold_expr(*while_expr.condition));
+            while_expr.consequence = folder.fold_block(while_expr.consequence);
+            Expr::While(while_expr)
+        }
+        Expr::Prefix(mut prefix) => {
+            prefix.right = Box::new(folder.fold_expr(*prefix.right));
+            Expr::Prefix(prefix)
+        }
+        Expr::Try(try_expr) => Expr::Try(fold_try(folder, try_expr)),
+        Expr::Function(function) => Expr::Function(folder.fold_function(function)),
+        expr @ (Expr::Int(_)
+        | Expr::Bool(_)
+        | Expr::Nil(_)
+        | Expr::Float(_)
+        | Expr::String(_)
+        | Expr::Identifier(_)) => expr,
+    }
+}
+
+pub fn fold_if<F: Folder + ?Sized>(folder: &mut F, mut if_expr: IfExpr) -> IfExpr {
+    if_expr.condition = Box::new(folder.fold_expr(*if_expr.condition));
+    if_expr.consequence = folder.fold_block(if_expr.consequence);
+    if_expr.el_if = if_expr.el_if.map(|el_if| Box::new(fold_if(folder, *el_if)));
+    if_expr.alternative = if_expr
+        .alternative
+        .map(|alternative| folder.fold_block(alternative));
+    if_expr
+}
+
+pub fn fold_try<F: Folder + ?Sized>(folder: &mut F, mut try_expr: TryExpr) -> TryExpr {
+    try_expr.body = folder.fold_block(try_expr.body);
+    try_expr.catch = try_expr.catch.map(|mut catch| {
+        catch.body = folder.fold_block(catch.body);
+        catch
+    });
+    try_expr.finally = try_expr.finally.map(|finally| folder.fold_block(finally));
+    try_expr
+}
+
+pub fn fold_function<F: Folder + ?Sized>(folder: &mut F, mut function: FnExpr) -> FnExpr {
+    let parameters = Rc::unwrap_or_clone(function.parameters)
+        .into_iter()
+        .map(|mut parameter| {
+            parameter.default = parameter.default.map(|default| folder.fold_expr(default));
+            parameter
+        })
+        .collect();
+    function.parameters = Rc::new(parameters);
+    function.body = Rc::new(folder.fold_block(Rc::unwrap_or_clone(function.body)));
+    function
+}
@@ -1,14 +1,18 @@
-use std::fmt::Display;
+use std::{cell::Cell, fmt::Display};
 
-use self::node::{Node, NodeRef, OpCode};
+use self::node::write_statements;
 
+pub mod fold;
 pub mod node;
+pub mod visit;
 
 pub use node::*;
 
+#[derive(Clone)]
 pub struct Program {
-    pub statements: Vec<NodeRef>,
+    pub statements: Vec<Stmt>,
     pub errors: Vec<String>,
+    pub slots: Cell<usize>,
 }
 
 impl Program {
@@ -16,10 +20,11 @@ impl Program {
         Self {
             statements: vec![],
             errors: vec![],
+            slots: Cell::new(0),
         }
     }
 
-    pub fn push_statements(&mut self, stmt: NodeRef) {
+    pub fn push_statements(&mut self, stmt: Stmt) {
         self.statements.push(stmt)
     }
 
@@ -28,19 +33,9 @@ impl Program {
     }
 }
 
-impl Node for Program {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Program
-    }
-}
-
 impl Display for Program {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_statements(f, &self.statements, 0, "")
     }
 }
 
@@ -1,29 +1,23 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, write_list, Expr};
 
+#[derive(Clone)]
 pub struct ArrayExpr {
-    pub values: Vec<NodeRef>,
+    pub values: Vec<Expr>,
 }
 
 impl ArrayExpr {
-    pub fn new(values: Vec<NodeRef>) -> Self {
+    pub fn new(values: Vec<Expr>) -> Self {
         Self { values }
     }
 }
 
-impl Node for ArrayExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Array
-    }
-}
-
 impl Display for ArrayExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "[")?;
+        write_list(f, &self.values, indent)?;
+        write!(f, "]")
     }
 }
@@ -1,7 +1,6 @@
 use std::fmt::Display;
 
-use crate::ast::node::{Node, OpCode};
-
+#[derive(Clone)]
 pub struct BoolExpr {
     pub value: bool,
 }
@@ -12,18 +11,8 @@ impl BoolExpr {
     }
 }
 
-impl Node for BoolExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Bool
-    }
-}
-
 impl Display for BoolExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
     }
 }
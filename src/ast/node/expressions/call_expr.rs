@@ -1,33 +1,42 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::{
+    ast::node::{binding_power, indent_level, write_list, write_operand, Expr},
+    lexer::Location,
+    parser::precedence::Precedence,
+};
 
+#[derive(Clone)]
 pub struct CallExpr {
-    pub function: NodeRef,
-    pub arguments: Vec<NodeRef>,
+    pub function: Box<Expr>,
+    pub arguments: Vec<Expr>,
+    pub locations: Vec<Location>,
+    pub location: Location,
 }
 
 impl CallExpr {
-    pub fn new(function: NodeRef, arguments: Vec<NodeRef>) -> Self {
+    pub fn new(
+        function: Expr,
+        arguments: Vec<Expr>,
+        locations: Vec<Location>,
+        location: Location,
+    ) -> Self {
         Self {
-            function,
+            function: Box::new(function),
             arguments,
+            locations,
+            location,
         }
     }
 }
 
-impl Node for CallExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Call
-    }
-}
-
 impl Display for CallExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        let grouped = binding_power(&self.function) < Precedence::Call;
+        write_operand(f, &self.function, indent, grouped)?;
+        write!(f, "(")?;
+        write_list(f, &self.arguments, indent)?;
+        write!(f, ")")
     }
 }
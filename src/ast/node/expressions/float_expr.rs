@@ -1,7 +1,6 @@
 use std::fmt::Display;
 
-use crate::ast::node::{Node, OpCode};
-
+#[derive(Clone)]
 pub struct FloatExpr {
     pub value: f64,
 }
@@ -12,18 +11,13 @@ impl FloatExpr {
     }
 }
 
-impl Node for FloatExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Float
-    }
-}
-
 impl Display for FloatExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.value.to_string();
+        if value.contains('.') {
+            write!(f, "{}", value)
+        } else {
+            write!(f, "{}.0", value)
+        }
     }
 }
@@ -1,35 +1,39 @@
-use std::{any::Any, fmt::Display, rc::Rc};
+use std::{cell::Cell, fmt::Display, rc::Rc};
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, write_list, BlockStatement, IdentifierExpr, ParameterExpr};
 
+#[derive(Clone)]
 pub struct FnExpr {
-    pub body: Rc<NodeRef>,
-    pub name: Option<Rc<NodeRef>>,
-    pub parameters: Rc<Vec<NodeRef>>,
+    pub body: Rc<BlockStatement>,
+    pub name: Option<IdentifierExpr>,
+    pub parameters: Rc<Vec<ParameterExpr>>,
+    pub slots: Cell<usize>,
 }
 
 impl FnExpr {
-    pub fn new(body: Rc<NodeRef>, name: Option<Rc<NodeRef>>, parameters: Rc<Vec<NodeRef>>) -> Self {
+    pub fn new(
+        body: Rc<BlockStatement>,
+        name: Option<IdentifierExpr>,
+        parameters: Rc<Vec<ParameterExpr>>,
+    ) -> Self {
         Self {
             body,
             name,
             parameters,
+            slots: Cell::new(0),
         }
     }
 }
 
-impl Node for FnExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Function
-    }
-}
-
 impl Display for FnExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "fn")?;
+        if let Some(ref name) = self.name {
+            write!(f, " {}", name)?;
+        }
+        write!(f, "(")?;
+        write_list(f, &self.parameters, indent)?;
+        write!(f, ") {:indent$}", self.body)
     }
 }
@@ -1,36 +1,42 @@
-use std::{any::Any, collections::HashMap, fmt::Display};
+use std::{collections::HashMap, fmt::Display};
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, Expr, INDENT};
 
+#[derive(Clone)]
 pub struct HashExpr {
-    pub pairs: HashMap<String, NodeRef>,
+    pub pairs: HashMap<String, Expr>,
+    pub keys: Vec<String>,
 }
 
 impl HashExpr {
     pub fn new() -> Self {
         Self {
             pairs: Default::default(),
+            keys: Default::default(),
         }
     }
 
-    pub fn set(&mut self, key: String, value: NodeRef) {
-        self.pairs.insert(key, value);
-    }
-}
-
-impl Node for HashExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Hash
+    pub fn set(&mut self, key: String, value: Expr) {
+        if self.pairs.insert(key.clone(), value).is_none() {
+            self.keys.push(key);
+        }
     }
 }
 
 impl Display for HashExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        if self.keys.is_empty() {
+            return write!(f, "| |");
+        }
+        let padding = INDENT.repeat(indent);
+        writeln!(f, "|")?;
+        let inner = indent + 1;
+        for key in self.keys.iter() {
+            let value = &self.pairs[key];
+            writeln!(f, "{}{}{}: {:inner$},", padding, INDENT, key, value)?;
+        }
+        write!(f, "{}|", padding)
     }
 }
 
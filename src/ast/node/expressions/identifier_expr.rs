@@ -1,24 +1,21 @@
-use std::fmt::Display;
+use std::{cell::Cell, fmt::Display};
 
-use crate::ast::node::{Node, OpCode};
+use crate::{lexer::Location, resolver::Slot};
 
+#[derive(Clone)]
 pub struct IdentifierExpr {
     pub value: String,
+    pub location: Location,
+    pub slot: Cell<Slot>,
 }
 
 impl IdentifierExpr {
-    pub fn new(value: String) -> Self {
-        Self { value }
-    }
-}
-
-impl Node for IdentifierExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Identifier
+    pub fn new(value: String, location: Location) -> Self {
+        Self {
+            value,
+            location,
+            slot: Cell::new(Slot::Global),
+        }
     }
 }
 
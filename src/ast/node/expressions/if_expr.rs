@@ -1,18 +1,19 @@
 use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, BlockStatement, Expr};
 
+#[derive(Clone)]
 pub struct IfExpr {
-    pub condition: NodeRef,
-    pub consequence: NodeRef,
-    pub alternative: Option<NodeRef>,
-    pub el_if: Option<NodeRef>,
+    pub condition: Box<Expr>,
+    pub consequence: BlockStatement,
+    pub alternative: Option<BlockStatement>,
+    pub el_if: Option<Box<IfExpr>>,
 }
 
 impl IfExpr {
-    pub fn new(condition: NodeRef, consequence: NodeRef) -> Self {
+    pub fn new(condition: Expr, consequence: BlockStatement) -> Self {
         Self {
-            condition,
+            condition: Box::new(condition),
             consequence,
             alternative: None,
             el_if: None,
@@ -20,18 +21,20 @@ impl IfExpr {
     }
 }
 
-impl Node for IfExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::If
-    }
-}
-
 impl Display for IfExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(
+            f,
+            "if {:indent$} {:indent$}",
+            self.condition, self.consequence
+        )?;
+        if let Some(ref el_if) = self.el_if {
+            write!(f, " else {:indent$}", el_if)
+        } else if let Some(ref alternative) = self.alternative {
+            write!(f, " else {:indent$}", alternative)
+        } else {
+            Ok(())
+        }
     }
 }
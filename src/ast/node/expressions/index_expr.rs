@@ -1,30 +1,34 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::{
+    ast::node::{binding_power, indent_level, write_operand, Expr},
+    lexer::Location,
+    parser::precedence::Precedence,
+};
 
+#[derive(Clone)]
 pub struct IndexExpr {
-    pub left: NodeRef,
-    pub index: NodeRef,
+    pub left: Box<Expr>,
+    pub index: Box<Expr>,
+    // where the opening bracket is
+    pub location: Location,
 }
 
 impl IndexExpr {
-    pub fn new(left: NodeRef, index: NodeRef) -> Self {
-        Self { left, index }
-    }
-}
-
-impl Node for IndexExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Index
+    pub fn new(left: Expr, index: Expr, location: Location) -> Self {
+        Self {
+            left: Box::new(left),
+            index: Box::new(index),
+            location,
+        }
     }
 }
 
 impl Display for IndexExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        let grouped = binding_power(&self.left) < Precedence::Dot;
+        write_operand(f, &self.left, indent, grouped)?;
+        write!(f, "[{:indent$}]", self.index)
     }
 }
@@ -1,35 +1,53 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::{
+    ast::node::{binding_power, indent_level, write_operand, Expr},
+    lexer::Location,
+    parser::precedence::Precedence,
+};
 
+#[derive(Clone)]
 pub struct InfixExpr {
-    pub operator: String,
-    pub right: NodeRef,
-    pub left: NodeRef,
+    pub operator: &'static str,
+    pub right: Box<Expr>,
+    pub left: Box<Expr>,
+    // where the operator is, errors of the operation are reported there
+    pub location: Location,
 }
 
 impl InfixExpr {
-    pub fn new(operator: String, right: NodeRef, left: NodeRef) -> Self {
+    pub fn new(operator: &'static str, right: Expr, left: Expr, location: Location) -> Self {
         Self {
             operator,
-            right,
-            left,
+            right: Box::new(right),
+            left: Box::new(left),
+            location,
         }
     }
 }
 
-impl Node for InfixExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Infix
-    }
-}
-
 impl Display for InfixExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        let precedence = Precedence::of_operator(self.operator);
+        // ** groups to the right, its right side is parsed like the operand of a prefix
+        let (left_grouped, right_grouped) = if self.operator == "**" {
+            (
+                binding_power(&self.left) <= precedence,
+                binding_power(&self.right) < Precedence::Prefix,
+            )
+        } else {
+            (
+                binding_power(&self.left) < precedence,
+                binding_power(&self.right) <= precedence,
+            )
+        };
+        write_operand(f, &self.left, indent, left_grouped)?;
+        if self.operator == "." {
+            write!(f, ".")?;
+        } else {
+            write!(f, " {} ", self.operator)?;
+        }
+        write_operand(f, &self.right, indent, right_grouped)
     }
 }
@@ -9,8 +9,11 @@ mod if_expr;
 mod index_expr;
 mod infix_expr;
 mod int_expr;
+mod nil_expr;
+mod parameter_expr;
 mod prefix_expr;
 mod string_expr;
+mod try_expr;
 mod while_expr;
 
 pub use array_expr::*;
@@ -24,6 +27,9 @@ pub use if_expr::*;
 pub use index_expr::*;
 pub use infix_expr::*;
 pub use int_expr::*;
+pub use nil_expr::*;
+pub use parameter_expr::*;
 pub use prefix_expr::*;
 pub use string_expr::*;
+pub use try_expr::*;
 pub use while_expr::*;
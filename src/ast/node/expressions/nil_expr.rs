@@ -0,0 +1,10 @@
+use std::fmt::Display;
+
+#[derive(Clone)]
+pub struct NilExpr;
+
+impl Display for NilExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "nil")
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt::Display;
+
+use crate::ast::node::{indent_level, Expr};
+
+#[derive(Clone)]
+pub struct ParameterExpr {
+    pub name: String,
+    pub default: Option<Expr>,
+    pub rest: bool,
+}
+
+impl ParameterExpr {
+    pub fn new(name: String, default: Option<Expr>, rest: bool) -> Self {
+        Self {
+            name,
+            default,
+            rest,
+        }
+    }
+}
+
+impl Display for ParameterExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        if self.rest {
+            write!(f, "...")?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(ref default) = self.default {
+            write!(f, " = {:indent$}", default)?;
+        }
+        Ok(())
+    }
+}
@@ -1,30 +1,34 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::{
+    ast::node::{binding_power, indent_level, write_operand, Expr},
+    lexer::Location,
+    parser::precedence::Precedence,
+};
 
+#[derive(Clone)]
 pub struct PrefixExpr {
-    pub operator: String,
-    pub right: NodeRef,
+    pub operator: &'static str,
+    pub right: Box<Expr>,
+    // where the operator is, errors of the operation are reported there
+    pub location: Location,
 }
 
 impl PrefixExpr {
-    pub fn new(operator: String, right: NodeRef) -> Self {
-        Self { operator, right }
-    }
-}
-
-impl Node for PrefixExpr {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Prefix
+    pub fn new(operator: &'static str, right: Expr, location: Location) -> Self {
+        Self {
+            operator,
+            right: Box::new(right),
+            location,
+        }
     }
 }
 
 impl Display for PrefixExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "{}", self.operator)?;
+        let grouped = binding_power(&self.right) < Precedence::Prefix;
+        write_operand(f, &self.right, indent, grouped)
     }
 }
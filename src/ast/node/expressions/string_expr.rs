@@ -1,7 +1,6 @@
 use std::fmt::Display;
 
-use crate::ast::node::{Node, OpCode};
-
+#[derive(Clone)]
 pub struct StringExpr {
     pub value: String,
 }
@@ -12,18 +11,8 @@ impl StringExpr {
     }
 }
 
-impl Node for StringExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::String
-    }
-}
-
 impl Display for StringExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\"", self.value)
     }
 }
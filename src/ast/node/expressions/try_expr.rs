@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+use crate::ast::node::{indent_level, BlockStatement, IdentifierExpr};
+
+#[derive(Clone)]
+pub struct TryExpr {
+    pub body: BlockStatement,
+    pub catch: Option<CatchClause>,
+    pub finally: Option<BlockStatement>,
+}
+
+// the caught error is bound to the name inside of the catch block only
+#[derive(Clone)]
+pub struct CatchClause {
+    pub name: IdentifierExpr,
+    pub body: BlockStatement,
+}
+
+impl TryExpr {
+    pub fn new(
+        body: BlockStatement,
+        catch: Option<CatchClause>,
+        finally: Option<BlockStatement>,
+    ) -> Self {
+        Self {
+            body,
+            catch,
+            finally,
+        }
+    }
+}
+
+impl CatchClause {
+    pub fn new(name: IdentifierExpr, body: BlockStatement) -> Self {
+        Self { name, body }
+    }
+}
+
+impl Display for TryExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "try {:indent$}", self.body)?;
+        if let Some(ref catch) = self.catch {
+            write!(f, " catch {} {:indent$}", catch.name, catch.body)?;
+        }
+        if let Some(ref finally) = self.finally {
+            write!(f, " finally {:indent$}", finally)?;
+        }
+        Ok(())
+    }
+}
@@ -1,33 +1,29 @@
 use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, BlockStatement, Expr};
 
+#[derive(Clone)]
 pub struct WhileExpr {
-    pub condition: NodeRef,
-    pub consequence: NodeRef,
+    pub condition: Box<Expr>,
+    pub consequence: BlockStatement,
 }
 
 impl WhileExpr {
-    pub fn new(condition: NodeRef, consequence: NodeRef) -> Self {
+    pub fn new(condition: Expr, consequence: BlockStatement) -> Self {
         Self {
-            condition,
+            condition: Box::new(condition),
             consequence,
         }
     }
 }
 
-impl Node for WhileExpr {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::While
-    }
-}
-
 impl Display for WhileExpr {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(
+            f,
+            "while {:indent$} {:indent$}",
+            self.condition, self.consequence
+        )
     }
 }
@@ -4,34 +4,215 @@ mod statements;
 pub use expressions::*;
 pub use statements::*;
 
-use std::{any::Any, fmt::Display};
+use std::fmt::{Display, Formatter, Result};
 
-pub type NodeRef = Box<dyn Node>;
-pub trait Node: Display {
-    fn as_any(&self) -> &dyn Any;
-    fn get_op_code(&self) -> OpCode;
+use crate::parser::precedence::Precedence;
+
+#[derive(Clone)]
+pub enum Stmt {
+    Let(LetStatement),
+    Var(VarStatement),
+    Ret(RetStatement),
+    Throw(ThrowStmt),
+    Test(TestStmt),
+    Import(ImportStmt),
+    Export(ExportStmt),
+    Expression(ExpressionStmt),
+    Trivia(Trivia),
+}
+
+#[derive(Clone)]
+pub enum Expr {
+    If(IfExpr),
+    Int(IntExpr),
+    Try(TryExpr),
+    Call(CallExpr),
+    Bool(BoolExpr),
+    Nil(NilExpr),
+    Hash(HashExpr),
+    Array(ArrayExpr),
+    Index(IndexExpr),
+    Infix(InfixExpr),
+    Float(FloatExpr),
+    While(WhileExpr),
+    Prefix(PrefixExpr),
+    String(StringExpr),
+    Function(FnExpr),
+    Identifier(IdentifierExpr),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OpCode {
     If,
     Let,
     Var,
     Ret,
     Int,
+    Try,
     Call,
     Bool,
+    Nil,
     Hash,
     Array,
     Index,
     Block,
+    Catch,
+    Throw,
+    Test,
+    Import,
+    Export,
     Infix,
     Float,
     While,
     Prefix,
     String,
+    Trivia,
     Program,
     Function,
+    Parameter,
     Expression,
     Identifier,
 }
+
+impl Stmt {
+    pub fn op_code(&self) -> OpCode {
+        match self {
+            Self::Let(_) => OpCode::Let,
+            Self::Var(_) => OpCode::Var,
+            Self::Ret(_) => OpCode::Ret,
+            Self::Throw(_) => OpCode::Throw,
+            Self::Test(_) => OpCode::Test,
+            Self::Import(_) => OpCode::Import,
+            Self::Export(_) => OpCode::Export,
+            Self::Expression(_) => OpCode::Expression,
+            Self::Trivia(_) => OpCode::Trivia,
+        }
+    }
+}
+
+impl Expr {
+    pub fn op_code(&self) -> OpCode {
+        match self {
+            Self::If(_) => OpCode::If,
+            Self::Int(_) => OpCode::Int,
+            Self::Try(_) => OpCode::Try,
+            Self::Call(_) => OpCode::Call,
+            Self::Bool(_) => OpCode::Bool,
+            Self::Nil(_) => OpCode::Nil,
+            Self::Hash(_) => OpCode::Hash,
+            Self::Array(_) => OpCode::Array,
+            Self::Index(_) => OpCode::Index,
+            Self::Infix(_) => OpCode::Infix,
+            Self::Float(_) => OpCode::Float,
+            Self::While(_) => OpCode::While,
+            Self::Prefix(_) => OpCode::Prefix,
+            Self::String(_) => OpCode::String,
+            Self::Function(_) => OpCode::Function,
+            Self::Identifier(_) => OpCode::Identifier,
+        }
+    }
+}
+
+impl Display for Stmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::Let(stmt) => stmt.fmt(f),
+            Self::Var(stmt) => stmt.fmt(f),
+            Self::Ret(stmt) => stmt.fmt(f),
+            Self::Throw(stmt) => stmt.fmt(f),
+            Self::Test(stmt) => stmt.fmt(f),
+            Self::Import(stmt) => stmt.fmt(f),
+            Self::Export(stmt) => stmt.fmt(f),
+            Self::Expression(stmt) => stmt.fmt(f),
+            Self::Trivia(trivia) => trivia.fmt(f),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::If(expr) => expr.fmt(f),
+            Self::Int(expr) => expr.fmt(f),
+            Self::Try(expr) => expr.fmt(f),
+            Self::Call(expr) => expr.fmt(f),
+            Self::Bool(expr) => expr.fmt(f),
+            Self::Nil(expr) => expr.fmt(f),
+            Self::Hash(expr) => expr.fmt(f),
+            Self::Array(expr) => expr.fmt(f),
+            Self::Index(expr) => expr.fmt(f),
+            Self::Infix(expr) => expr.fmt(f),
+            Self::Float(expr) => expr.fmt(f),
+            Self::While(expr) => expr.fmt(f),
+            Self::Prefix(expr) => expr.fmt(f),
+            Self::String(expr) => expr.fmt(f),
+            Self::Function(expr) => expr.fmt(f),
+            Self::Identifier(expr) => expr.fmt(f),
+        }
+    }
+}
+
+pub(crate) const INDENT: &str = "    ";
+
+// the formatter width is used as the indentation level of nested blocks
+pub(crate) fn indent_level(f: &Formatter<'_>) -> usize {
+    f.width().unwrap_or(0)
+}
+
+pub(crate) fn binding_power(expr: &Expr) -> Precedence {
+    match expr {
+        Expr::Infix(infix) => Precedence::of_operator(infix.operator),
+        Expr::Prefix(_) => Precedence::Prefix,
+        _ => Precedence::Call,
+    }
+}
+
+pub(crate) fn write_operand(
+    f: &mut Formatter<'_>,
+    expr: &Expr,
+    indent: usize,
+    grouped: bool,
+) -> Result {
+    if grouped {
+        write!(f, "({:indent$})", expr)
+    } else {
+        write!(f, "{:indent$}", expr)
+    }
+}
+
+pub(crate) fn write_statements(
+    f: &mut Formatter<'_>,
+    stmts: &[Stmt],
+    indent: usize,
+    padding: &str,
+) -> Result {
+    for (idx, stmt) in stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Trivia(Trivia::TrailingComment(comment)) => write!(f, " {}", comment)?,
+            Stmt::Trivia(Trivia::BlankLine) => writeln!(f)?,
+            Stmt::Trivia(comment) => {
+                if idx > 0 || indent > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "{}{}", padding, comment)?;
+            }
+            _ => {
+                if idx > 0 || indent > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "{}{:indent$}", padding, stmt)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_list<T: Display>(f: &mut Formatter<'_>, nodes: &[T], indent: usize) -> Result {
+    for (idx, node) in nodes.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{:indent$}", node)?;
+    }
+    Ok(())
+}
@@ -1,27 +1,18 @@
-use std::{any::Any, fmt::Display, rc::Rc};
+use std::{fmt::Display, rc::Rc};
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, write_statements, Stmt, INDENT};
 
+#[derive(Clone)]
 pub struct BlockStatement {
-    pub statements: Rc<Vec<NodeRef>>,
+    pub statements: Rc<Vec<Stmt>>,
 }
 
 impl BlockStatement {
-    pub fn new(statements: Rc<Vec<NodeRef>>) -> Self {
+    pub fn new(statements: Rc<Vec<Stmt>>) -> Self {
         Self { statements }
     }
 }
 
-impl Node for BlockStatement {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Block
-    }
-}
-
 impl Default for BlockStatement {
     fn default() -> Self {
         Self::new(Rc::new(vec![]))
@@ -29,7 +20,14 @@ impl Default for BlockStatement {
 }
 
 impl Display for BlockStatement {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        if self.statements.is_empty() {
+            return write!(f, "{{}}");
+        }
+        let padding = INDENT.repeat(indent);
+        write!(f, "{{")?;
+        write_statements(f, &self.statements, indent + 1, &INDENT.repeat(indent + 1))?;
+        write!(f, "\n{}}}", padding)
     }
 }
@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use crate::ast::node::{indent_level, Expr, Stmt};
+
+use super::ExpressionStmt;
+
+#[derive(Clone)]
+pub struct ExportStmt {
+    pub statement: Box<Stmt>,
+}
+
+impl ExportStmt {
+    pub fn new(statement: Stmt) -> Self {
+        Self {
+            statement: Box::new(statement),
+        }
+    }
+
+    pub fn name(&self) -> Option<String> {
+        match &*self.statement {
+            Stmt::Let(stmt) => Some(stmt.name.to_string()),
+            Stmt::Var(stmt) => Some(stmt.name.to_string()),
+            Stmt::Expression(ExpressionStmt {
+                expression: Expr::Function(function),
+            }) => function.name.as_ref().map(|name| name.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ExportStmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "export {:indent$}", self.statement)
+    }
+}
@@ -1,29 +1,26 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, Expr};
 
+#[derive(Clone)]
 pub struct ExpressionStmt {
-    pub expression: NodeRef,
+    pub expression: Expr,
 }
 
 impl ExpressionStmt {
-    pub fn new(expr: NodeRef) -> Self {
+    pub fn new(expr: Expr) -> Self {
         Self { expression: expr }
     }
 }
 
-impl Node for ExpressionStmt {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Expression
-    }
-}
-
 impl Display for ExpressionStmt {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "{:indent$}", self.expression)?;
+        match self.expression {
+            Expr::If(_) | Expr::While(_) | Expr::Try(_) => Ok(()),
+            Expr::Function(ref function) if function.name.is_some() => Ok(()),
+            _ => write!(f, ";"),
+        }
     }
 }
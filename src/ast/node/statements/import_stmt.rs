@@ -0,0 +1,50 @@
+use std::{cell::Cell, fmt::Display};
+
+use crate::{lexer::Location, resolver::Slot};
+
+#[derive(Clone)]
+pub struct ImportStmt {
+    pub path: String,
+    pub alias: Option<String>,
+    pub location: Location,
+    pub slot: Cell<Slot>,
+}
+
+impl ImportStmt {
+    pub fn new(path: String, alias: Option<String>, location: Location) -> Self {
+        Self {
+            path,
+            alias,
+            location,
+            slot: Cell::new(Slot::Global),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self.alias {
+            Some(ref alias) => alias.clone(),
+            None => {
+                let file = self.path.rsplit('/').next().unwrap_or(&self.path);
+                file.strip_suffix(".mila").unwrap_or(file).to_string()
+            }
+        }
+    }
+}
+
+impl Display for ImportStmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let is_identifier = self
+            .path
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        if is_identifier {
+            write!(f, "import {}", self.path)?;
+        } else {
+            write!(f, "import \"{}\"", self.path)?;
+        }
+        if let Some(ref alias) = self.alias {
+            write!(f, " as {}", alias)?;
+        }
+        write!(f, ";")
+    }
+}
@@ -1,11 +1,21 @@
 mod block_stmt;
+mod export_stmt;
 mod expression_stmt;
+mod import_stmt;
 mod let_stmt;
 mod ret_stmt;
+mod test_stmt;
+mod throw_stmt;
+mod trivia_stmt;
 mod var_stmt;
 
 pub use block_stmt::*;
+pub use export_stmt::*;
 pub use expression_stmt::*;
+pub use import_stmt::*;
 pub use let_stmt::*;
 pub use ret_stmt::*;
+pub use test_stmt::*;
+pub use throw_stmt::*;
+pub use trivia_stmt::*;
 pub use var_stmt::*;
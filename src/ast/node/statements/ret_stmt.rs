@@ -1,29 +1,24 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, Expr};
 
+#[derive(Clone)]
 pub struct RetStatement {
-    pub value: Option<NodeRef>,
+    pub value: Option<Expr>,
 }
 
 impl RetStatement {
-    pub fn new(value: Option<NodeRef>) -> Self {
+    pub fn new(value: Option<Expr>) -> Self {
         Self { value }
     }
 }
 
-impl Node for RetStatement {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Ret
-    }
-}
-
 impl Display for RetStatement {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        match self.value {
+            Some(ref value) => write!(f, "ret {:indent$};", value),
+            None => write!(f, "ret;"),
+        }
     }
 }
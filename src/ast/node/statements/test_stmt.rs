@@ -0,0 +1,31 @@
+use std::fmt::Display;
+
+use crate::{
+    ast::node::{indent_level, BlockStatement},
+    lexer::Location,
+};
+
+// only run by `mila test`, running the script skips it
+#[derive(Clone)]
+pub struct TestStmt {
+    pub name: String,
+    pub body: BlockStatement,
+    pub location: Location,
+}
+
+impl TestStmt {
+    pub fn new(name: String, body: BlockStatement, location: Location) -> Self {
+        Self {
+            name,
+            body,
+            location,
+        }
+    }
+}
+
+impl Display for TestStmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "test \"{}\" {:indent$}", self.name, self.body)
+    }
+}
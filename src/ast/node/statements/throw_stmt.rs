@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+use crate::{
+    ast::node::{indent_level, Expr},
+    lexer::Location,
+};
+
+#[derive(Clone)]
+pub struct ThrowStmt {
+    pub value: Expr,
+    pub location: Location,
+}
+
+impl ThrowStmt {
+    pub fn new(value: Expr, location: Location) -> Self {
+        Self { value, location }
+    }
+}
+
+impl Display for ThrowStmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "throw {:indent$};", self.value)
+    }
+}
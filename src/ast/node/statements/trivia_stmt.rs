@@ -0,0 +1,17 @@
+use std::fmt::Display;
+
+#[derive(Clone)]
+pub enum Trivia {
+    Comment(String),
+    TrailingComment(String),
+    BlankLine,
+}
+
+impl Display for Trivia {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Comment(comment) | Self::TrailingComment(comment) => write!(f, "{}", comment),
+            Self::BlankLine => Ok(()),
+        }
+    }
+}
@@ -1,30 +1,22 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use crate::ast::node::{Node, NodeRef, OpCode};
+use crate::ast::node::{indent_level, Expr, IdentifierExpr};
 
+#[derive(Clone)]
 pub struct VarStatement {
-    pub name: NodeRef,
-    pub value: NodeRef,
+    pub name: IdentifierExpr,
+    pub value: Expr,
 }
 
 impl VarStatement {
-    pub fn new(name: NodeRef, value: NodeRef) -> Self {
+    pub fn new(name: IdentifierExpr, value: Expr) -> Self {
         Self { name, value }
     }
 }
 
-impl Node for VarStatement {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_op_code(&self) -> OpCode {
-        OpCode::Var
-    }
-}
-
 impl Display for VarStatement {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let indent = indent_level(f);
+        write!(f, "var {} = {:indent$};", self.name, self.value)
     }
 }
@@ -0,0 +1,127 @@
+use super::{
+    node::{BlockStatement, Expr, FnExpr, IfExpr, Stmt, TryExpr},
+    Program,
+};
+
+// read only pass over the tree, every method defaults to walking the children
+// in the order they are evaluated so a pass only overrides the nodes it cares about
+pub trait Visitor {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_block(&mut self, block: &BlockStatement) {
+        walk_block(self, block)
+    }
+
+    fn visit_function(&mut self, function: &FnExpr) {
+        walk_function(self, function)
+    }
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for stmt in program.statements.iter() {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_block<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockStatement) {
+    for stmt in block.statements.iter() {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Let(let_stmt) => visitor.visit_expr(&let_stmt.value),
+        Stmt::Var(var_stmt) => visitor.visit_expr(&var_stmt.value),
+        Stmt::Ret(ret) => {
+            if let Some(value) = &ret.value {
+                visitor.visit_expr(value);
+            }
+        }
+        Stmt::Throw(throw) => visitor.visit_expr(&throw.value),
+        Stmt::Test(test) => visitor.visit_block(&test.body),
+        Stmt::Export(export) => visitor.visit_stmt(&export.statement),
+        Stmt::Expression(expr) => visitor.visit_expr(&expr.expression),
+        Stmt::Import(_) | Stmt::Trivia(_) => {}
+    }
+}
+
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::If(if_expr) => walk_if(visitor, if_expr),
+        Expr::Call(call) => {
+            visitor.visit_expr(&call.function);
+            for argument in call.arguments.iter() {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::Hash(hash) => {
+            for key in hash.keys.iter() {
+                visitor.visit_expr(&hash.pairs[key]);
+            }
+        }
+        Expr::Array(array) => {
+            for value in array.values.iter() {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Index(index) => {
+            visitor.visit_expr(&index.left);
+            visitor.visit_expr(&index.index);
+        }
+        Expr::Infix(infix) => {
+            visitor.visit_expr(&infix.left);
+            visitor.visit_expr(&infix.right);
+        }
+        Expr::While(while_expr) => {
+            visitor.visit_expr(&while_expr.condition);
+            visitor.visit_block(&while_expr.consequence);
+        }
+        Expr::Prefix(prefix) => visitor.visit_expr(&prefix.right),
+        Expr::Try(try_expr) => walk_try(visitor, try_expr),
+        Expr::Function(function) => visitor.visit_function(function),
+        Expr::Int(_)
+        | Expr::Bool(_)
+        | Expr::Nil(_)
+        | Expr::Float(_)
+        | Expr::String(_)
+        | Expr::Identifier(_) => {}
+    }
+}
+
+pub fn walk_if<V: Visitor + ?Sized>(visitor: &mut V, if_expr: &IfExpr) {
+    visitor.visit_expr(&if_expr.condition);
+    visitor.visit_block(&if_expr.consequence);
+    if let Some(el_if) = &if_expr.el_if {
+        walk_if(visitor, el_if);
+    } else if let Some(alternative) = &if_expr.alternative {
+        visitor.visit_block(alternative);
+    }
+}
+
+pub fn walk_try<V: Visitor + ?Sized>(visitor: &mut V, try_expr: &TryExpr) {
+    visitor.visit_block(&try_expr.body);
+    if let Some(catch) = &try_expr.catch {
+        visitor.visit_block(&catch.body);
+    }
+    if let Some(finally) = &try_expr.finally {
+        visitor.visit_block(finally);
+    }
+}
+
+pub fn walk_function<V: Visitor + ?Sized>(visitor: &mut V, function: &FnExpr) {
+    for default in function
+        .parameters
+        .iter()
+        .filter_map(|parameter| parameter.default.as_ref())
+    {
+        visitor.visit_expr(default);
+    }
+    visitor.visit_block(&function.body);
+}
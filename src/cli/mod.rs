@@ -0,0 +1,358 @@
+mod repl;
+mod test_runner;
+
+use std::{
+    cell::RefCell,
+    fs,
+    io::{self, Read},
+    rc::Rc,
+};
+
+use mila::{
+    ast::Program,
+    dump::{collect_tokens, dump_tokens, dump_tokens_json, dump_tree, dump_tree_json},
+    evaluator::{
+        environment::{Environment, EnvironmentRef},
+        global_built_ins,
+        objects::Value,
+        Evaluator, DEFAULT_MAX_DEPTH,
+    },
+    formatter::format_source,
+    lexer::Lexer,
+    parser::Parser,
+    resolver::resolve,
+    vm::Vm,
+};
+
+// the deepest recursion the interpreter thread stack holds, a call takes a few
+// kilobytes of it in debug builds and when built ins call back into the script
+const MAX_DEPTH: usize = crate::STACK_SIZE / (16 * 1024);
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 64;
+pub const EXIT_PARSE_ERROR: i32 = 65;
+pub const EXIT_NO_INPUT: i32 = 66;
+pub const EXIT_RUNTIME_ERROR: i32 = 70;
+
+const USAGE: &str = "usage: mila [command] [options] <file.mila | -> [args...]
+
+commands:
+    run      run a script (default command)
+    repl     start an interactive session, takes --vm and --max-depth
+    check    parse the given files or -e code and look for unknown words without running them
+    fmt      format the given files, use --check to only verify them
+    test     run the test blocks of *_test.mila files in the given paths,
+             --golden compares the output of scripts with their .out file
+             and --update rewrites them
+    tokens   print the tokens of a script
+    ast      print the syntax tree of a script
+
+options:
+    -e <code>  run the given code instead of a file
+    -          read the script from stdin
+    --json     print tokens or the syntax tree as json
+    --max-depth <n>
+               maximum depth of non tail recursive calls, default 2000,
+               larger values than 16384 are lowered to it
+    --vm       run the script on the bytecode virtual machine
+    -h, --help print this message
+
+exit codes:
+    64 usage error, 65 parse error, 66 input not found, 70 runtime error,
+    any other value comes from exit(n)";
+
+enum Input {
+    File(String),
+    Stdin,
+    Inline(String),
+}
+
+impl Input {
+    fn name(&self) -> String {
+        match self {
+            Self::File(file) => file.clone(),
+            Self::Stdin => "<stdin>".to_string(),
+            Self::Inline(_) => "<eval>".to_string(),
+        }
+    }
+
+    // files and stdin are lexed while they are read
+    fn lexer(&self) -> Result<Lexer, i32> {
+        let name = Rc::new(self.name());
+        match self {
+            Self::File(file) => match fs::File::open(file) {
+                Ok(file) if file.metadata().is_ok_and(|metadata| metadata.is_dir()) => {
+                    Err(self.open_error(io::ErrorKind::IsADirectory.into()))
+                }
+                Ok(file) => Ok(Lexer::from_reader(io::BufReader::new(file), name)),
+                Err(e) => Err(self.open_error(e)),
+            },
+            Self::Stdin => Ok(Lexer::from_reader(io::stdin().lock(), name)),
+            Self::Inline(code) => Ok(Lexer::new(code.clone(), name)),
+        }
+    }
+
+    fn open_error(&self, error: io::Error) -> i32 {
+        eprintln!("Error on openfile {}: {:?}", self.name(), error.kind());
+        EXIT_NO_INPUT
+    }
+
+    fn read(&self) -> Result<String, i32> {
+        let source = match self {
+            Self::File(file) => fs::read_to_string(file),
+            Self::Stdin => {
+                let mut buffer = String::new();
+                io::stdin().read_to_string(&mut buffer).map(|_| buffer)
+            }
+            Self::Inline(code) => Ok(code.clone()),
+        };
+        source.map_err(|e| self.open_error(e))
+    }
+}
+
+pub fn run(args: Vec<String>) -> i32 {
+    let (command, args) = match args.first().map(String::as_str) {
+        Some(command @ ("run" | "repl" | "check" | "fmt" | "test" | "tokens" | "ast")) => {
+            (command.to_string(), &args[1..])
+        }
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            return EXIT_SUCCESS;
+        }
+        None => ("repl".to_string(), &args[..]),
+        Some(_) => ("run".to_string(), &args[..]),
+    };
+    match &command[..] {
+        "repl" => match parse_options(args) {
+            Some((options, [])) => repl::start(&options),
+            _ => usage(),
+        },
+        "fmt" => fmt(args),
+        "check" => check(args),
+        "test" => test_runner::test(args),
+        "run" | "tokens" | "ast" => match parse_input(args) {
+            Some((options, input, script_args)) => match &command[..] {
+                "tokens" => tokens(&input, &options),
+                "ast" => ast(&input, &options),
+                _ => run_input(&input, &options, script_args),
+            },
+            None => usage(),
+        },
+        _ => usage(),
+    }
+}
+
+fn usage() -> i32 {
+    eprintln!("{}", USAGE);
+    EXIT_USAGE
+}
+
+struct Options {
+    json: bool,
+    vm: bool,
+    max_depth: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            json: false,
+            vm: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+fn parse_options(mut args: &[String]) -> Option<(Options, &[String])> {
+    let mut options = Options::default();
+    loop {
+        match args.first().map(String::as_str) {
+            Some("--json") => {
+                options.json = true;
+                args = &args[1..];
+            }
+            Some("--vm") => {
+                options.vm = true;
+                args = &args[1..];
+            }
+            Some("--max-depth") => {
+                options.max_depth = args.get(1)?.parse::<usize>().ok()?.min(MAX_DEPTH);
+                args = &args[2..];
+            }
+            _ => break,
+        }
+    }
+    Some((options, args))
+}
+
+fn parse_input(args: &[String]) -> Option<(Options, Input, &[String])> {
+    let (options, args) = parse_options(args)?;
+    let (input, script_args) = match args.first().map(String::as_str) {
+        Some("-e") => (Input::Inline(args.get(1)?.clone()), &args[2..]),
+        Some("-") => (Input::Stdin, &args[1..]),
+        Some(file) if !file.starts_with('-') => (Input::File(file.to_string()), &args[1..]),
+        _ => return None,
+    };
+    Some((options, input, script_args))
+}
+
+fn parse(input: &Input) -> Result<Program, i32> {
+    let mut parser = Parser::new(input.lexer()?);
+    let program = parser.parse_program();
+    if program.errors.is_empty() {
+        Ok(program)
+    } else {
+        for error in program.errors.iter() {
+            eprintln!("{}", error)
+        }
+        Err(EXIT_PARSE_ERROR)
+    }
+}
+
+pub fn global_environment(script_args: &[String]) -> EnvironmentRef {
+    let mut environment = Environment::default();
+    let args: Vec<Value> = script_args
+        .iter()
+        .map(|arg| Value::from(arg.clone()))
+        .collect();
+    environment.set_variable("args".to_string(), Value::array(args));
+    Rc::new(RefCell::new(environment))
+}
+
+fn run_input(input: &Input, options: &Options, script_args: &[String]) -> i32 {
+    let program = match parse(input) {
+        Ok(program) => program,
+        Err(code) => return code,
+    };
+    let result = engine(options)(&program, global_environment(script_args));
+    match result.as_error() {
+        Some(error) => {
+            eprintln!("{}", error.report());
+            EXIT_RUNTIME_ERROR
+        }
+        None => EXIT_SUCCESS,
+    }
+}
+
+type Engine = Box<dyn Fn(&Program, EnvironmentRef) -> Value>;
+
+// the tree walker, or the virtual machine with --vm
+fn engine(options: &Options) -> Engine {
+    if options.vm {
+        let vm = Vm::with_max_depth(options.max_depth);
+        Box::new(move |program, environment| vm.run(program, environment))
+    } else {
+        let evaluator = Evaluator::with_max_depth(options.max_depth);
+        Box::new(move |program, environment| evaluator.eval(program, environment))
+    }
+}
+
+// every input is read like the one of run, what follows it is the next input
+fn check(mut args: &[String]) -> i32 {
+    if args.is_empty() {
+        return usage();
+    }
+    let mut status = EXIT_SUCCESS;
+    while !args.is_empty() {
+        let Some((_, input, rest)) = parse_input(args) else {
+            return usage();
+        };
+        args = rest;
+        let program = match parse(&input) {
+            Ok(program) => program,
+            Err(code) => {
+                status = code;
+                continue;
+            }
+        };
+        let built_ins = global_built_ins();
+        let unresolved = resolve(&program, &global_environment(&[]).borrow());
+        for (name, location) in unresolved {
+            if !built_ins.contains_key(&name) {
+                eprintln!("unknown word '{}' in {}", name, location);
+                status = EXIT_PARSE_ERROR;
+            }
+        }
+    }
+    status
+}
+
+fn tokens(input: &Input, options: &Options) -> i32 {
+    let source = match input.read() {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
+    let tokens = collect_tokens(Lexer::with_trivia(source, Rc::new(input.name())));
+    if options.json {
+        println!("{}", dump_tokens_json(&tokens));
+    } else {
+        print!("{}", dump_tokens(&tokens));
+    }
+    EXIT_SUCCESS
+}
+
+fn ast(input: &Input, options: &Options) -> i32 {
+    match parse(input) {
+        Ok(program) if options.json => {
+            println!("{}", dump_tree_json(&program));
+            EXIT_SUCCESS
+        }
+        Ok(program) => {
+            print!("{}", dump_tree(&program));
+            EXIT_SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+fn fmt(args: &[String]) -> i32 {
+    let check = args.iter().any(|arg| arg == "--check");
+    let mut files: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--check")
+        .collect();
+    if files.is_empty() {
+        files.push("-");
+    }
+    let mut status = EXIT_SUCCESS;
+    for file in files {
+        let input = if file == "-" {
+            Input::Stdin
+        } else {
+            Input::File(file.to_string())
+        };
+        let source = match input.read() {
+            Ok(source) => source,
+            Err(code) => {
+                status = code;
+                continue;
+            }
+        };
+        let formatted = match format_source(source.clone(), Rc::new(input.name())) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}", error)
+                }
+                status = EXIT_PARSE_ERROR;
+                continue;
+            }
+        };
+        if check {
+            if formatted != source {
+                eprintln!("{} is not formatted", input.name());
+                status = EXIT_FAILURE;
+            }
+        } else if file == "-" {
+            print!("{}", formatted);
+        } else if formatted != source {
+            if let Err(e) = fs::write(file, formatted) {
+                eprintln!("Error on write {}: {:?}", file, e.kind());
+                status = EXIT_FAILURE;
+            }
+        }
+    }
+    status
+}
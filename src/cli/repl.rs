@@ -0,0 +1,86 @@
+use std::{
+    io::{self, Write},
+    rc::Rc,
+};
+
+use mila::{
+    ast::node::Stmt,
+    evaluator::objects::{Type, Value},
+    lexer::{Lexer, TokenType},
+    parser::Parser,
+};
+
+use super::{engine, global_environment, Options, EXIT_FAILURE, EXIT_SUCCESS};
+
+const PROMPT: &str = "mila> ";
+const CONTINUATION_PROMPT: &str = "...   ";
+
+pub fn start(options: &Options) -> i32 {
+    let run = engine(options);
+    let environment = global_environment(&[]);
+    let mut buffer = String::new();
+    loop {
+        if buffer.is_empty() {
+            print!("{}", PROMPT);
+        } else {
+            print!("{}", CONTINUATION_PROMPT);
+        }
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) => {
+                println!();
+                return EXIT_SUCCESS;
+            }
+            Ok(_) => buffer.push_str(&line),
+            Err(e) => {
+                eprintln!("Error on read input: {:?}", e.kind());
+                return EXIT_FAILURE;
+            }
+        }
+        if !is_complete(&buffer) {
+            continue;
+        }
+        let source = std::mem::take(&mut buffer);
+        if source.trim().is_empty() {
+            continue;
+        }
+        let lexer = Lexer::new(source, Rc::new("<repl>".to_string()));
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        if !program.errors.is_empty() {
+            for error in program.errors {
+                eprintln!("{}", error)
+            }
+            continue;
+        }
+        let is_expression = program
+            .statements
+            .last()
+            .is_some_and(|stmt| matches!(stmt, Stmt::Expression(_)));
+        let result = run(&program, Rc::clone(&environment));
+        match result {
+            Value::Error(error) => eprintln!("{}", error.report()),
+            Value::Nil => {}
+            result if is_expression && result.get_type() != Type::Function => {
+                println!("{}", result)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_complete(source: &str) -> bool {
+    let mut lexer = Lexer::new(source.to_string(), Rc::new("<repl>".to_string()));
+    let mut depth = 0;
+    loop {
+        let token = lexer.next_token();
+        match token.token_type {
+            TokenType::LBrace | TokenType::LParen | TokenType::LBracket => depth += 1,
+            TokenType::RBrace | TokenType::RParen | TokenType::RBracket => depth -= 1,
+            TokenType::Illegal if lexer.text(token.span).starts_with('"') => return false,
+            TokenType::Eof => return depth <= 0,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use mila::{
+    ast::{Program, Stmt},
+    evaluator::Evaluator,
+    vm::Vm,
+};
+
+use super::{global_environment, parse, usage, Input, EXIT_FAILURE, EXIT_SUCCESS};
+
+#[derive(Default)]
+struct TestOptions {
+    vm: bool,
+    golden: bool,
+    update: bool,
+}
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+}
+
+impl Summary {
+    fn record(&mut self, name: &str, failure: Option<String>) {
+        match failure {
+            Some(failure) => {
+                self.failed += 1;
+                println!("FAIL {}", name);
+                for line in failure.lines() {
+                    println!("    {}", line);
+                }
+            }
+            None => {
+                self.passed += 1;
+                println!("ok   {}", name);
+            }
+        }
+    }
+}
+
+pub fn test(args: &[String]) -> i32 {
+    let mut options = TestOptions::default();
+    let mut paths = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--vm" => options.vm = true,
+            "--golden" => options.golden = true,
+            "--update" => options.update = true,
+            arg if arg.starts_with('-') => return usage(),
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if options.update && !options.golden {
+        return usage();
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+    let suffix = if options.golden {
+        ".mila"
+    } else {
+        "_test.mila"
+    };
+    let mut files = vec![];
+    for path in paths {
+        collect_files(&path, suffix, &mut files);
+    }
+    let mut summary = Summary::default();
+    for file in files {
+        if options.golden {
+            golden(&file, &options, &mut summary);
+        } else {
+            run_tests(&file, &options, &mut summary);
+        }
+    }
+    println!("\n{} passed, {} failed", summary.passed, summary.failed);
+    if summary.failed > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_SUCCESS
+    }
+}
+
+// a file given by name is always used, directories are walked for the suffix
+fn collect_files(path: &Path, suffix: &str, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    let mut entries: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+        Err(_) => return,
+    };
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_files(&entry, suffix, files);
+        } else if entry.to_string_lossy().ends_with(suffix) {
+            files.push(entry);
+        }
+    }
+}
+
+// every test block runs on its own with the rest of the file around it,
+// a file without test blocks is a single test
+fn run_tests(file: &Path, options: &TestOptions, summary: &mut Summary) {
+    let name = file.to_string_lossy().to_string();
+    let program = match parse(&Input::File(name.clone())) {
+        Ok(program) => program,
+        Err(_) => {
+            summary.record(&name, Some("parse error".to_string()));
+            return;
+        }
+    };
+    let (tests, rest): (Vec<Stmt>, Vec<Stmt>) = program
+        .statements
+        .into_iter()
+        .partition(|stmt| matches!(stmt, Stmt::Test(_)));
+    if tests.is_empty() {
+        let program = Program {
+            statements: rest,
+            ..Program::default()
+        };
+        summary.record(&name, run_program(&program, options));
+        return;
+    }
+    for test in tests {
+        if let Stmt::Test(test) = test {
+            let mut statements = rest.clone();
+            statements.extend(test.body.statements.iter().cloned());
+            let program = Program {
+                statements,
+                ..Program::default()
+            };
+            let name = format!("{} > {}", name, test.name);
+            summary.record(&name, run_program(&program, options));
+        }
+    }
+}
+
+fn run_program(program: &Program, options: &TestOptions) -> Option<String> {
+    let environment = global_environment(&[]);
+    let result = if options.vm {
+        Vm::default().run(program, environment)
+    } else {
+        Evaluator::default().eval(program, environment)
+    };
+    result.as_error().map(|error| error.report())
+}
+
+// scripts with a .out file next to them must print exactly its content,
+// they run in their own process from their directory to keep relative paths.
+// --update only rewrites existing files, an empty .out file opts a script in
+fn golden(file: &Path, options: &TestOptions, summary: &mut Summary) {
+    let name = file.to_string_lossy().to_string();
+    let expected_file = file.with_extension("out");
+    if !expected_file.exists() {
+        return;
+    }
+    let failure = match golden_output(file, options) {
+        Ok(output) if options.update => fs::write(&expected_file, output)
+            .err()
+            .map(|e| format!("Error on write {}: {:?}", expected_file.display(), e.kind())),
+        Ok(output) => match fs::read_to_string(&expected_file) {
+            Ok(expected) if expected == output => None,
+            Ok(expected) => Some(diff(&expected, &output)),
+            Err(e) => Some(format!(
+                "Error on openfile {}: {:?}",
+                expected_file.display(),
+                e.kind()
+            )),
+        },
+        Err(failure) => Some(failure),
+    };
+    summary.record(&name, failure);
+}
+
+fn golden_output(file: &Path, options: &TestOptions) -> Result<String, String> {
+    let executable = env::current_exe().map_err(|e| e.to_string())?;
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let script = file.file_name().ok_or_else(|| "not a file".to_string())?;
+    let mut command = Command::new(executable);
+    command.arg("run");
+    if options.vm {
+        command.arg("--vm");
+    }
+    let output = command
+        .arg(script)
+        .current_dir(directory)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr)
+            .trim_end()
+            .to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// the first line that differs is enough to find the problem
+fn diff(expected: &str, output: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let output_lines: Vec<&str> = output.lines().collect();
+    let line = expected_lines
+        .iter()
+        .zip(output_lines.iter())
+        .position(|(expected, output)| expected != output)
+        .unwrap_or_else(|| expected_lines.len().min(output_lines.len()));
+    let show = |lines: &[&str]| {
+        lines
+            .get(line)
+            .map_or("<end of output>".to_string(), |line| format!("{:?}", line))
+    };
+    format!(
+        "output differs at line {}\nexpected: {}\n     got: {}",
+        line + 1,
+        show(&expected_lines),
+        show(&output_lines)
+    )
+}
@@ -0,0 +1,92 @@
+use std::rc::Rc;
+
+use crate::{
+    ast::node::{BlockStatement, ParameterExpr},
+    evaluator::{
+        objects::Value,
+        operations::{BinaryOperator, PrefixOperator},
+    },
+    lexer::Location,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Constant(usize),
+    Nothing,
+    Pop,
+    GetLocal(usize, usize, usize),
+    SetLocal(usize, usize),
+    GetGlobal(usize),
+    SetGlobal(usize),
+    Binary(BinaryOperator),
+    Prefix(PrefixOperator),
+    Jump(usize),
+    Branch(usize, usize),
+    JumpUnless(usize),
+    Array(usize),
+    Hash(usize),
+    Index,
+    Closure(usize),
+    Call(usize),
+    TailCall(usize),
+    LookupMethod(usize),
+    CallMethod(usize),
+    Member(usize),
+    NotFunction,
+    Default(usize, usize),
+    Import(usize),
+    Try(usize),
+    EndTry,
+    Catch,
+    Throw,
+    Return,
+    End,
+}
+
+pub struct CallSite {
+    pub arguments: usize,
+    pub location: Location,
+}
+
+pub struct Import {
+    pub path: String,
+    pub location: Location,
+}
+
+#[derive(Default)]
+pub struct Chunk {
+    pub code: Vec<Op>,
+    pub locations: Vec<Option<Location>>,
+    pub constants: Vec<Value>,
+    pub names: Vec<String>,
+    pub hashes: Vec<Vec<String>>,
+    pub calls: Vec<CallSite>,
+    pub functions: Vec<Rc<Proto>>,
+    pub imports: Vec<Import>,
+    pub slots: usize,
+}
+
+impl Chunk {
+    pub fn location(&self, ip: usize) -> Option<&Location> {
+        self.locations.get(ip).and_then(Option::as_ref)
+    }
+}
+
+pub struct Proto {
+    pub name: Option<String>,
+    pub body: Rc<BlockStatement>,
+    pub parameters: Rc<Vec<ParameterExpr>>,
+    pub required: usize,
+    pub positional: usize,
+    pub rest: bool,
+    pub chunk: Rc<Chunk>,
+}
+
+impl Proto {
+    pub fn describe(&self) -> String {
+        match self.name {
+            Some(ref name) => format!("function {}", name),
+            None => "anonymous function".to_string(),
+        }
+    }
+}
@@ -0,0 +1,466 @@
+mod chunk;
+
+pub use chunk::*;
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    ast::{
+        node::{
+            BlockStatement, CallExpr, Expr, FnExpr, IdentifierExpr, IfExpr, InfixExpr, Stmt,
+            TryExpr,
+        },
+        Program,
+    },
+    evaluator::{
+        environment::Environment,
+        objects::Value,
+        operations::{BinaryOperator, PrefixOperator},
+    },
+    lexer::Location,
+    resolver::{resolve, Slot},
+};
+
+pub fn compile(program: &Program, environment: &Environment) -> Rc<Chunk> {
+    resolve(program, environment);
+    let mut compiler = Compiler {
+        functions: vec![FunctionState::new(true)],
+    };
+    compiler.current().chunk.slots = program.slots.get();
+    compiler.statements(&program.statements);
+    compiler.emit(Op::End);
+    Rc::new(compiler.functions.pop().unwrap().chunk)
+}
+
+struct FunctionState {
+    chunk: Chunk,
+    // locations given to errors raised by the operations emitted under them
+    contexts: Vec<Location>,
+    names: HashMap<String, usize>,
+    script: bool,
+    // error handlers installed at this point of the function
+    handlers: usize,
+    // finally blocks a ret has to run before leaving the function
+    finallies: Vec<Finally>,
+}
+
+#[derive(Clone)]
+struct Finally {
+    // handlers installed outside of the try
+    handlers: usize,
+    body: BlockStatement,
+}
+
+impl FunctionState {
+    fn new(script: bool) -> Self {
+        Self {
+            chunk: Chunk::default(),
+            contexts: Vec::new(),
+            names: HashMap::new(),
+            script,
+            handlers: 0,
+            finallies: Vec::new(),
+        }
+    }
+}
+
+struct Compiler {
+    functions: Vec<FunctionState>,
+}
+
+impl Compiler {
+    fn current(&mut self) -> &mut FunctionState {
+        self.functions.last_mut().unwrap()
+    }
+
+    fn emit(&mut self, op: Op) -> usize {
+        let function = self.current();
+        let location = function.contexts.last().cloned();
+        function.chunk.code.push(op);
+        function.chunk.locations.push(location);
+        function.chunk.code.len() - 1
+    }
+
+    fn here(&mut self) -> usize {
+        self.current().chunk.code.len()
+    }
+
+    fn patch(&mut self, at: usize, op: Op) {
+        self.current().chunk.code[at] = op;
+    }
+
+    fn located(&mut self, location: &Location, compile: impl FnOnce(&mut Self)) {
+        self.current().contexts.push(location.clone());
+        compile(self);
+        self.current().contexts.pop();
+    }
+
+    fn constant(&mut self, value: Value) {
+        let constants = &mut self.current().chunk.constants;
+        constants.push(value);
+        let index = constants.len() - 1;
+        self.emit(Op::Constant(index));
+    }
+
+    fn name(&mut self, name: &str) -> usize {
+        let function = self.current();
+        if let Some(index) = function.names.get(name) {
+            return *index;
+        }
+        function.chunk.names.push(name.to_string());
+        let index = function.chunk.names.len() - 1;
+        function.names.insert(name.to_string(), index);
+        index
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let(let_stmt) => {
+                self.expr(&let_stmt.value);
+                self.assign(&let_stmt.name);
+            }
+            Stmt::Var(var_stmt) => {
+                self.expr(&var_stmt.value);
+                self.located(&var_stmt.name.location, |compiler| {
+                    compiler.emit(Op::NotFunction);
+                });
+                self.assign(&var_stmt.name);
+            }
+            Stmt::Expression(expr) => self.expr(&expr.expression),
+            Stmt::Export(export) => self.stmt(&export.statement),
+            Stmt::Import(import) => {
+                let imports = &mut self.current().chunk.imports;
+                imports.push(Import {
+                    path: import.path.clone(),
+                    location: import.location.clone(),
+                });
+                let index = imports.len() - 1;
+                self.emit(Op::Import(index));
+                self.store(import.slot.get(), &import.name());
+            }
+            Stmt::Ret(ret) => {
+                let function = self.current();
+                let tail =
+                    !function.script && function.handlers == 0 && function.finallies.is_empty();
+                match &ret.value {
+                    Some(Expr::Call(call)) if tail => {
+                        self.call(call, true);
+                        return;
+                    }
+                    Some(value) => self.expr(value),
+                    None => {
+                        self.emit(Op::Nothing);
+                    }
+                }
+                self.finallies();
+                self.emit(Op::Return);
+            }
+            Stmt::Throw(throw) => {
+                self.expr(&throw.value);
+                self.located(&throw.location, |compiler| {
+                    compiler.emit(Op::Throw);
+                });
+            }
+            Stmt::Trivia(_) | Stmt::Test(_) => {
+                self.emit(Op::Nothing);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Int(int) => self.constant(Value::Int(int.value)),
+            Expr::Bool(boolean) => self.constant(Value::Bool(boolean.value)),
+            Expr::Float(float) => self.constant(Value::Float(float.value)),
+            Expr::String(string) => self.constant(Value::from(string.value.clone())),
+            Expr::Nil(_) => {
+                self.emit(Op::Nothing);
+            }
+            Expr::Identifier(identifier) => {
+                let name = self.name(&identifier.value);
+                let op = match identifier.slot.get() {
+                    Slot::Local(depth, index) => Op::GetLocal(depth, index, name),
+                    Slot::Global => Op::GetGlobal(name),
+                };
+                self.located(&identifier.location, |compiler| {
+                    compiler.emit(op);
+                });
+            }
+            Expr::Prefix(prefix) => {
+                self.expr(&prefix.right);
+                let operator = PrefixOperator::from_operator(prefix.operator)
+                    .expect("prefix operators are checked by the parser");
+                self.located(&prefix.location, |compiler| {
+                    compiler.emit(Op::Prefix(operator));
+                });
+            }
+            Expr::Infix(infix) => self.infix(infix),
+            Expr::Call(call) => self.call(call, false),
+            Expr::Array(array) => {
+                for value in array.values.iter() {
+                    self.expr(value);
+                }
+                self.emit(Op::Array(array.values.len()));
+            }
+            Expr::Hash(hash) => {
+                for key in hash.keys.iter() {
+                    self.expr(&hash.pairs[key]);
+                }
+                let hashes = &mut self.current().chunk.hashes;
+                hashes.push(hash.keys.clone());
+                let index = hashes.len() - 1;
+                self.emit(Op::Hash(index));
+            }
+            Expr::Index(index) => {
+                self.expr(&index.left);
+                self.expr(&index.index);
+                self.located(&index.location, |compiler| {
+                    compiler.emit(Op::Index);
+                });
+            }
+            Expr::If(if_expr) => self.if_expr(if_expr),
+            Expr::Try(try_expr) => self.try_expr(try_expr),
+            Expr::While(while_expr) => {
+                self.emit(Op::Nothing);
+                let start = self.here();
+                self.expr(&while_expr.condition);
+                let exit = self.emit(Op::JumpUnless(0));
+                self.emit(Op::Pop);
+                self.statements(&while_expr.consequence.statements);
+                self.emit(Op::Jump(start));
+                let end = self.here();
+                self.patch(exit, Op::JumpUnless(end));
+            }
+            Expr::Function(function) => self.function(function),
+        }
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) {
+        let statements: Vec<&Stmt> = statements
+            .iter()
+            .filter(|stmt| !matches!(stmt, Stmt::Trivia(_)))
+            .collect();
+        if statements.is_empty() {
+            self.emit(Op::Nothing);
+        }
+        for (i, stmt) in statements.iter().enumerate() {
+            if i > 0 {
+                self.emit(Op::Pop);
+            }
+            self.stmt(stmt);
+        }
+    }
+
+    fn if_expr(&mut self, if_expr: &IfExpr) {
+        self.expr(&if_expr.condition);
+        let branch = self.emit(Op::Branch(0, 0));
+        self.statements(&if_expr.consequence.statements);
+        let jump = self.emit(Op::Jump(0));
+        let otherwise = self.here();
+        match (&if_expr.el_if, &if_expr.alternative) {
+            (Some(el_if), _) => self.if_expr(el_if),
+            (None, Some(alternative)) => self.statements(&alternative.statements),
+            (None, None) => {
+                self.emit(Op::Nothing);
+            }
+        }
+        let end = self.here();
+        self.patch(branch, Op::Branch(otherwise, end));
+        self.patch(jump, Op::Jump(end));
+    }
+
+    // the try body runs under a handler that jumps to the catch block, with
+    // a finally block the catch runs under another one that jumps to a copy
+    // of the finally block which throws the error again
+    fn try_expr(&mut self, try_expr: &TryExpr) {
+        let outer = self.current().handlers;
+        if let Some(finally) = &try_expr.finally {
+            self.current().finallies.push(Finally {
+                handlers: outer,
+                body: finally.clone(),
+            });
+        }
+        let handler = self.protect();
+        self.statements(&try_expr.body.statements);
+        self.unprotect();
+        let mut exits = vec![self.emit(Op::Jump(0))];
+        let mut failed = self.here();
+        if let Some(catch) = &try_expr.catch {
+            self.patch(handler, Op::Try(failed));
+            let rethrow = try_expr.finally.is_some().then(|| self.protect());
+            self.emit(Op::Catch);
+            self.assign(&catch.name);
+            self.emit(Op::Pop);
+            self.statements(&catch.body.statements);
+            if rethrow.is_some() {
+                self.unprotect();
+            }
+            exits.push(self.emit(Op::Jump(0)));
+            failed = self.here();
+            if let Some(rethrow) = rethrow {
+                self.patch(rethrow, Op::Try(failed));
+            }
+        } else {
+            self.patch(handler, Op::Try(failed));
+        }
+        if let Some(finally) = &try_expr.finally {
+            self.current().finallies.pop();
+            self.statements(&finally.statements);
+            self.emit(Op::Pop);
+            self.emit(Op::Throw);
+        }
+        let end = self.here();
+        for exit in exits {
+            self.patch(exit, Op::Jump(end));
+        }
+        if let Some(finally) = &try_expr.finally {
+            self.statements(&finally.statements);
+            self.emit(Op::Pop);
+        }
+    }
+
+    fn protect(&mut self) -> usize {
+        self.current().handlers += 1;
+        self.emit(Op::Try(0))
+    }
+
+    fn unprotect(&mut self) {
+        self.current().handlers -= 1;
+        self.emit(Op::EndTry);
+    }
+
+    // a ret runs the finally blocks it is inside of, innermost first, each
+    // one without the handlers of its own try
+    fn finallies(&mut self) {
+        let handlers = self.current().handlers;
+        let finallies = std::mem::take(&mut self.current().finallies);
+        for (index, finally) in finallies.iter().enumerate().rev() {
+            while self.current().handlers > finally.handlers {
+                self.unprotect();
+            }
+            self.current().finallies = finallies[..index].to_vec();
+            self.statements(&finally.body.statements);
+            self.emit(Op::Pop);
+        }
+        let function = self.current();
+        function.handlers = handlers;
+        function.finallies = finallies;
+    }
+
+    fn infix(&mut self, infix: &InfixExpr) {
+        self.expr(&infix.left);
+        match &*infix.right {
+            Expr::Identifier(member) if infix.operator == "." => {
+                let member = self.name(&member.value);
+                self.located(&infix.location, |compiler| {
+                    compiler.emit(Op::Member(member));
+                });
+            }
+            Expr::Call(call) if infix.operator == "." => {
+                let name = self.name(&call.function.to_string());
+                let site = self.call_site(call);
+                self.located(&call.location, |compiler| {
+                    compiler.emit(Op::LookupMethod(name));
+                });
+                self.arguments(call);
+                self.located(&call.location, |compiler| {
+                    compiler.emit(Op::CallMethod(site));
+                });
+            }
+            _ => {
+                self.expr(&infix.right);
+                let operator = BinaryOperator::from_operator(infix.operator)
+                    .expect("infix operators are checked by the parser");
+                self.located(&infix.location, |compiler| {
+                    compiler.emit(Op::Binary(operator));
+                });
+            }
+        }
+    }
+
+    fn call(&mut self, call: &CallExpr, tail: bool) {
+        self.expr(&call.function);
+        self.arguments(call);
+        let site = self.call_site(call);
+        let op = if tail {
+            Op::TailCall(site)
+        } else {
+            Op::Call(site)
+        };
+        self.located(&call.location, |compiler| {
+            compiler.emit(op);
+        });
+    }
+
+    fn arguments(&mut self, call: &CallExpr) {
+        for (argument, location) in call.arguments.iter().zip(&call.locations) {
+            self.located(location, |compiler| compiler.expr(argument));
+        }
+    }
+
+    fn call_site(&mut self, call: &CallExpr) -> usize {
+        let calls = &mut self.current().chunk.calls;
+        calls.push(CallSite {
+            arguments: call.arguments.len(),
+            location: call.location.clone(),
+        });
+        calls.len() - 1
+    }
+
+    fn function(&mut self, function: &FnExpr) {
+        let parameters = &function.parameters;
+        let rest = parameters.last().is_some_and(|parameter| parameter.rest);
+        let positional = parameters.len() - rest as usize;
+        let required = parameters
+            .iter()
+            .filter(|parameter| parameter.default.is_none() && !parameter.rest)
+            .count();
+
+        let mut state = FunctionState::new(false);
+        state.chunk.slots = function.slots.get();
+        self.functions.push(state);
+
+        for (slot, parameter) in parameters.iter().enumerate() {
+            if let Some(default) = &parameter.default {
+                let skip = self.emit(Op::Default(slot, 0));
+                self.expr(default);
+                self.emit(Op::SetLocal(0, slot));
+                self.emit(Op::Pop);
+                let end = self.here();
+                self.patch(skip, Op::Default(slot, end));
+            }
+        }
+        self.statements(&function.body.statements);
+        self.emit(Op::Return);
+        let state = self.functions.pop().unwrap();
+
+        let proto = Proto {
+            name: function.name.as_ref().map(|name| name.to_string()),
+            body: Rc::clone(&function.body),
+            parameters: Rc::clone(&function.parameters),
+            required,
+            positional,
+            rest,
+            chunk: Rc::new(state.chunk),
+        };
+        let functions = &mut self.current().chunk.functions;
+        functions.push(Rc::new(proto));
+        let index = functions.len() - 1;
+        self.emit(Op::Closure(index));
+        if let Some(name) = &function.name {
+            self.assign(name);
+        }
+    }
+
+    fn assign(&mut self, name: &IdentifierExpr) {
+        self.store(name.slot.get(), &name.value);
+    }
+
+    fn store(&mut self, slot: Slot, name: &str) {
+        let op = match slot {
+            Slot::Local(depth, index) => Op::SetLocal(depth, index),
+            Slot::Global => Op::SetGlobal(self.name(name)),
+        };
+        self.emit(op);
+    }
+}
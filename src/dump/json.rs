@@ -0,0 +1,17 @@
+pub(crate) fn json_string(value: &str) -> String {
+    let mut buffer = String::with_capacity(value.len() + 2);
+    buffer.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => buffer.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => buffer.push(ch),
+        }
+    }
+    buffer.push('"');
+    buffer
+}
@@ -0,0 +1,203 @@
+mod json;
+mod tokens;
+mod tree;
+
+pub use tokens::*;
+pub use tree::*;
+
+use crate::ast::{
+    node::{
+        BlockStatement, CatchClause, Expr, IdentifierExpr, IfExpr, OpCode, ParameterExpr, Stmt,
+        Trivia,
+    },
+    Program,
+};
+
+// any node of the tree that can be dumped on its own
+#[derive(Clone, Copy)]
+pub(crate) enum Tree<'a> {
+    Program(&'a Program),
+    Block(&'a BlockStatement),
+    Stmt(&'a Stmt),
+    Expr(&'a Expr),
+    If(&'a IfExpr),
+    Catch(&'a CatchClause),
+    Name(&'a IdentifierExpr),
+    Parameter(&'a ParameterExpr),
+}
+
+pub(crate) enum Field<'a> {
+    Node(Tree<'a>),
+    List(Vec<Tree<'a>>),
+    Pairs(Vec<(&'a str, Tree<'a>)>),
+}
+
+pub(crate) struct NodeInfo<'a> {
+    pub op_code: OpCode,
+    pub value: Option<String>,
+    pub fields: Vec<(&'static str, Field<'a>)>,
+}
+
+fn statements(statements: &[Stmt]) -> Field<'_> {
+    Field::List(statements.iter().map(Tree::Stmt).collect())
+}
+
+fn expressions(expressions: &[Expr]) -> Field<'_> {
+    Field::List(expressions.iter().map(Tree::Expr).collect())
+}
+
+pub(crate) fn describe(tree: Tree<'_>) -> NodeInfo<'_> {
+    let mut value = None;
+    let mut fields = Vec::new();
+    let op_code = match tree {
+        Tree::Program(program) => {
+            fields.push(("statements", statements(&program.statements)));
+            OpCode::Program
+        }
+        Tree::Block(block) => {
+            fields.push(("statements", statements(&block.statements)));
+            OpCode::Block
+        }
+        Tree::Name(name) => {
+            value = Some(name.value.clone());
+            OpCode::Identifier
+        }
+        Tree::Parameter(parameter) => {
+            value = Some(match parameter.rest {
+                true => format!("...{}", parameter.name),
+                false => parameter.name.clone(),
+            });
+            if let Some(ref default) = parameter.default {
+                fields.push(("default", Field::Node(Tree::Expr(default))));
+            }
+            OpCode::Parameter
+        }
+        Tree::If(if_expr) => {
+            fields.push(("condition", Field::Node(Tree::Expr(&if_expr.condition))));
+            fields.push((
+                "consequence",
+                Field::Node(Tree::Block(&if_expr.consequence)),
+            ));
+            if let Some(ref el_if) = if_expr.el_if {
+                fields.push(("else_if", Field::Node(Tree::If(el_if))));
+            }
+            if let Some(ref alternative) = if_expr.alternative {
+                fields.push(("alternative", Field::Node(Tree::Block(alternative))));
+            }
+            OpCode::If
+        }
+        Tree::Catch(catch) => {
+            value = Some(catch.name.value.clone());
+            fields.push(("body", Field::Node(Tree::Block(&catch.body))));
+            OpCode::Catch
+        }
+        Tree::Stmt(stmt) => {
+            match stmt {
+                Stmt::Expression(stmt) => {
+                    fields.push(("expression", Field::Node(Tree::Expr(&stmt.expression))));
+                }
+                Stmt::Let(stmt) => {
+                    fields.push(("name", Field::Node(Tree::Name(&stmt.name))));
+                    fields.push(("value", Field::Node(Tree::Expr(&stmt.value))));
+                }
+                Stmt::Var(stmt) => {
+                    fields.push(("name", Field::Node(Tree::Name(&stmt.name))));
+                    fields.push(("value", Field::Node(Tree::Expr(&stmt.value))));
+                }
+                Stmt::Ret(stmt) => {
+                    if let Some(ref ret) = stmt.value {
+                        fields.push(("value", Field::Node(Tree::Expr(ret))));
+                    }
+                }
+                Stmt::Trivia(trivia) => {
+                    value = match trivia {
+                        Trivia::BlankLine => None,
+                        comment => Some(comment.to_string()),
+                    };
+                }
+                Stmt::Import(import) => {
+                    value = Some(match import.alias {
+                        Some(ref alias) => format!("{} as {}", import.path, alias),
+                        None => import.path.clone(),
+                    });
+                }
+                Stmt::Throw(stmt) => {
+                    fields.push(("value", Field::Node(Tree::Expr(&stmt.value))));
+                }
+                Stmt::Test(test) => {
+                    value = Some(test.name.clone());
+                    fields.push(("body", Field::Node(Tree::Block(&test.body))));
+                }
+                Stmt::Export(export) => {
+                    fields.push(("statement", Field::Node(Tree::Stmt(&export.statement))));
+                }
+            }
+            stmt.op_code()
+        }
+        Tree::Expr(expr) => {
+            match expr {
+                Expr::Int(int) => value = Some(int.value.to_string()),
+                Expr::Bool(boolean) => value = Some(boolean.value.to_string()),
+                Expr::Nil(_) => {}
+                Expr::Float(float) => value = Some(float.to_string()),
+                Expr::String(string) => value = Some(string.value.clone()),
+                Expr::Identifier(identifier) => value = Some(identifier.value.clone()),
+                Expr::Prefix(prefix) => {
+                    value = Some(prefix.operator.to_string());
+                    fields.push(("right", Field::Node(Tree::Expr(&prefix.right))));
+                }
+                Expr::Infix(infix) => {
+                    value = Some(infix.operator.to_string());
+                    fields.push(("left", Field::Node(Tree::Expr(&infix.left))));
+                    fields.push(("right", Field::Node(Tree::Expr(&infix.right))));
+                }
+                Expr::Call(call) => {
+                    fields.push(("function", Field::Node(Tree::Expr(&call.function))));
+                    fields.push(("arguments", expressions(&call.arguments)));
+                }
+                Expr::Index(index) => {
+                    fields.push(("left", Field::Node(Tree::Expr(&index.left))));
+                    fields.push(("index", Field::Node(Tree::Expr(&index.index))));
+                }
+                Expr::Array(array) => fields.push(("values", expressions(&array.values))),
+                Expr::Hash(hash) => {
+                    let pairs = hash
+                        .keys
+                        .iter()
+                        .map(|key| (key.as_str(), Tree::Expr(&hash.pairs[key])))
+                        .collect();
+                    fields.push(("pairs", Field::Pairs(pairs)));
+                }
+                Expr::If(if_expr) => return describe(Tree::If(if_expr)),
+                Expr::While(while_expr) => {
+                    fields.push(("condition", Field::Node(Tree::Expr(&while_expr.condition))));
+                    fields.push((
+                        "consequence",
+                        Field::Node(Tree::Block(&while_expr.consequence)),
+                    ));
+                }
+                Expr::Try(try_expr) => {
+                    fields.push(("body", Field::Node(Tree::Block(&try_expr.body))));
+                    if let Some(ref catch) = try_expr.catch {
+                        fields.push(("catch", Field::Node(Tree::Catch(catch))));
+                    }
+                    if let Some(ref finally) = try_expr.finally {
+                        fields.push(("finally", Field::Node(Tree::Block(finally))));
+                    }
+                }
+                Expr::Function(function) => {
+                    value = function.name.as_ref().map(|name| name.to_string());
+                    let parameters = function.parameters.iter().map(Tree::Parameter).collect();
+                    fields.push(("parameters", Field::List(parameters)));
+                    fields.push(("body", Field::Node(Tree::Block(&function.body))));
+                }
+            }
+            expr.op_code()
+        }
+    };
+    NodeInfo {
+        op_code,
+        value,
+        fields,
+    }
+}
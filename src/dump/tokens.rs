@@ -0,0 +1,68 @@
+use crate::lexer::{Lexer, Token, TokenType};
+
+use super::json::json_string;
+
+// the tokens of a whole input with the lexer that resolves their values
+pub struct Tokens {
+    lexer: Lexer,
+    tokens: Vec<Token>,
+}
+
+impl Tokens {
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Token, &str)> {
+        self.tokens
+            .iter()
+            .map(|token| (token, self.lexer.text(token.span)))
+    }
+}
+
+pub fn collect_tokens(mut lexer: Lexer) -> Tokens {
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token();
+        let is_eof = token.token_type == TokenType::Eof;
+        tokens.push(token);
+        if is_eof {
+            break;
+        }
+    }
+    Tokens { lexer, tokens }
+}
+
+pub fn dump_tokens(tokens: &Tokens) -> String {
+    let mut buffer = String::new();
+    for (token, value) in tokens.iter() {
+        let location = token.location.to_string();
+        let token_type = format!("{:?}", token.token_type);
+        buffer.push_str(&format!(
+            "{:<24} {:<20} {:?}\n",
+            location, token_type, value
+        ));
+    }
+    buffer
+}
+
+pub fn dump_tokens_json(tokens: &Tokens) -> String {
+    let tokens: Vec<String> = tokens
+        .iter()
+        .map(|(token, value)| {
+            format!(
+                "{{\"type\":{},\"value\":{},\"file\":{},\"line\":{},\"column\":{}}}",
+                json_string(&format!("{:?}", token.token_type)),
+                json_string(value),
+                json_string(&token.location.file().name()),
+                token.location.line(),
+                token.location.column()
+            )
+        })
+        .collect();
+    format!("[{}]", tokens.join(","))
+}
@@ -0,0 +1,73 @@
+use crate::ast::Program;
+
+use super::{describe, json::json_string, Field, Tree};
+
+pub fn dump_tree(program: &Program) -> String {
+    let mut buffer = String::new();
+    write_tree(&mut buffer, Tree::Program(program), None, 0);
+    buffer
+}
+
+fn write_tree(buffer: &mut String, node: Tree<'_>, label: Option<String>, depth: usize) {
+    let info = describe(node);
+    buffer.push_str(&"  ".repeat(depth));
+    if let Some(label) = label {
+        buffer.push_str(&label);
+        buffer.push_str(": ");
+    }
+    buffer.push_str(&format!("{:?}", info.op_code));
+    if let Some(value) = info.value {
+        buffer.push_str(&format!(" {:?}", value));
+    }
+    buffer.push('\n');
+    for (name, field) in info.fields {
+        match field {
+            Field::Node(child) => write_tree(buffer, child, Some(name.to_string()), depth + 1),
+            Field::List(children) => {
+                for (idx, child) in children.into_iter().enumerate() {
+                    let label = format!("{}[{}]", name, idx);
+                    write_tree(buffer, child, Some(label), depth + 1)
+                }
+            }
+            Field::Pairs(pairs) => {
+                for (key, child) in pairs {
+                    let label = format!("{}[{:?}]", name, key);
+                    write_tree(buffer, child, Some(label), depth + 1)
+                }
+            }
+        }
+    }
+}
+
+pub fn dump_tree_json(program: &Program) -> String {
+    json_tree(Tree::Program(program))
+}
+
+fn json_tree(node: Tree<'_>) -> String {
+    let info = describe(node);
+    let mut members = vec![format!(
+        "\"op\":{}",
+        json_string(&format!("{:?}", info.op_code))
+    )];
+    if let Some(value) = info.value {
+        members.push(format!("\"text\":{}", json_string(&value)));
+    }
+    for (name, field) in info.fields {
+        let value = match field {
+            Field::Node(child) => json_tree(child),
+            Field::List(children) => {
+                let children: Vec<String> = children.into_iter().map(json_tree).collect();
+                format!("[{}]", children.join(","))
+            }
+            Field::Pairs(pairs) => {
+                let pairs: Vec<String> = pairs
+                    .into_iter()
+                    .map(|(key, child)| format!("{}:{}", json_string(key), json_tree(child)))
+                    .collect();
+                format!("{{{}}}", pairs.join(","))
+            }
+        };
+        members.push(format!("{}:{}", json_string(name), value));
+    }
+    format!("{{{}}}", members.join(","))
+}
@@ -2,167 +2,251 @@ use std::{
     fs,
     io::{self, Write},
     process,
-    rc::Rc,
 };
 
-use crate::{downcast_any, evaluator::objects::Str};
-
-use super::objects::{Array, EvalError, Float, Integer, ObjectRef, Type};
+use super::{
+    objects::{Caller, ErrorKind, FileHandle, Type, Value},
+    operations,
+};
 
-pub(super) fn len(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new("expected only one argument".to_string()));
-    }
-    let first = args.first().unwrap();
-    match first.get_type() {
-        Type::Array => {
-            let arr_sz = downcast_any!(first => Array).values.borrow().len();
-            Rc::new(Integer::new(arr_sz as isize))
-        }
-        Type::String => {
-            let str_sz = downcast_any!(first => Str).value.len();
-            Rc::new(Integer::new(str_sz as isize))
-        }
-        typ => Rc::new(EvalError::new(format!(
-            "unsupported operation len of {}",
-            typ
-        ))),
+pub(super) fn len(args: &[Value]) -> Value {
+    if let Some(error) = arguments("len", args, 1) {
+        return error;
+    }
+    match &args[0] {
+        Value::Array(array) => Value::Int(array.values.borrow().len() as isize),
+        Value::Str(string) => Value::Int(string.value.len() as isize),
+        value => Value::error(
+            ErrorKind::Type,
+            format!("unsupported operation len of {}", value.get_type()),
+        ),
     }
 }
 
-pub(super) fn puts(args: &[ObjectRef]) -> ObjectRef {
+pub(super) fn puts(args: &[Value]) -> Value {
     let mut buffer = String::with_capacity(50);
     for arg in args {
         buffer.push_str(&arg.to_string())
     }
     print!("{}", buffer);
     let _ = std::io::stdout().flush();
-    Rc::new(Str::new(buffer))
+    Value::from(buffer)
 }
 
-pub(super) fn putsln(args: &[ObjectRef]) -> ObjectRef {
+pub(super) fn putsln(args: &[Value]) -> Value {
     let mut buffer = String::with_capacity(50);
     for arg in args {
         buffer.push_str(&arg.to_string())
     }
     println!("{}", buffer);
-    Rc::new(Str::new(buffer))
+    Value::from(buffer)
 }
 
-pub(super) fn eputs(args: &[ObjectRef]) -> ObjectRef {
+pub(super) fn eputs(args: &[Value]) -> Value {
     let mut buffer = String::with_capacity(50);
     for arg in args {
         buffer.push_str(&arg.to_string())
     }
     eprint!("{}", buffer);
     let _ = std::io::stderr().flush();
-    Rc::new(Str::new(buffer))
+    Value::from(buffer)
 }
 
-pub(super) fn eputsln(args: &[ObjectRef]) -> ObjectRef {
+pub(super) fn eputsln(args: &[Value]) -> Value {
     let mut buffer = String::with_capacity(50);
     for arg in args {
         buffer.push_str(&arg.to_string())
     }
     eprint!("{}", buffer);
-    Rc::new(Str::new(buffer))
+    Value::from(buffer)
 }
 
-pub(super) fn exit(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new("expected only one argument".to_string()));
-    }
-    let first = args.first().unwrap();
-    if first.get_type() != Type::Int {
-        return Rc::new(EvalError::new("only use int values".to_string()));
+pub(super) fn exit(args: &[Value]) -> Value {
+    if let Some(error) = arguments("exit", args, 1) {
+        return error;
+    }
+    match args[0].as_int() {
+        Some(code) => {
+            FileHandle::flush_all();
+            process::exit(code as i32)
+        }
+        None => Value::error(ErrorKind::Type, "only use int values".to_string()),
     }
-    process::exit(downcast_any!(first => Integer).value as i32)
 }
 
-pub(super) fn to_int(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new("expected only one argument".to_string()));
+pub(super) fn to_int(args: &[Value]) -> Value {
+    if let Some(error) = arguments("to_int", args, 1) {
+        return error;
     }
-    let first = args.first().unwrap();
-    match first.get_type() {
-        Type::String => {
-            let string = downcast_any!(first => Str);
+    match &args[0] {
+        Value::Str(string) => {
             if let Ok(value) = string.value.parse::<isize>() {
-                Rc::new(Integer::new(value))
+                Value::Int(value)
             } else {
-                Rc::new(EvalError::new(format!(
-                    "{} is not parsable to int",
-                    string.value
-                )))
+                Value::error(
+                    ErrorKind::Value,
+                    format!("{} is not parsable to int", string.value),
+                )
             }
         }
-        Type::Float => {
-            let float = downcast_any!(first => Float);
-            Rc::new(Integer::new(float.value as isize))
-        }
-        Type::Int => Rc::clone(first),
-        _ => Rc::new(EvalError::new("this type is not parsable".to_string())),
+        Value::Float(value) => Value::Int(*value as isize),
+        Value::Int(value) => Value::Int(*value),
+        _ => Value::error(ErrorKind::Type, "this type is not parsable".to_string()),
     }
 }
 
-pub(super) fn to_float(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new("expected only one argument".to_string()));
+pub(super) fn to_float(args: &[Value]) -> Value {
+    if let Some(error) = arguments("to_float", args, 1) {
+        return error;
     }
-    let first = args.first().unwrap();
-    match first.get_type() {
-        Type::String => {
-            let string = downcast_any!(first => Str);
+    match &args[0] {
+        Value::Str(string) => {
             if let Ok(value) = string.value.parse::<f64>() {
-                Rc::new(Float::new(value))
+                Value::Float(value)
             } else {
-                Rc::new(EvalError::new(format!(
-                    "{} is not parsable to float",
-                    string.value
-                )))
+                Value::error(
+                    ErrorKind::Value,
+                    format!("{} is not parsable to float", string.value),
+                )
             }
         }
-        Type::Int => {
-            let int = downcast_any!(first => Integer);
-            Rc::new(Float::new(int.value as f64))
-        }
-        Type::Float => Rc::clone(first),
-        _ => Rc::new(EvalError::new("this type is not parsable".to_string())),
+        Value::Int(value) => Value::Float(*value as f64),
+        Value::Float(value) => Value::Float(*value),
+        _ => Value::error(ErrorKind::Type, "this type is not parsable".to_string()),
     }
 }
 
-pub(super) fn to_string(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new("expected only one argument".to_string()));
+pub(super) fn to_string(args: &[Value]) -> Value {
+    if let Some(error) = arguments("to_str", args, 1) {
+        return error;
     }
-    Rc::new(Str::new(format!("{}", args.first().unwrap())))
+    Value::from(args[0].to_string())
 }
 
-pub(super) fn read(args: &[ObjectRef]) -> ObjectRef {
-    if !args.is_empty() {
-        return Rc::new(EvalError::new(
-            "invalid number of arguments, expected 0 arguments".to_string(),
-        ));
+pub(super) fn read(args: &[Value]) -> Value {
+    if let Some(error) = arguments("read", args, 0) {
+        return error;
     }
+    // nil at the end of the input
     let mut buffer: String = String::new();
-    io::stdin().read_line(&mut buffer).unwrap();
-    Rc::new(Str::new(buffer))
+    match io::stdin().read_line(&mut buffer) {
+        Ok(0) => Value::Nil,
+        Ok(_) => Value::from(buffer),
+        Err(e) => Value::error(ErrorKind::Io, e.to_string()),
+    }
 }
 
-pub(super) fn read_file_as_string(args: &[ObjectRef]) -> ObjectRef {
-    if args.len() != 1 {
-        return Rc::new(EvalError::new(
-            "invalid number of arguments, expected 1 arguments".to_string(),
-        ));
+pub(super) fn read_file_as_string(args: &[Value]) -> Value {
+    if let Some(error) = arguments("read_file_as_string", args, 1) {
+        return error;
     }
-    let first = &args[0];
-    let path = if first.get_type() != Type::String {
-        return Rc::new(EvalError::new("file path has to be a string".to_string()));
-    } else {
-        &downcast_any!(first => Str).value
+    let path = match args[0].as_str() {
+        Some(path) => path,
+        None => return Value::error(ErrorKind::Type, "file path has to be a string".to_string()),
     };
     match fs::read_to_string(path) {
-        Ok(value) => Rc::new(Str::new(value)),
-        Err(e) => Rc::new(EvalError::new(e.to_string())),
+        Ok(value) => Value::from(value),
+        Err(e) => Value::error(ErrorKind::Io, e.to_string()),
     }
 }
+
+pub(super) fn assert(args: &[Value]) -> Value {
+    if args.is_empty() || args.len() > 2 {
+        return Value::error(
+            ErrorKind::Argument,
+            "expected a condition and an optional message".to_string(),
+        );
+    }
+    match &args[0] {
+        Value::Bool(true) => Value::Nil,
+        Value::Bool(false) => match args.get(1) {
+            Some(message) => Value::error(ErrorKind::Assertion, message.to_string()),
+            None => Value::error(ErrorKind::Assertion, "assertion failed".to_string()),
+        },
+        value => Value::error(
+            ErrorKind::Type,
+            format!("assert expects a bool but got {}", value.get_type()),
+        ),
+    }
+}
+
+pub(super) fn assert_eq(args: &[Value]) -> Value {
+    if let Some(error) = arguments("assert_eq", args, 2) {
+        return error;
+    }
+    if operations::equal(&args[0], &args[1]) {
+        return Value::Nil;
+    }
+    // quotes strings so "1" and 1 can be told apart
+    let describe = |value: &Value| match value {
+        Value::Str(string) => format!("\"{}\"", string.value),
+        value => value.to_string(),
+    };
+    Value::error(
+        ErrorKind::Assertion,
+        format!(
+            "expected {} but got {}",
+            describe(&args[1]),
+            describe(&args[0])
+        ),
+    )
+}
+
+// calls the function and returns the error it raised
+pub(super) fn assert_error(args: &[Value], call: Caller) -> Value {
+    let (function, kind) = match args {
+        [function] => (function, None),
+        [function, Value::Str(kind)] => (function, Some(&kind.value)),
+        _ => {
+            return Value::error(
+                ErrorKind::Argument,
+                "expected a function and an optional error kind".to_string(),
+            )
+        }
+    };
+    if !matches!(function.get_type(), Type::Function | Type::BuiltInFn) {
+        return Value::error(
+            ErrorKind::Type,
+            format!("{} is not a function", function.get_type()),
+        );
+    }
+    match call(function.clone(), Vec::new()) {
+        Value::Error(error) => match kind {
+            Some(kind) if error.kind.to_string() != *kind => Value::error(
+                ErrorKind::Assertion,
+                format!(
+                    "expected a {} error but got {}: {}",
+                    kind, error.kind, error.message
+                ),
+            ),
+            _ => error.caught(),
+        },
+        value => Value::error(
+            ErrorKind::Assertion,
+            format!("expected an error but got {}", value),
+        ),
+    }
+}
+
+// the argument checks shared by the namespaces, name is how scripts call the built in
+pub(super) fn arguments(name: &str, args: &[Value], count: usize) -> Option<Value> {
+    if args.len() != count {
+        return Some(Value::error(
+            ErrorKind::Argument,
+            format!(
+                "invalid number of arguments, {} expected {} arguments but got {}",
+                name,
+                count,
+                args.len()
+            ),
+        ));
+    }
+    None
+}
+
+pub(super) fn type_error(name: &str, expected: &str, value: &Value) -> Value {
+    Value::error(
+        ErrorKind::Type,
+        format!("{} expects {} but got {}", name, expected, value.get_type()),
+    )
+}
@@ -1,10 +1,13 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::objects::ObjectRef;
+use super::{gc, objects::Value};
 
+// the outermost environment holds the globals by name, the ones below it are
+// frames whose locals are indexed by the slots the resolver gave them
 #[derive(Default)]
 pub struct Environment {
-    pub variables: HashMap<String, ObjectRef>,
+    pub variables: HashMap<String, Value>,
+    pub slots: Vec<Option<Value>>,
     pub outer: Option<EnvironmentRef>,
 }
 
@@ -15,21 +18,33 @@ impl Environment {
         Self {
             outer,
             variables: Default::default(),
+            slots: Default::default(),
         }
     }
 
-    pub fn set_variable(&mut self, name: String, value: ObjectRef) -> Option<ObjectRef> {
+    // wraps the environment so it can be shared and freed when it ends up in a cycle
+    pub fn shared(environment: Self) -> EnvironmentRef {
+        gc::track(Rc::new(RefCell::new(environment)))
+    }
+
+    pub fn frame(slots: usize, outer: EnvironmentRef) -> Self {
+        Self {
+            outer: Some(outer),
+            variables: Default::default(),
+            slots: vec![None; slots],
+        }
+    }
+
+    pub fn set_variable(&mut self, name: String, value: Value) -> Option<Value> {
         match &self.outer {
-            Some(outer) if self.exist_in_outer(&name) => {
-                outer.borrow_mut().set_variable(name, value)
-            }
-            _ => self.variables.insert(name, value),
+            Some(outer) => outer.borrow_mut().set_variable(name, value),
+            None => self.variables.insert(name, value),
         }
     }
 
-    pub fn get_variable(&self, name: &str) -> Option<ObjectRef> {
+    pub fn get_variable(&self, name: &str) -> Option<Value> {
         match self.variables.get(name) {
-            Some(value) => Some(Rc::clone(value)),
+            Some(value) => Some(value.clone()),
             None => {
                 if let Some(env) = &self.outer {
                     env.borrow().get_variable(name)
@@ -40,10 +55,24 @@ impl Environment {
         }
     }
 
-    fn exist_in_outer(&self, name: &str) -> bool {
-        if let Some(outer) = &self.outer {
-            return outer.borrow().get_variable(name).is_some();
+    pub fn get_slot(&self, depth: usize, index: usize) -> Option<Value> {
+        match (depth, &self.outer) {
+            (0, _) => self.slots.get(index).cloned().flatten(),
+            (_, Some(outer)) => outer.borrow().get_slot(depth - 1, index),
+            (_, None) => None,
+        }
+    }
+
+    pub fn set_slot(&mut self, depth: usize, index: usize, value: Value) {
+        match (depth, &self.outer) {
+            (0, _) => {
+                if index >= self.slots.len() {
+                    self.slots.resize(index + 1, None);
+                }
+                self.slots[index] = Some(value);
+            }
+            (_, Some(outer)) => outer.borrow_mut().set_slot(depth - 1, index, value),
+            (_, None) => {}
         }
-        false
     }
 }
@@ -0,0 +1,215 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    rc::Rc,
+    time::UNIX_EPOCH,
+};
+
+use crate::builtin_map;
+
+use super::{
+    built_in,
+    objects::{BuiltIn, ErrorKind, FileHandle, HashObj, Module, Stream, Value},
+};
+
+// file operations live in the `fs` namespace instead of the globals
+pub(super) fn module() -> Value {
+    let bindings = builtin_map![
+        "read_file" => Value::BuiltIn(BuiltIn::new(read_file)),
+        "write_file" => Value::BuiltIn(BuiltIn::new(write_file)),
+        "append_file" => Value::BuiltIn(BuiltIn::new(append_file)),
+        "exists" => Value::BuiltIn(BuiltIn::new(exists)),
+        "is_dir" => Value::BuiltIn(BuiltIn::new(is_dir)),
+        "list_dir" => Value::BuiltIn(BuiltIn::new(list_dir)),
+        "mkdir" => Value::BuiltIn(BuiltIn::new(mkdir)),
+        "remove" => Value::BuiltIn(BuiltIn::new(remove)),
+        "rename" => Value::BuiltIn(BuiltIn::new(rename)),
+        "copy" => Value::BuiltIn(BuiltIn::new(copy)),
+        "metadata" => Value::BuiltIn(BuiltIn::new(metadata)),
+        "open" => Value::BuiltIn(BuiltIn::new(open)),
+        "stdin" => handle("<stdin>", Stream::Stdin),
+        "stdout" => handle("<stdout>", Stream::Stdout),
+        "stderr" => handle("<stderr>", Stream::Stderr)
+    ];
+    Value::Module(Rc::new(Module::new("fs".to_string(), bindings)))
+}
+
+fn strings<'a>(name: &str, args: &'a [Value], count: usize) -> Result<Vec<&'a str>, Value> {
+    let name = format!("fs.{}", name);
+    if let Some(error) = built_in::arguments(&name, args, count) {
+        return Err(error);
+    }
+    args.iter()
+        .map(|arg| {
+            arg.as_str()
+                .ok_or_else(|| built_in::type_error(&name, "strings", arg))
+        })
+        .collect()
+}
+
+fn handle(name: &str, stream: Stream) -> Value {
+    Value::File(Rc::new(FileHandle::new(name.to_string(), stream)))
+}
+
+fn open(args: &[Value]) -> Value {
+    let (path, mode) = match strings("open", args, 2) {
+        Ok(args) => (args[0], args[1]),
+        Err(error) => return error,
+    };
+    match FileHandle::open(path, mode) {
+        Ok(file) => Value::File(file),
+        Err(error) => error,
+    }
+}
+
+fn io_error(path: &str, error: io::Error) -> Value {
+    Value::error(ErrorKind::Io, format!("{}: {}", path, error))
+}
+
+fn read_file(args: &[Value]) -> Value {
+    let path = match strings("read_file", args, 1) {
+        Ok(args) => args[0],
+        Err(error) => return error,
+    };
+    match fs::read_to_string(path) {
+        Ok(content) => Value::from(content),
+        Err(e) => io_error(path, e),
+    }
+}
+
+fn write_file(args: &[Value]) -> Value {
+    let (path, content) = match strings("write_file", args, 2) {
+        Ok(args) => (args[0], args[1]),
+        Err(error) => return error,
+    };
+    match fs::write(path, content) {
+        Ok(()) => Value::Nil,
+        Err(e) => io_error(path, e),
+    }
+}
+
+fn append_file(args: &[Value]) -> Value {
+    let (path, content) = match strings("append_file", args, 2) {
+        Ok(args) => (args[0], args[1]),
+        Err(error) => return error,
+    };
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()));
+    match written {
+        Ok(()) => Value::Nil,
+        Err(e) => io_error(path, e),
+    }
+}
+
+fn exists(args: &[Value]) -> Value {
+    match strings("exists", args, 1) {
+        Ok(args) => Value::from(Path::new(args[0]).exists()),
+        Err(error) => error,
+    }
+}
+
+fn is_dir(args: &[Value]) -> Value {
+    match strings("is_dir", args, 1) {
+        Ok(args) => Value::from(Path::new(args[0]).is_dir()),
+        Err(error) => error,
+    }
+}
+
+// the names of the entries, sorted so scripts see the same order everywhere
+fn list_dir(args: &[Value]) -> Value {
+    let path = match strings("list_dir", args, 1) {
+        Ok(args) => args[0],
+        Err(error) => return error,
+    };
+    let entries = fs::read_dir(path).and_then(|entries| {
+        entries
+            .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().to_string()))
+            .collect::<io::Result<Vec<String>>>()
+    });
+    match entries {
+        Ok(mut names) => {
+            names.sort();
+            Value::array(names.into_iter().map(Value::from).collect())
+        }
+        Err(e) => io_error(path, e),
+    }
+}
+
+// creates the missing parents too
+fn mkdir(args: &[Value]) -> Value {
+    let path = match strings("mkdir", args, 1) {
+        Ok(args) => args[0],
+        Err(error) => return error,
+    };
+    match fs::create_dir_all(path) {
+        Ok(()) => Value::Nil,
+        Err(e) => io_error(path, e),
+    }
+}
+
+// directories are only removed when empty
+fn remove(args: &[Value]) -> Value {
+    let path = match strings("remove", args, 1) {
+        Ok(args) => args[0],
+        Err(error) => return error,
+    };
+    let removed = if Path::new(path).is_dir() {
+        fs::remove_dir(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match removed {
+        Ok(()) => Value::Nil,
+        Err(e) => io_error(path, e),
+    }
+}
+
+fn rename(args: &[Value]) -> Value {
+    let (from, to) = match strings("rename", args, 2) {
+        Ok(args) => (args[0], args[1]),
+        Err(error) => return error,
+    };
+    match fs::rename(from, to) {
+        Ok(()) => Value::Nil,
+        Err(e) => io_error(from, e),
+    }
+}
+
+// returns the number of bytes copied
+fn copy(args: &[Value]) -> Value {
+    let (from, to) = match strings("copy", args, 2) {
+        Ok(args) => (args[0], args[1]),
+        Err(error) => return error,
+    };
+    match fs::copy(from, to) {
+        Ok(size) => Value::from(size as isize),
+        Err(e) => io_error(from, e),
+    }
+}
+
+// size in bytes and mtime in seconds since the unix epoch
+fn metadata(args: &[Value]) -> Value {
+    let path = match strings("metadata", args, 1) {
+        Ok(args) => args[0],
+        Err(error) => return error,
+    };
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => return io_error(path, e),
+    };
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(Value::Nil, |since| Value::from(since.as_secs() as isize));
+    let mut hash = HashObj::default();
+    hash.put("size".to_string(), Value::from(metadata.len() as isize));
+    hash.put("mtime".to_string(), mtime);
+    hash.put("is_dir".to_string(), Value::from(metadata.is_dir()));
+    Value::from(hash)
+}
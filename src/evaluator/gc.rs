@@ -0,0 +1,274 @@
+use std::{
+    cell::RefCell,
+    collections::{hash_map::Entry, HashMap},
+    rc::{Rc, Weak},
+};
+
+use crate::vm::{Closure, Frame};
+
+use super::{
+    environment::Environment,
+    objects::{Array, Function, HashObj, Value},
+};
+
+// reference counting frees everything but cycles, so every object that can
+// take part in one is tracked here and a collection looks for groups of them
+// that are only referenced by each other
+
+pub const DEFAULT_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    // tracked objects still alive
+    pub live: usize,
+    // objects tracked since the interpreter started
+    pub allocated: usize,
+    pub collections: usize,
+    // objects freed by breaking cycles
+    pub collected: usize,
+}
+
+pub trait Trace {
+    // visits the address of every object this one holds a reference to,
+    // returns false when the object is borrowed and can not be looked at
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool;
+    // drops the references this object holds
+    fn clear(&self);
+}
+
+struct Heap {
+    objects: Vec<Weak<dyn Trace>>,
+    pending: usize,
+    threshold: usize,
+    stats: HeapStats,
+}
+
+thread_local! {
+    static HEAP: RefCell<Heap> = RefCell::new(Heap {
+        objects: Vec::new(),
+        pending: 0,
+        threshold: DEFAULT_THRESHOLD,
+        stats: HeapStats::default(),
+    });
+}
+
+// registers an object with the collector, tracking the same object twice is harmless
+pub fn track<T: Trace + 'static>(object: Rc<T>) -> Rc<T> {
+    let due = HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.objects.push(Rc::downgrade(&object) as Weak<dyn Trace>);
+        heap.stats.allocated += 1;
+        heap.pending += 1;
+        heap.pending >= heap.threshold.max(heap.stats.live)
+    });
+    if due {
+        collect();
+    }
+    object
+}
+
+// number of allocations between automatic collections, zero collects on every one
+pub fn set_threshold(threshold: usize) {
+    HEAP.with(|heap| heap.borrow_mut().threshold = threshold);
+}
+
+pub fn stats() -> HeapStats {
+    HEAP.with(|heap| {
+        let heap = heap.borrow();
+        let mut live: Vec<*const ()> = heap
+            .objects
+            .iter()
+            .filter(|object| object.strong_count() > 0)
+            .map(|object| object.as_ptr() as *const ())
+            .collect();
+        live.sort();
+        live.dedup();
+        HeapStats {
+            live: live.len(),
+            ..heap.stats
+        }
+    })
+}
+
+// frees the objects only reachable from cycles and returns how many there were
+pub fn collect() -> usize {
+    let tracked = HEAP.with(|heap| std::mem::take(&mut heap.borrow_mut().objects));
+    let mut objects: Vec<Rc<dyn Trace>> = Vec::with_capacity(tracked.len());
+    let mut indexes: HashMap<*const (), usize> = HashMap::with_capacity(tracked.len());
+    for object in tracked.iter().filter_map(Weak::upgrade) {
+        if let Entry::Vacant(entry) = indexes.entry(Rc::as_ptr(&object) as *const ()) {
+            entry.insert(objects.len());
+            objects.push(object);
+        }
+    }
+    drop(tracked);
+
+    // references left after removing the ones between tracked objects come
+    // from outside, the interpreter stacks or the host, and make an object a root
+    let mut external: Vec<usize> = objects
+        .iter()
+        .map(|object| Rc::strong_count(object) - 1)
+        .collect();
+    let mut traced = vec![false; objects.len()];
+    for (index, object) in objects.iter().enumerate() {
+        traced[index] = object.trace(&mut |child| {
+            if let Some(&child) = indexes.get(&child) {
+                external[child] -= 1;
+            }
+        });
+    }
+
+    let mut reachable = vec![false; objects.len()];
+    let mut pending: Vec<usize> = (0..objects.len())
+        .filter(|&index| external[index] > 0 || !traced[index])
+        .collect();
+    while let Some(index) = pending.pop() {
+        if reachable[index] {
+            continue;
+        }
+        reachable[index] = true;
+        objects[index].trace(&mut |child| {
+            if let Some(&child) = indexes.get(&child) {
+                if !reachable[child] {
+                    pending.push(child);
+                }
+            }
+        });
+    }
+
+    let mut garbage = 0;
+    for (object, _) in objects.iter().zip(&reachable).filter(|(_, r)| !**r) {
+        object.clear();
+        garbage += 1;
+    }
+    let survivors: Vec<Weak<dyn Trace>> = objects
+        .iter()
+        .zip(&reachable)
+        .filter(|(_, reachable)| **reachable)
+        .map(|(object, _)| Rc::downgrade(object))
+        .collect();
+    let live = survivors.len();
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+        heap.objects.extend(survivors);
+        heap.pending = 0;
+        heap.stats.live = live;
+        heap.stats.collections += 1;
+        heap.stats.collected += garbage;
+    });
+    drop(objects);
+    garbage
+}
+
+fn address<T>(object: &Rc<T>) -> *const () {
+    Rc::as_ptr(object) as *const ()
+}
+
+fn trace_value(value: &Value, visit: &mut dyn FnMut(*const ())) {
+    match value {
+        Value::Array(array) => visit(address(array)),
+        Value::Hash(hash) => visit(address(hash)),
+        Value::Function(function) => visit(address(function)),
+        Value::Closure(closure) => visit(address(closure)),
+        _ => {}
+    }
+}
+
+impl Trace for Array {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        let Ok(values) = self.values.try_borrow() else {
+            return false;
+        };
+        values.iter().for_each(|value| trace_value(value, visit));
+        true
+    }
+
+    fn clear(&self) {
+        if let Ok(mut values) = self.values.try_borrow_mut() {
+            let cleared = std::mem::take(&mut *values);
+            drop(values);
+            drop(cleared);
+        }
+    }
+}
+
+impl Trace for HashObj {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        self.pairs
+            .values()
+            .for_each(|value| trace_value(value, visit));
+        true
+    }
+
+    // hashes can not change after they are built, the cycles they are part
+    // of go through an environment or an array
+    fn clear(&self) {}
+}
+
+impl Trace for Function {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        visit(address(&self.environment));
+        true
+    }
+
+    fn clear(&self) {}
+}
+
+impl Trace for Closure {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        visit(address(&self.frame));
+        visit(address(&self.globals));
+        true
+    }
+
+    fn clear(&self) {}
+}
+
+impl Trace for RefCell<Environment> {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        let Ok(environment) = self.try_borrow() else {
+            return false;
+        };
+        environment
+            .variables
+            .values()
+            .chain(environment.slots.iter().flatten())
+            .for_each(|value| trace_value(value, visit));
+        if let Some(outer) = &environment.outer {
+            visit(address(outer));
+        }
+        true
+    }
+
+    fn clear(&self) {
+        if let Ok(mut environment) = self.try_borrow_mut() {
+            let cleared = std::mem::take(&mut *environment);
+            drop(environment);
+            drop(cleared);
+        }
+    }
+}
+
+impl Trace for Frame {
+    fn trace(&self, visit: &mut dyn FnMut(*const ())) -> bool {
+        let Ok(slots) = self.slots.try_borrow() else {
+            return false;
+        };
+        slots
+            .iter()
+            .flatten()
+            .for_each(|value| trace_value(value, visit));
+        if let Some(outer) = &self.outer {
+            visit(address(outer));
+        }
+        true
+    }
+
+    fn clear(&self) {
+        if let Ok(mut slots) = self.slots.try_borrow_mut() {
+            let cleared = std::mem::take(&mut *slots);
+            drop(slots);
+            drop(cleared);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::{cmp::Ordering, collections::HashMap, f64::consts, rc::Rc};
+
+use crate::builtin_map;
+
+use super::{
+    built_in,
+    objects::{BuiltIn, ErrorKind, Module, Value},
+    operations,
+};
+
+// every function takes ints and floats, ints stay ints where the result allows it
+pub(super) fn module() -> Value {
+    let bindings = builtin_map![
+        "sqrt" => Value::BuiltIn(BuiltIn::new(sqrt)),
+        "pow" => Value::BuiltIn(BuiltIn::new(pow)),
+        "abs" => Value::BuiltIn(BuiltIn::new(abs)),
+        "floor" => Value::BuiltIn(BuiltIn::new(floor)),
+        "ceil" => Value::BuiltIn(BuiltIn::new(ceil)),
+        "round" => Value::BuiltIn(BuiltIn::new(round)),
+        "trunc" => Value::BuiltIn(BuiltIn::new(trunc)),
+        "min" => Value::BuiltIn(BuiltIn::new(min)),
+        "max" => Value::BuiltIn(BuiltIn::new(max)),
+        "clamp" => Value::BuiltIn(BuiltIn::new(clamp)),
+        "sin" => Value::BuiltIn(BuiltIn::new(sin)),
+        "cos" => Value::BuiltIn(BuiltIn::new(cos)),
+        "tan" => Value::BuiltIn(BuiltIn::new(tan)),
+        "atan2" => Value::BuiltIn(BuiltIn::new(atan2)),
+        "log" => Value::BuiltIn(BuiltIn::new(log)),
+        "exp" => Value::BuiltIn(BuiltIn::new(exp)),
+        "is_nan" => Value::BuiltIn(BuiltIn::new(is_nan)),
+        "is_finite" => Value::BuiltIn(BuiltIn::new(is_finite)),
+        "pi" => Value::Float(consts::PI),
+        "e" => Value::Float(consts::E),
+        "inf" => Value::Float(f64::INFINITY),
+        "nan" => Value::Float(f64::NAN)
+    ];
+    Value::Module(Rc::new(Module::new("math".to_string(), bindings)))
+}
+
+fn numbers(name: &str, args: &[Value], count: usize) -> Result<Vec<f64>, Value> {
+    if let Some(error) = built_in::arguments(&format!("math.{}", name), args, count) {
+        return Err(error);
+    }
+    args.iter().map(|arg| number(name, arg)).collect()
+}
+
+fn number(name: &str, value: &Value) -> Result<f64, Value> {
+    match value {
+        Value::Int(value) => Ok(*value as f64),
+        Value::Float(value) => Ok(*value),
+        value => Err(built_in::type_error(
+            &format!("math.{}", name),
+            "numbers",
+            value,
+        )),
+    }
+}
+
+// functions that always give a float
+fn float(name: &str, args: &[Value], function: fn(f64) -> f64) -> Value {
+    match numbers(name, args, 1) {
+        Ok(numbers) => Value::Float(function(numbers[0])),
+        Err(error) => error,
+    }
+}
+
+fn sqrt(args: &[Value]) -> Value {
+    float("sqrt", args, f64::sqrt)
+}
+
+fn sin(args: &[Value]) -> Value {
+    float("sin", args, f64::sin)
+}
+
+fn cos(args: &[Value]) -> Value {
+    float("cos", args, f64::cos)
+}
+
+fn tan(args: &[Value]) -> Value {
+    float("tan", args, f64::tan)
+}
+
+fn exp(args: &[Value]) -> Value {
+    float("exp", args, f64::exp)
+}
+
+fn atan2(args: &[Value]) -> Value {
+    match numbers("atan2", args, 2) {
+        Ok(numbers) => Value::Float(numbers[0].atan2(numbers[1])),
+        Err(error) => error,
+    }
+}
+
+// natural logarithm, or in the base given as second argument
+fn log(args: &[Value]) -> Value {
+    match args.len() {
+        1 => return float("log", args, f64::ln),
+        2 => {}
+        len => {
+            return Value::error(
+                ErrorKind::Argument,
+                format!(
+                    "invalid number of arguments, math.log expected 1 or 2 arguments but got {}",
+                    len
+                ),
+            )
+        }
+    }
+    match numbers("log", args, 2) {
+        Ok(numbers) => Value::Float(numbers[0].log(numbers[1])),
+        Err(error) => error,
+    }
+}
+
+// the same as the ** operator
+fn pow(args: &[Value]) -> Value {
+    match numbers("pow", args, 2) {
+        Ok(_) => operations::power(&args[0], &args[1]),
+        Err(error) => error,
+    }
+}
+
+fn abs(args: &[Value]) -> Value {
+    let numbers = match numbers("abs", args, 1) {
+        Ok(numbers) => numbers,
+        Err(error) => return error,
+    };
+    match &args[0] {
+        Value::Int(value) => match value.checked_abs() {
+            Some(value) => Value::Int(value),
+            None => Value::error(
+                ErrorKind::Value,
+                format!("math.abs({}) overflows int", value),
+            ),
+        },
+        _ => Value::Float(numbers[0].abs()),
+    }
+}
+
+// rounding gives an int, it fails for nan, infinities and floats out of the int range
+fn rounded(name: &str, args: &[Value], function: fn(f64) -> f64) -> Value {
+    let numbers = match numbers(name, args, 1) {
+        Ok(numbers) => numbers,
+        Err(error) => return error,
+    };
+    if let Value::Int(value) = args[0] {
+        return Value::Int(value);
+    }
+    let value = function(numbers[0]);
+    if value >= isize::MIN as f64 && value < isize::MAX as f64 {
+        Value::Int(value as isize)
+    } else {
+        Value::error(ErrorKind::Value, format!("cannot convert {} to int", value))
+    }
+}
+
+fn floor(args: &[Value]) -> Value {
+    rounded("floor", args, f64::floor)
+}
+
+fn ceil(args: &[Value]) -> Value {
+    rounded("ceil", args, f64::ceil)
+}
+
+// halfway cases are rounded away from zero
+fn round(args: &[Value]) -> Value {
+    rounded("round", args, f64::round)
+}
+
+fn trunc(args: &[Value]) -> Value {
+    rounded("trunc", args, f64::trunc)
+}
+
+// takes any number of arguments and returns one of them unchanged,
+// ints are compared as ints and the first nan wins over everything
+fn extreme(name: &str, args: &[Value], replaces: Ordering) -> Value {
+    if args.is_empty() {
+        return Value::error(
+            ErrorKind::Argument,
+            format!("math.{} expects at least one argument", name),
+        );
+    }
+    let numbers = match numbers(name, args, args.len()) {
+        Ok(numbers) => numbers,
+        Err(error) => return error,
+    };
+    if let Some(index) = numbers.iter().position(|number| number.is_nan()) {
+        return args[index].clone();
+    }
+    let mut result = 0;
+    for (index, value) in args.iter().enumerate() {
+        let ordering = match (value, &args[result]) {
+            (Value::Int(value), Value::Int(current)) => value.cmp(current),
+            _ => numbers[index].total_cmp(&numbers[result]),
+        };
+        if ordering == replaces {
+            result = index;
+        }
+    }
+    args[result].clone()
+}
+
+fn min(args: &[Value]) -> Value {
+    extreme("min", args, Ordering::Less)
+}
+
+fn max(args: &[Value]) -> Value {
+    extreme("max", args, Ordering::Greater)
+}
+
+fn clamp(args: &[Value]) -> Value {
+    let numbers = match numbers("clamp", args, 3) {
+        Ok(numbers) => numbers,
+        Err(error) => return error,
+    };
+    let (value, low, high) = (numbers[0], numbers[1], numbers[2]);
+    if low > high {
+        return Value::error(
+            ErrorKind::Value,
+            format!(
+                "math.clamp lower bound {} is greater than {}",
+                args[1], args[2]
+            ),
+        );
+    }
+    if value < low {
+        args[1].clone()
+    } else if value > high {
+        args[2].clone()
+    } else {
+        args[0].clone()
+    }
+}
+
+fn is_nan(args: &[Value]) -> Value {
+    match numbers("is_nan", args, 1) {
+        Ok(numbers) => Value::from(numbers[0].is_nan()),
+        Err(error) => error,
+    }
+}
+
+fn is_finite(args: &[Value]) -> Value {
+    match numbers("is_finite", args, 1) {
+        Ok(numbers) => Value::from(numbers[0].is_finite()),
+        Err(error) => error,
+    }
+}
@@ -1,712 +1,609 @@
-use std::{cell::RefCell, cmp::Ordering, collections::HashMap, process, rc::Rc};
+use std::{cell::Cell, collections::HashMap, rc::Rc};
 
 use crate::{
     ast::{
         node::{
-            ArrayExpr, BlockStatement, BoolExpr, CallExpr, ExpressionStmt, FloatExpr, FnExpr,
-            HashExpr, IfExpr, IndexExpr, InfixExpr, IntExpr, NodeRef, OpCode, PrefixExpr,
-            RetStatement, StringExpr, VarStatement, WhileExpr,
+            ArrayExpr, BlockStatement, CallExpr, Expr, HashExpr, IdentifierExpr, IfExpr, IndexExpr,
+            InfixExpr, PrefixExpr, RetStatement, Stmt, TryExpr, WhileExpr,
         },
-        LetStatement, Program,
+        Program,
     },
-    builtin_map, downcast, downcast_any, downcast_option,
+    builtin_map,
     evaluator::objects::Type,
+    lexer::Location,
+    resolver::{resolve, Slot},
 };
 
 use self::{
     environment::{Environment, EnvironmentRef},
+    modules::ModuleLoader,
     objects::{
-        Array, Boolean, BuiltIn, EvalError, Float, Function, HashObj, Integer, Object, ObjectRef,
-        Ret, Str,
+        Array, BuiltIn, ErrorKind, EvalError, FileHandle, Function, HashObj, Ret, Str, TailCall,
+        Value,
     },
+    operations::{BinaryOperator, PrefixOperator},
 };
 
 pub mod built_in;
 pub mod environment;
+mod fs;
+pub mod gc;
+mod math;
+pub(crate) mod modules;
 pub mod objects;
+pub mod operations;
+mod random;
+mod time;
 
-pub type BuiltInMap = HashMap<String, ObjectRef>;
+pub type BuiltInMap = HashMap<String, Value>;
+pub type MethodTable = HashMap<Type, BuiltInMap>;
 
 pub struct Evaluator {
     built_in: BuiltInMap,
+    methods: MethodTable,
+    modules: ModuleLoader,
+    depth: Cell<usize>,
+    max_depth: usize,
+    // try blocks open in the function being evaluated, a ret inside of them is not a tail call
+    tries: Cell<usize>,
+}
+
+pub const DEFAULT_MAX_DEPTH: usize = 2_000;
+
+pub fn global_built_ins() -> BuiltInMap {
+    builtin_map!["exit" => Value::BuiltIn(BuiltIn::new(built_in::exit)),
+        "len" => Value::BuiltIn(BuiltIn::new(built_in::len)),
+        "puts" => Value::BuiltIn(BuiltIn::new(built_in::puts)),
+        "eputs" => Value::BuiltIn(BuiltIn::new(built_in::eputs)),
+        "read" => Value::BuiltIn(BuiltIn::new(built_in::read)),
+        "to_int" => Value::BuiltIn(BuiltIn::new(built_in::to_int)),
+        "to_str" => Value::BuiltIn(BuiltIn::new(built_in::to_string)),
+        "to_float" => Value::BuiltIn(BuiltIn::new(built_in::to_float)),
+        "putsln" => Value::BuiltIn(BuiltIn::new(built_in::putsln)),
+        "eputsln" => Value::BuiltIn(BuiltIn::new(built_in::eputsln)),
+        "read_file_as_string" => Value::BuiltIn(BuiltIn::new(built_in::read_file_as_string)),
+        "fs" => fs::module(),
+        "math" => math::module(),
+        "random" => random::module(),
+        "time" => time::module(),
+        "assert" => Value::BuiltIn(BuiltIn::new(built_in::assert)),
+        "assert_eq" => Value::BuiltIn(BuiltIn::new(built_in::assert_eq)),
+        "assert_error" => Value::BuiltIn(BuiltIn::with_callback(built_in::assert_error))
+    ]
+}
+
+pub(crate) fn type_methods() -> MethodTable {
+    let mut methods = MethodTable::new();
+    methods.insert(Type::String, Str::methods());
+    methods.insert(Type::Array, Array::methods());
+    methods.insert(Type::File, FileHandle::methods());
+    methods
 }
 
 impl Evaluator {
     pub fn new() -> Self {
-        let built_in: BuiltInMap = builtin_map!["exit" => Rc::new(BuiltIn::new(built_in::exit)),
-            "len" => Rc::new(BuiltIn::new(built_in::len)),
-            "puts" => Rc::new(BuiltIn::new(built_in::puts)),
-            "eputs" => Rc::new(BuiltIn::new(built_in::eputs)),
-            "read" => Rc::new(BuiltIn::new(built_in::read)),
-            "to_int" => Rc::new(BuiltIn::new(built_in::to_int)),
-            "to_str" => Rc::new(BuiltIn::new(built_in::to_string)),
-            "to_float" => Rc::new(BuiltIn::new(built_in::to_float)),
-            "putsln" => Rc::new(BuiltIn::new(built_in::putsln)),
-            "eputsln" => Rc::new(BuiltIn::new(built_in::eputsln)),
-            "read_file_as_string" => Rc::new(BuiltIn::new(built_in::read_file_as_string))
-        ];
-
-        Self { built_in }
+        Self {
+            built_in: global_built_ins(),
+            methods: type_methods(),
+            modules: Default::default(),
+            depth: Cell::new(0),
+            max_depth: DEFAULT_MAX_DEPTH,
+            tries: Cell::new(0),
+        }
+    }
+
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            ..Self::new()
+        }
     }
 }
 
 impl Evaluator {
-    pub fn eval(&self, node: Option<&NodeRef>, environment: EnvironmentRef) -> Option<ObjectRef> {
-        if let Some(node) = node {
-            match node.get_op_code() {
-                OpCode::Hash => self.eval_hash(node, environment),
-                OpCode::While => self.eval_while(node, environment),
-                OpCode::Ret => self.eval_return_smtmt(node, environment),
-                OpCode::Index => self.eval_index(node, environment),
-                OpCode::If => self.eval_if(node, environment),
-                OpCode::Array => Some(self.eval_array(node, environment)),
-                OpCode::Let => {
-                    let let_stmt = downcast_any!(node => LetStatement);
-                    let value = Rc::clone(
-                        &self
-                            .eval(Some(&let_stmt.value), Rc::clone(&environment))
-                            .unwrap(),
-                    );
-                    if self.is_error(&Some(Rc::clone(&value))) {
-                        Some(value)
-                    } else {
-                        self.set_variable(
-                            let_stmt.name.to_string(),
-                            Rc::clone(&value),
-                            environment,
-                        );
-                        Some(value)
-                    }
-                }
-                OpCode::Var => {
-                    let let_stmt = downcast_any!(node => VarStatement);
-                    let value = Rc::clone(
-                        &self
-                            .eval(Some(&let_stmt.value), Rc::clone(&environment))
-                            .unwrap(),
-                    );
-                    if self.is_error(&Some(Rc::clone(&value))) {
-                        Some(value)
-                    } else {
-                        if value.get_type() == Type::Function {
-                            return Some(Rc::new(EvalError::new(
-                                "function only be set with let".to_string(),
-                            )));
-                        }
-                        self.set_variable(
-                            let_stmt.name.to_string(),
-                            Rc::clone(&value),
-                            environment,
-                        );
-                        Some(value)
-                    }
-                }
-                OpCode::Int => {
-                    let int_expr = downcast_any!(node => IntExpr);
-                    Some(Rc::new(Integer::new(int_expr.value)))
-                }
-                OpCode::Bool => {
-                    let int_expr = downcast_any!(node => BoolExpr);
-                    Some(Rc::new(Boolean::new(int_expr.value)))
-                }
+    pub fn eval(&self, program: &Program, environment: EnvironmentRef) -> Value {
+        resolve(program, &environment.borrow());
+        let frame = Environment::frame(program.slots.get(), gc::track(environment));
+        self.eval_statements(&program.statements, Environment::shared(frame))
+    }
 
-                OpCode::Block => {
-                    let block_stmt = downcast_any!(node => BlockStatement);
-                    let sub_environment = Rc::new(RefCell::new(Environment::new(Some(Rc::clone(
-                        &environment,
-                    )))));
-                    Some(self.eval_statements(&block_stmt.statements, sub_environment))
-                }
-                OpCode::Infix => Some(self.eval_infix(node, environment)),
-                OpCode::Float => {
-                    let int_expr = downcast_any!(node => FloatExpr);
-                    Some(Rc::new(Float::new(int_expr.value)))
+    fn eval_stmt(&self, stmt: &Stmt, environment: EnvironmentRef) -> Value {
+        match stmt {
+            Stmt::Ret(ret_stmt) => self.eval_return_smtmt(ret_stmt, environment),
+            Stmt::Let(let_stmt) => {
+                let value = self.eval_expr(&let_stmt.value, Rc::clone(&environment));
+                if !value.is_error() {
+                    self.assign(&let_stmt.name, value.clone(), &environment);
                 }
-                OpCode::Prefix => Some(self.eval_prefix(node, environment)),
-                OpCode::String => {
-                    let int_expr = downcast_any!(node => StringExpr);
-                    Some(Rc::new(Str::new(int_expr.value.clone())))
-                }
-                OpCode::Program => {
-                    let program = downcast_any!(node => Program);
-                    Some(self.eval_statements(&program.statements, environment))
+                value
+            }
+            Stmt::Var(var_stmt) => {
+                let value = self.eval_expr(&var_stmt.value, Rc::clone(&environment));
+                if value.is_error() {
+                    return value;
                 }
-                OpCode::Function => {
-                    let function_expr = downcast_any!(node => FnExpr);
-                    let body = Rc::clone(&function_expr.body);
-                    let parameters = Rc::clone(&function_expr.parameters);
-                    let function: ObjectRef =
-                        Rc::new(Function::new(body, parameters, Rc::clone(&environment)));
-                    if let Some(name) = &function_expr.name {
-                        self.set_variable(name.to_string(), Rc::clone(&function), environment);
-                    }
-                    Some(function)
+                if value.get_type() == Type::Function {
+                    let error =
+                        Value::error(ErrorKind::Type, "function only be set with let".to_string());
+                    return self.locate_error(error, &var_stmt.name.location);
                 }
-                OpCode::Expression => {
-                    let expr = downcast_any!(node => ExpressionStmt);
-                    self.eval(Some(&expr.expression), environment)
+                self.assign(&var_stmt.name, value.clone(), &environment);
+                value
+            }
+            Stmt::Throw(throw) => {
+                let value = self.eval_expr(&throw.value, environment);
+                if value.is_error() {
+                    return value;
                 }
-                OpCode::Identifier => {
-                    let identifier = node.to_string();
-                    if let Some(value) = environment.borrow().get_variable(&identifier) {
-                        Some(value)
-                    } else if let Some(value) = self.built_in.get(&identifier) {
-                        Some(Rc::clone(value))
-                    } else {
-                        Some(Rc::new(EvalError::new(format!(
-                            "unknown word '{}'",
-                            identifier
-                        ))))
-                    }
+                let error = Value::from(EvalError::thrown(&value));
+                self.locate_error(error, &throw.location)
+            }
+            Stmt::Trivia(_) | Stmt::Test(_) => Value::Nil,
+            Stmt::Import(import) => self.eval_import(import, environment),
+            Stmt::Export(export) => self.eval_stmt(&export.statement, environment),
+            Stmt::Expression(expr) => self.eval_expr(&expr.expression, environment),
+        }
+    }
+
+    fn eval_expr(&self, expr: &Expr, environment: EnvironmentRef) -> Value {
+        match expr {
+            Expr::Hash(hash_expr) => self.eval_hash(hash_expr, environment),
+            Expr::While(while_expr) => self.eval_while(while_expr, environment),
+            Expr::Index(index_expr) => self.eval_index(index_expr, environment),
+            Expr::If(if_expr) => self.eval_if(if_expr, environment),
+            Expr::Try(try_expr) => self.eval_try(try_expr, environment),
+            Expr::Array(array_expr) => self.eval_array(array_expr, environment),
+            Expr::Int(int_expr) => Value::Int(int_expr.value),
+            Expr::Bool(bool_expr) => Value::Bool(bool_expr.value),
+            Expr::Nil(_) => Value::Nil,
+            Expr::Infix(infix_expr) => self.eval_infix(infix_expr, environment),
+            Expr::Float(float_expr) => Value::Float(float_expr.value),
+            Expr::Prefix(prefix_expr) => self.eval_prefix(prefix_expr, environment),
+            Expr::String(string_expr) => Value::from(string_expr.value.clone()),
+            Expr::Function(function_expr) => {
+                let body = Rc::clone(&function_expr.body);
+                let parameters = Rc::clone(&function_expr.parameters);
+                let name = function_expr.name.as_ref().map(|name| name.to_string());
+                let function = Value::Function(gc::track(Rc::new(Function::new(
+                    name,
+                    body,
+                    parameters,
+                    function_expr.slots.get(),
+                    Rc::clone(&environment),
+                ))));
+                if let Some(name) = &function_expr.name {
+                    self.assign(name, function.clone(), &environment);
                 }
-                OpCode::Call => {
-                    let call_expr = downcast_any!(node => CallExpr);
-                    let function = self.eval(Some(&call_expr.function), Rc::clone(&environment));
-                    if self.is_error(&function) {
-                        return function;
-                    }
-                    let mut args = Vec::with_capacity(3);
-                    for arg in call_expr.arguments.iter() {
-                        args.push(self.eval(Some(arg), Rc::clone(&environment)));
-                    }
-                    if args.len() == 1 && self.is_error(args.first().unwrap()) {
-                        return function;
-                    }
-                    self.apply_function(function.unwrap(), args)
+                function
+            }
+            Expr::Identifier(identifier) => {
+                let local = match identifier.slot.get() {
+                    Slot::Local(depth, index) => environment.borrow().get_slot(depth, index),
+                    Slot::Global => None,
+                };
+                let name = &identifier.value;
+                if let Some(value) = local {
+                    value
+                } else if let Some(value) = environment.borrow().get_variable(name) {
+                    value
+                } else if let Some(value) = self.built_in.get(name) {
+                    value.clone()
+                } else {
+                    let error = Value::error(ErrorKind::Name, format!("unknown word '{}'", name));
+                    self.locate_error(error, &identifier.location)
                 }
             }
-        } else {
-            None
+            Expr::Call(call_expr) => {
+                let (function, args) = match self.eval_call(call_expr, environment) {
+                    Ok(call) => call,
+                    Err(error) => return error,
+                };
+                let result = self.apply_function(function, args);
+                self.locate_error(result, &call_expr.location)
+            }
         }
     }
 
     #[inline]
-    fn set_variable(
-        &self,
-        name: String,
-        value: ObjectRef,
-        enviroment: EnvironmentRef,
-    ) -> Option<ObjectRef> {
-        enviroment.borrow_mut().set_variable(name, value)
+    fn assign(&self, name: &IdentifierExpr, value: Value, environment: &EnvironmentRef) {
+        self.store(name.slot.get(), &name.value, value, environment);
     }
 
     #[inline]
-    fn eval_statements(&self, stmts: &[NodeRef], enviroment: EnvironmentRef) -> ObjectRef {
-        let mut result = None;
-        for stmt in stmts.iter() {
-            result = self.eval(Some(stmt), Rc::clone(&enviroment));
-            //TODO: improve this
-
-            if let Some(ref result) = result {
-                if result.get_type() == Type::Return || self.is_error(&Some(Rc::clone(result))) {
-                    break;
-                }
+    pub(super) fn store(&self, slot: Slot, name: &str, value: Value, environment: &EnvironmentRef) {
+        match slot {
+            Slot::Local(depth, index) => environment.borrow_mut().set_slot(depth, index, value),
+            Slot::Global => {
+                environment
+                    .borrow_mut()
+                    .set_variable(name.to_string(), value);
             }
         }
-        if let Some(result) = result {
-            result
-        } else {
-            process::exit(1)
-        }
     }
 
     #[inline]
-    fn eval_prefix(&self, node: &NodeRef, enviroment: EnvironmentRef) -> ObjectRef {
-        let prefix = downcast_any!(node => PrefixExpr);
-        let value = self.eval(Some(&prefix.right), enviroment);
-        match (&prefix.operator[..], value) {
-            ("!", Some(value)) if value.get_type() == Type::Bool => {
-                let boolean_value = downcast_any!(value => Boolean);
-                Rc::new(Boolean::new(!boolean_value.value))
-            }
-            ("!", Some(value)) if value.get_type() == Type::Int => {
-                let integer_value = downcast_any!(value => Integer);
-                Rc::new(Integer::new(!integer_value.value))
-            }
-            ("-", Some(value)) if value.get_type() == Type::Int => {
-                let integer_value = downcast_any!(value => Integer);
-                Rc::new(Integer::new(-integer_value.value))
+    fn eval_statements(&self, stmts: &[Stmt], enviroment: EnvironmentRef) -> Value {
+        let mut result = Value::Nil;
+        for stmt in stmts.iter() {
+            if let Stmt::Trivia(_) = stmt {
+                continue;
             }
-            ("-", Some(value)) if value.get_type() == Type::Float => {
-                let float_value = downcast_any!(value => Float);
-                Rc::new(Float::new(-float_value.value))
+            result = self.eval_stmt(stmt, Rc::clone(&enviroment));
+            if result.get_type() == Type::Return || result.is_error() {
+                break;
             }
-            (op, Some(value)) => Rc::new(EvalError::new(format!(
-                "unsuported operation '{}' with '{}'",
-                op,
-                value.get_type()
-            ))),
-            _ => Rc::new(EvalError::new("unexpected error".to_string())),
         }
+        result
+    }
+
+    #[inline]
+    fn eval_block(&self, block: &BlockStatement, environment: EnvironmentRef) -> Value {
+        self.eval_statements(&block.statements, environment)
+    }
+
+    #[inline]
+    fn eval_prefix(&self, prefix: &PrefixExpr, enviroment: EnvironmentRef) -> Value {
+        let value = self.eval_expr(&prefix.right, enviroment);
+        let result = match PrefixOperator::from_operator(prefix.operator) {
+            _ if value.is_error() => return value,
+            Some(operator) => operations::prefix(operator, value),
+            None => Value::error(
+                ErrorKind::Type,
+                format!(
+                    "unsuported operation '{}' with '{}'",
+                    prefix.operator,
+                    value.get_type()
+                ),
+            ),
+        };
+        self.locate_error(result, &prefix.location)
     }
 
     fn eval_object_function(
         &self,
-        object: ObjectRef,
-        call: &NodeRef,
+        object: Value,
+        call_expr: &CallExpr,
         environment: EnvironmentRef,
-    ) -> ObjectRef {
-        let call_expr = downcast_any!(call => CallExpr);
+    ) -> Value {
         let function_name = call_expr.function.to_string();
-        match object.get_type() {
-            Type::Array => {
-                let arr = downcast_any!(object => Array);
-                if let Some(function) = arr.functions.get(&function_name) {
-                    let args = self.create_object_call_env(
-                        Rc::clone(&object),
-                        &call_expr.arguments,
-                        environment,
-                    );
-                    let function = downcast_any!(function => BuiltIn);
-                    (function.function)(&args)
-                } else {
-                    Rc::new(EvalError::new(format!(
-                        "unknown function {}",
-                        function_name
-                    )))
-                }
-            }
-            Type::String => {
-                let string = downcast_any!(object => Str);
-                if let Some(function) = string.functions.get(&function_name) {
-                    let args = self.create_object_call_env(
-                        Rc::clone(&object),
-                        &call_expr.arguments,
-                        environment,
-                    );
-                    let function = downcast_any!(function => BuiltIn);
-                    (function.function)(&args)
-                } else {
-                    Rc::new(EvalError::new(format!(
-                        "unknown function {}",
-                        function_name
-                    )))
-                }
-            }
-            typ => Rc::new(EvalError::new(format!(
-                "{} does not support functions for now",
-                typ
-            ))),
+        let function = operations::method(&self.methods, &object, &function_name);
+        if function.is_error() {
+            return self.locate_error(function, &call_expr.location);
         }
+        let mut args = match self.eval_arguments(call_expr, environment) {
+            Ok(args) => args,
+            Err(error) => return error,
+        };
+        if object.get_type() != Type::Module {
+            args.insert(0, object);
+        }
+        let result = self.apply_function(function, args);
+        self.locate_error(result, &call_expr.location)
     }
 
-    fn create_object_call_env(
+    fn eval_call(
         &self,
-        object: ObjectRef,
-        arguments: &[NodeRef],
+        call_expr: &CallExpr,
         environment: EnvironmentRef,
-    ) -> Vec<ObjectRef> {
-        let mut args: Vec<ObjectRef> = vec![object];
-        for arg in arguments.iter() {
-            if let Some(arg) = self.eval(Some(arg), Rc::clone(&environment)) {
-                args.push(arg)
-            } else {
-                return vec![Rc::new(EvalError::new(
-                    "error on parse arguments".to_string(),
-                ))];
+    ) -> Result<(Value, Vec<Value>), Value> {
+        let function = self.eval_expr(&call_expr.function, Rc::clone(&environment));
+        if function.is_error() {
+            return Err(function);
+        }
+        Ok((function, self.eval_arguments(call_expr, environment)?))
+    }
+
+    fn eval_arguments(
+        &self,
+        call_expr: &CallExpr,
+        environment: EnvironmentRef,
+    ) -> Result<Vec<Value>, Value> {
+        let mut args = Vec::with_capacity(call_expr.arguments.len());
+        for (arg, location) in call_expr.arguments.iter().zip(&call_expr.locations) {
+            let arg = self.eval_expr(arg, Rc::clone(&environment));
+            if arg.is_error() {
+                return Err(self.locate_error(arg, location));
             }
+            args.push(arg);
         }
-        args
+        Ok(args)
     }
 
     #[inline]
-    fn eval_infix(&self, node: &NodeRef, environment: EnvironmentRef) -> ObjectRef {
-        let infix_expr = downcast_any!(node => InfixExpr);
-        let left = self.eval(Some(&infix_expr.left), Rc::clone(&environment));
-        if infix_expr.right.get_op_code() == OpCode::Call
-            && ".".cmp(&infix_expr.operator) == Ordering::Equal
-        {
-            return self.eval_object_function(
-                left.unwrap(),
-                &infix_expr.right,
-                Rc::clone(&environment),
-            );
+    fn locate_error(&self, result: Value, location: &Location) -> Value {
+        match &result {
+            Value::Error(error) if error.location.is_none() => {
+                Value::from(error.located(location.clone()))
+            }
+            _ => result,
         }
-        let right = self.eval(Some(&infix_expr.right), environment);
-        match (left, right) {
-            (Some(left), Some(right)) => match (left.get_type(), right.get_type()) {
-                (Type::Int, Type::Int) => {
-                    let left = downcast_any!(left => Integer);
-                    let right = downcast_any!(right => Integer);
-                    match &infix_expr.operator[..] {
-                        "+" => Rc::new(Integer::new(left.value + right.value)),
-                        "-" => Rc::new(Integer::new(left.value - right.value)),
-                        "*" => Rc::new(Integer::new(left.value * right.value)),
-                        "/" => Rc::new(Integer::new(left.value / right.value)),
-                        "%" => Rc::new(Integer::new(left.value % right.value)),
-                        "<<" => Rc::new(Integer::new(left.value << right.value)),
-                        ">>" => Rc::new(Integer::new(left.value >> right.value)),
-                        "&" => Rc::new(Integer::new(left.value & right.value)),
-                        "|" => Rc::new(Integer::new(left.value | right.value)),
-                        "^" => Rc::new(Integer::new(left.value ^ right.value)),
-                        ">" => Rc::new(Boolean::new(left.value > right.value)),
-                        "<" => Rc::new(Boolean::new(left.value < right.value)),
-                        ">=" => Rc::new(Boolean::new(left.value >= right.value)),
-                        "<=" => Rc::new(Boolean::new(left.value <= right.value)),
-                        "!=" => Rc::new(Boolean::new(left.value != right.value)),
-                        "==" => Rc::new(Boolean::new(left.value == right.value)),
-                        _ => Rc::new(EvalError::new(format!(
-                            "unsoported operation {} {} {}",
-                            left.get_type(),
-                            infix_expr.operator,
-                            right.get_type()
-                        ))),
-                    }
-                }
-                (Type::Float, Type::Float) => {
-                    let left = downcast_any!(left => Float);
-                    let right = downcast_any!(right => Float);
-                    match &infix_expr.operator[..] {
-                        "+" => Rc::new(Float::new(left.value + right.value)),
-                        "-" => Rc::new(Float::new(left.value - right.value)),
-                        "*" => Rc::new(Float::new(left.value * right.value)),
-                        "/" => Rc::new(Float::new(left.value / right.value)),
-                        ">" => Rc::new(Boolean::new(left.value > right.value)),
-                        "<" => Rc::new(Boolean::new(left.value < right.value)),
-                        ">=" => Rc::new(Boolean::new(left.value >= right.value)),
-                        "<=" => Rc::new(Boolean::new(left.value <= right.value)),
-                        "!=" => Rc::new(Boolean::new(left.value != right.value)),
-                        "==" => Rc::new(Boolean::new(left.value == right.value)),
-                        _ => Rc::new(EvalError::new(format!(
-                            "unsoported operation {} {} {}",
-                            left.get_type(),
-                            infix_expr.operator,
-                            right.get_type()
-                        ))),
-                    }
-                }
-                (Type::Bool, Type::Bool) => {
-                    let left = downcast_any!(left => Boolean);
-                    let right = downcast_any!(right => Boolean);
-                    match &infix_expr.operator[..] {
-                        "&&" => Rc::new(Boolean::new(left.value && right.value)),
-                        "||" => Rc::new(Boolean::new(left.value || right.value)),
-                        _ => Rc::new(EvalError::new(format!(
-                            "unsoported operation {} {} {}",
-                            left.get_type(),
-                            infix_expr.operator,
-                            right.get_type()
-                        ))),
-                    }
-                }
-                (Type::String, Type::String) => {
-                    let left = downcast_any!(left => Str);
-                    let right = downcast_any!(right => Str);
-                    match &infix_expr.operator[..] {
-                        "+" => Rc::new(Str::new(format!("{}{}", left.value, right.value))),
-                        "!=" => Rc::new(Boolean::new(
-                            left.value.cmp(&right.value) != Ordering::Equal,
-                        )),
-                        "==" => Rc::new(Boolean::new(
-                            left.value.cmp(&right.value) == Ordering::Equal,
-                        )),
-                        ">" => Rc::new(Boolean::new(
-                            left.value.cmp(&right.value) == Ordering::Greater,
-                        )),
-                        "<" => {
-                            Rc::new(Boolean::new(left.value.cmp(&right.value) == Ordering::Less))
-                        }
-                        _ => Rc::new(EvalError::new(format!(
-                            "unsoported operation {} {} {}",
-                            left.get_type(),
-                            infix_expr.operator,
-                            right.get_type()
-                        ))),
-                    }
-                }
-                (left, right) => Rc::new(EvalError::new(format!(
-                    "unsoported operation {} {} {}",
-                    left, infix_expr.operator, right
-                ))),
-            },
-            _ => Rc::new(EvalError::new("unsoported operation ".to_string())),
+    }
+
+    #[inline]
+    fn eval_infix(&self, infix_expr: &InfixExpr, environment: EnvironmentRef) -> Value {
+        let left = self.eval_expr(&infix_expr.left, Rc::clone(&environment));
+        if left.is_error() {
+            return left;
+        }
+        if let Expr::Identifier(member) = &*infix_expr.right {
+            if infix_expr.operator == "." {
+                let result = match &left {
+                    Value::Module(module) => match module.get(&member.value) {
+                        Some(value) => value.clone(),
+                        None => Value::error(
+                            ErrorKind::Name,
+                            format!("module {} has no member '{}'", module.name, member),
+                        ),
+                    },
+                    _ => Value::error(
+                        ErrorKind::Type,
+                        format!("unsoported operation {} . {}", left.get_type(), member),
+                    ),
+                };
+                return self.locate_error(result, &infix_expr.location);
+            }
         }
+        if let Expr::Call(call_expr) = &*infix_expr.right {
+            if infix_expr.operator == "." {
+                return self.eval_object_function(left, call_expr, environment);
+            }
+        }
+        let right = self.eval_expr(&infix_expr.right, environment);
+        if right.is_error() {
+            return right;
+        }
+        let result = match BinaryOperator::from_operator(infix_expr.operator) {
+            Some(operator) => operations::binary(operator, left, right),
+            None => Value::error(
+                ErrorKind::Type,
+                format!(
+                    "unsoported operation {} {} {}",
+                    left.get_type(),
+                    infix_expr.operator,
+                    right.get_type()
+                ),
+            ),
+        };
+        self.locate_error(result, &infix_expr.location)
     }
 
     #[inline]
-    fn eval_array(&self, node: &NodeRef, enviroment: EnvironmentRef) -> ObjectRef {
-        let array_expr = downcast_any!(node => ArrayExpr);
-        let mut values: Vec<ObjectRef> = Vec::with_capacity(10);
+    fn eval_array(&self, array_expr: &ArrayExpr, enviroment: EnvironmentRef) -> Value {
+        let mut values: Vec<Value> = Vec::with_capacity(10);
         for expr in array_expr.values.iter() {
-            let evaluated = self.eval(Some(expr), Rc::clone(&enviroment));
-            if self.is_error(&evaluated) {
-                return evaluated.unwrap();
+            let evaluated = self.eval_expr(expr, Rc::clone(&enviroment));
+            if evaluated.is_error() {
+                return evaluated;
             }
-            values.push(evaluated.unwrap());
+            values.push(evaluated);
         }
-        Rc::new(Array::new(RefCell::new(values)))
+        Value::array(values)
     }
 
     #[inline]
-    fn eval_if(&self, node: &NodeRef, environment: EnvironmentRef) -> Option<ObjectRef> {
-        let if_expr = downcast_any!(node => IfExpr);
-        let condition = self.eval(Some(&if_expr.condition), Rc::clone(&environment));
-        if self.is_error(&condition) {
-            return condition;
-        }
-        match downcast_option!(condition => Boolean) {
-            Some(condition) => {
-                if condition.value {
-                    self.eval(Some(&if_expr.consequence), environment)
-                } else if let Some(ref el_if) = if_expr.el_if {
-                    self.eval(Some(el_if), environment)
+    fn eval_if(&self, if_expr: &IfExpr, environment: EnvironmentRef) -> Value {
+        let condition = self.eval_expr(&if_expr.condition, Rc::clone(&environment));
+        match condition {
+            Value::Bool(true) => self.eval_block(&if_expr.consequence, environment),
+            Value::Bool(false) => {
+                if let Some(ref el_if) = if_expr.el_if {
+                    self.eval_if(el_if, environment)
                 } else if let Some(ref alternative) = if_expr.alternative {
-                    self.eval(Some(alternative), environment)
+                    self.eval_block(alternative, environment)
                 } else {
-                    None
+                    Value::Nil
                 }
             }
-            None => None,
+            Value::Error(_) => condition,
+            _ => Value::Nil,
         }
     }
 
     #[inline]
-    fn eval_while(&self, node: &NodeRef, environment: EnvironmentRef) -> Option<ObjectRef> {
-        let if_expr = downcast_any!(node => WhileExpr);
-        let mut result: Option<ObjectRef> = None;
+    fn eval_while(&self, while_expr: &WhileExpr, environment: EnvironmentRef) -> Value {
+        let mut result = Value::Nil;
         loop {
-            let condition = self.eval(Some(&if_expr.condition), Rc::clone(&environment));
-            if self.is_error(&condition) {
-                return condition;
-            }
-            match downcast_option!(condition => Boolean) {
-                Some(condition) => {
-                    if condition.value {
-                        result = self.eval(Some(&if_expr.consequence), Rc::clone(&environment));
-                        if let Some(ref result) = result {
-                            if result.get_type() == Type::Return {
-                                break;
-                            } else if result.get_type() == Type::Error {
-                                process::exit(1);
-                            }
-                        } else {
-                            break;
-                        }
-                    } else {
+            let condition = self.eval_expr(&while_expr.condition, Rc::clone(&environment));
+            match condition {
+                Value::Bool(true) => {
+                    result = self.eval_block(&while_expr.consequence, Rc::clone(&environment));
+                    if result.get_type() == Type::Return || result.is_error() {
                         break;
                     }
                 }
-                None => break,
+                Value::Error(_) => return condition,
+                _ => break,
             }
         }
         result
     }
 
-    #[inline]
-    fn apply_function(
-        &self,
-        function: ObjectRef,
-        arguments: Vec<Option<ObjectRef>>,
-    ) -> Option<ObjectRef> {
-        if let Some(function) = downcast!(function => Function) {
-            let new_env = self.create_function_environment(function, arguments);
-            let body = downcast_any!(function.body => BlockStatement);
-            let body = Some(self.eval_statements(&body.statements, new_env));
-            if self.is_error(&body) {
-                return body;
-            }
-            self.extract_ret_val(body)
-        } else if let Some(fnc) = downcast!(function => BuiltIn) {
-            let mut args: Vec<ObjectRef> = Vec::with_capacity(3);
-            for arg in arguments.iter() {
-                args.push(Rc::clone(arg.as_ref().unwrap()))
+    fn eval_try(&self, try_expr: &TryExpr, environment: EnvironmentRef) -> Value {
+        let mut result = self.protected(true, || {
+            self.eval_block(&try_expr.body, Rc::clone(&environment))
+        });
+        if let (Value::Error(error), Some(catch)) = (&result, &try_expr.catch) {
+            self.assign(&catch.name, error.caught(), &environment);
+            result = self.protected(try_expr.finally.is_some(), || {
+                self.eval_block(&catch.body, Rc::clone(&environment))
+            });
+        }
+        if let Some(ref finally) = try_expr.finally {
+            // an error or a ret in the finally block wins over the result
+            let finished = self.eval_block(finally, environment);
+            if finished.get_type() == Type::Return || finished.is_error() {
+                return finished;
             }
-            Some((fnc.function)(&args))
-        } else {
-            None
         }
+        result
     }
 
     #[inline]
-    fn extract_ret_val(&self, evaluated: Option<ObjectRef>) -> Option<ObjectRef> {
-        if let Some(ret) = downcast_option!(evaluated.as_ref() => Ret) {
-            Some(Rc::clone(&ret.val))
-        } else {
-            evaluated
-        }
+    fn protected(&self, protected: bool, eval: impl FnOnce() -> Value) -> Value {
+        let tries = self.tries.get();
+        self.tries.set(tries + protected as usize);
+        let result = eval();
+        self.tries.set(tries);
+        result
     }
 
     #[inline]
-    fn create_function_environment(
-        &self,
-        function: &Function,
-        arguments: Vec<Option<ObjectRef>>,
-    ) -> EnvironmentRef {
-        let mut env = Environment::new(Some(Rc::clone(&function.environment)));
-        for (idx, arg) in arguments.iter().enumerate() {
-            let argument_name = function.parameters.get(idx).as_ref().unwrap().to_string();
-            env.set_variable(argument_name, Rc::clone(arg.as_ref().unwrap()));
+    fn apply_function(&self, function: Value, arguments: Vec<Value>) -> Value {
+        let mut result = self.call_function(function, arguments);
+        // tail calls come back as values so the loop reuses this stack frame
+        while let Value::TailCall(tail_call) = result {
+            let next = self.call_function(tail_call.function.clone(), tail_call.arguments.clone());
+            result = self.locate_error(next, &tail_call.location);
         }
-        Rc::new(RefCell::new(env))
+        result
     }
 
-    #[inline]
-    fn eval_return_smtmt(&self, node: &NodeRef, environment: EnvironmentRef) -> Option<ObjectRef> {
-        let ret_stmt = downcast_any!(node => RetStatement);
-        match &ret_stmt.value {
-            Some(expr) => {
-                let val = self.eval(Some(expr), environment);
-                match val {
-                    _ if self.is_error(&val) => val,
-                    Some(val) => Some(Rc::new(Ret::new(val))),
-                    None => None,
+    fn call_function(&self, function: Value, arguments: Vec<Value>) -> Value {
+        match &function {
+            Value::Function(function) => {
+                if self.depth.get() >= self.max_depth {
+                    return Value::error(
+                        ErrorKind::Recursion,
+                        format!(
+                            "maximum recursion depth exceeded calling {}",
+                            function.describe()
+                        ),
+                    );
                 }
+                let new_env = match self.create_function_environment(function, arguments) {
+                    Ok(env) => env,
+                    Err(error) => return error,
+                };
+                self.depth.set(self.depth.get() + 1);
+                let tries = self.tries.replace(0);
+                let body = self.eval_block(&function.body, new_env);
+                self.tries.set(tries);
+                self.depth.set(self.depth.get() - 1);
+                self.extract_ret_val(body)
             }
-            None => None,
+            Value::BuiltIn(built_in) => built_in.call(&arguments, &|function, arguments| {
+                self.apply_function(function, arguments)
+            }),
+            _ => Value::error(
+                ErrorKind::Type,
+                format!("{} is not a function", function.get_type()),
+            ),
         }
     }
 
-    #[inline(always)]
-    fn extract_array_index(&self, obj: ObjectRef, position: usize) -> Option<ObjectRef> {
-        let array = downcast_any!(obj => Array);
-        let values = array.values.borrow();
-        if position < values.len() {
-            Some(Rc::clone(&values[position]))
-        } else {
-            Some(Rc::new(EvalError::new(format!(
-                "invalid index {}",
-                position
-            ))))
+    #[inline]
+    fn extract_ret_val(&self, evaluated: Value) -> Value {
+        match evaluated {
+            Value::Return(ret) => ret.val.clone(),
+            evaluated => evaluated,
         }
     }
 
-    #[inline(always)]
-    fn extract_string_index(&self, obj: ObjectRef, position: usize) -> Option<ObjectRef> {
-        let string = downcast_any!(obj => Str);
-        let value = &string.value;
-        if position < value.len() {
-            Some(Rc::new(Str::new(
-                value.chars().nth(position).unwrap().to_string(),
-            )))
-        } else {
-            Some(Rc::new(EvalError::new(format!(
-                "invalid string position {}",
-                position
-            ))))
+    #[inline]
+    fn create_function_environment(
+        &self,
+        function: &Function,
+        arguments: Vec<Value>,
+    ) -> Result<EnvironmentRef, Value> {
+        let parameters = &function.parameters;
+        let rest = parameters.last().is_some_and(|parameter| parameter.rest);
+        let positional = parameters.len() - rest as usize;
+        let required = parameters
+            .iter()
+            .filter(|parameter| parameter.default.is_none() && !parameter.rest)
+            .count();
+        if arguments.len() < required || (!rest && arguments.len() > positional) {
+            let expected = match (rest, required == positional) {
+                (true, _) => format!("at least {}", required),
+                (false, true) => required.to_string(),
+                (false, false) => format!("{} to {}", required, positional),
+            };
+            return Err(Value::error(
+                ErrorKind::Argument,
+                format!(
+                    "{} expects {} arguments but got {}",
+                    function.describe(),
+                    expected,
+                    arguments.len()
+                ),
+            ));
         }
+        let env = Environment::shared(Environment::frame(
+            function.slots,
+            Rc::clone(&function.environment),
+        ));
+        let mut arguments = arguments.into_iter();
+        for (index, parameter) in parameters.iter().enumerate() {
+            let value = if parameter.rest {
+                Value::array(arguments.by_ref().collect())
+            } else if let Some(argument) = arguments.next() {
+                argument
+            } else {
+                let value = match &parameter.default {
+                    Some(default) => self.eval_expr(default, Rc::clone(&env)),
+                    None => Value::Nil,
+                };
+                if value.is_error() {
+                    return Err(value);
+                }
+                value
+            };
+            env.borrow_mut().slots[index] = Some(value);
+        }
+        Ok(env)
     }
 
-    //TODO: improve this code and check errors
     #[inline]
-    fn eval_index(&self, node: &NodeRef, environment: EnvironmentRef) -> Option<ObjectRef> {
-        let index_expr = downcast_any!(node => IndexExpr);
-
-        let left = &index_expr.left;
-        if let Some(index) = self.eval(Some(&index_expr.index), Rc::clone(&environment)) {
-            match index.get_type() {
-                Type::Int => {
-                    let position = downcast_any!(index => Integer).value as usize;
-                    match left.get_op_code() {
-                        OpCode::Identifier => {
-                            let eval = self.eval(Some(&index_expr.left), environment);
-                            if let Some(eval) = eval {
-                                if eval.get_type() == Type::Array {
-                                    self.extract_array_index(eval, position)
-                                } else if eval.get_type() == Type::String {
-                                    self.extract_string_index(eval, position)
-                                } else {
-                                    todo!()
-                                }
-                            } else {
-                                eval
-                            }
-                        }
-                        OpCode::Array => {
-                            let eval = self.eval(Some(left), environment);
-                            if self.is_error(&eval) {
-                                return eval;
-                            }
-                            self.extract_array_index(eval.unwrap(), position)
-                        }
-                        OpCode::String => {
-                            let string = self.eval(Some(left), environment);
-                            if self.is_error(&string) {
-                                return string;
-                            }
-                            self.extract_string_index(string.unwrap(), position)
-                        }
-                        _ => Some(Rc::new(EvalError::new(format!(
-                            "unsuported operation {:?}[{}]",
-                            left.get_op_code(),
-                            position
-                        )))),
-                    }
+    fn eval_return_smtmt(&self, ret_stmt: &RetStatement, environment: EnvironmentRef) -> Value {
+        match &ret_stmt.value {
+            Some(Expr::Call(call_expr)) if self.depth.get() > 0 && self.tries.get() == 0 => {
+                match self.eval_call(call_expr, environment) {
+                    Ok((function, args)) => Value::TailCall(Rc::new(TailCall::new(
+                        function,
+                        args,
+                        call_expr.location.clone(),
+                    ))),
+                    Err(error) => error,
                 }
-                Type::String => {
-                    let key = &downcast_any!(index => Str).value;
-                    match left.get_op_code() {
-                        OpCode::Hash => {
-                            let hash = self.eval(Some(left), environment);
-                            if self.is_error(&hash) {
-                                return hash;
-                            }
-                            self.extract_hash_value(&hash.unwrap(), key)
-                        }
-                        OpCode::Identifier => {
-                            let evalueted = self.eval(Some(left), environment);
-                            match evalueted {
-                                Some(expec) if expec.get_type() == Type::Hash => {
-                                    self.extract_hash_value(&expec, key)
-                                }
-                                _ => Some(Rc::new(EvalError::new(format!(
-                                    "unsuported operation {:?}[{}]",
-                                    left.get_op_code(),
-                                    key
-                                )))),
-                            }
-                        }
-                        _ => Some(Rc::new(EvalError::new(format!(
-                            "unsuported operation {:?}[{}]",
-                            left.get_op_code(),
-                            key
-                        )))),
-                    }
+            }
+            Some(expr) => {
+                let val = self.eval_expr(expr, environment);
+                if val.is_error() {
+                    val
+                } else {
+                    Value::Return(Rc::new(Ret::new(val)))
                 }
-                _ => Some(Rc::new(EvalError::new(
-                    "operation not supported".to_string(),
-                ))),
             }
-        } else {
-            Some(Rc::new(EvalError::new(
-                "operation not supported".to_string(),
-            )))
-        }
-    }
-
-    fn extract_hash_value(&self, hash_obj: &ObjectRef, key: &str) -> Option<ObjectRef> {
-        let hash_obj = downcast_any!(hash_obj => HashObj);
-        if let Some(value) = hash_obj.get(key) {
-            Some(Rc::clone(value))
-        } else {
-            Some(Rc::new(EvalError::new(format!("unknown key {}", key))))
+            None => Value::Return(Rc::new(Ret::new(Value::Nil))),
         }
     }
 
     #[inline]
-    fn is_error(&self, to_check: &Option<ObjectRef>) -> bool {
-        match to_check {
-            Some(check) if check.get_type() == Type::Error => true,
-            None => true,
-            _ => false,
+    fn eval_index(&self, index_expr: &IndexExpr, environment: EnvironmentRef) -> Value {
+        let left = self.eval_expr(&index_expr.left, Rc::clone(&environment));
+        if left.is_error() {
+            return left;
+        }
+        let index = self.eval_expr(&index_expr.index, environment);
+        if index.is_error() {
+            return index;
         }
+        let result = operations::index(&left, &index);
+        self.locate_error(result, &index_expr.location)
     }
 
     #[inline]
-    fn eval_hash(
-        &self,
-        node: &NodeRef,
-        environment: Rc<RefCell<Environment>>,
-    ) -> Option<Rc<dyn Object>> {
-        let hash_expr = downcast_any!(node => HashExpr);
+    fn eval_hash(&self, hash_expr: &HashExpr, environment: EnvironmentRef) -> Value {
         let mut hash_obj = HashObj::default();
-        for (key, value) in hash_expr.pairs.iter() {
-            let value = self.eval(Some(value), Rc::clone(&environment));
-            if self.is_error(&value) {
+        // in source order, like the compiler
+        for key in hash_expr.keys.iter() {
+            let value = self.eval_expr(&hash_expr.pairs[key], Rc::clone(&environment));
+            if value.is_error() {
                 return value;
             }
-            hash_obj.put(key.clone(), Rc::clone(value.as_ref().unwrap()));
+            hash_obj.put(key.clone(), value);
         }
-        Some(Rc::new(hash_obj))
+        Value::from(hash_obj)
     }
 }
 
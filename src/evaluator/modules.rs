@@ -0,0 +1,186 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crate::{
+    ast::{
+        node::{ImportStmt, Stmt},
+        Program,
+    },
+    lexer::{Lexer, Location},
+    parser::Parser,
+};
+
+use super::{
+    environment::{Environment, EnvironmentRef},
+    objects::{ErrorKind, Module, Value},
+    Evaluator,
+};
+
+#[derive(Default)]
+pub(crate) struct ModuleLoader {
+    modules: RefCell<HashMap<PathBuf, Value>>,
+    loading: RefCell<Vec<PathBuf>>,
+}
+
+impl ModuleLoader {
+    // the script that runs first is never loaded here, it is registered by its first
+    // import so importing it back is reported as a cycle instead of running it again
+    pub fn load(
+        &self,
+        import: &str,
+        location: &Location,
+        eval_module: impl FnOnce(Program, &Path) -> Value,
+    ) -> Value {
+        let entry = match self.loading.borrow().is_empty() {
+            true => fs::canonicalize(&*location.file().name())
+                .ok()
+                .filter(|path| path.is_file()),
+            false => None,
+        };
+        let Some(entry) = entry else {
+            return self.load_module(import, location, eval_module);
+        };
+        self.loading.borrow_mut().push(entry);
+        let module = self.load_module(import, location, eval_module);
+        self.loading.borrow_mut().pop();
+        module
+    }
+
+    fn load_module(
+        &self,
+        import: &str,
+        location: &Location,
+        eval_module: impl FnOnce(Program, &Path) -> Value,
+    ) -> Value {
+        let path = match fs::canonicalize(resolve_path(import, location)) {
+            Ok(path) => path,
+            Err(e) => {
+                return Value::error(
+                    ErrorKind::Import,
+                    format!("cannot import '{}' in {}: {}", import, location, e),
+                )
+            }
+        };
+        if let Some(module) = self.modules.borrow().get(&path) {
+            return module.clone();
+        }
+        let cycle_start = self.loading.borrow().iter().position(|file| *file == path);
+        if let Some(start) = cycle_start {
+            let mut cycle: Vec<String> = self.loading.borrow()[start..]
+                .iter()
+                .map(|file| file.display().to_string())
+                .collect();
+            cycle.push(path.display().to_string());
+            return Value::error(
+                ErrorKind::Import,
+                format!(
+                    "import cycle detected in {}: {}",
+                    location,
+                    cycle.join(" -> ")
+                ),
+            );
+        }
+        let opened = File::open(&path).and_then(|file| match file.metadata()?.is_dir() {
+            true => Err(io::ErrorKind::IsADirectory.into()),
+            false => Ok(file),
+        });
+        let file = match opened {
+            Ok(file) => file,
+            Err(e) => {
+                return Value::error(
+                    ErrorKind::Import,
+                    format!("cannot import '{}' in {}: {}", import, location, e),
+                )
+            }
+        };
+        let lexer = Lexer::from_reader(BufReader::new(file), Rc::new(path.display().to_string()));
+        let program = Parser::new(lexer).parse_program();
+        if !program.errors.is_empty() {
+            return Value::error(ErrorKind::Import, program.errors.join("\n"));
+        }
+        self.loading.borrow_mut().push(path.clone());
+        let module = eval_module(program, &path);
+        self.loading.borrow_mut().pop();
+        if !module.is_error() {
+            self.modules.borrow_mut().insert(path, module.clone());
+        }
+        module
+    }
+}
+
+impl Evaluator {
+    pub(super) fn eval_import(&self, import: &ImportStmt, environment: EnvironmentRef) -> Value {
+        let module = self
+            .modules
+            .load(&import.path, &import.location, |program, path| {
+                self.eval_module(program, path)
+            });
+        if !module.is_error() {
+            self.store(
+                import.slot.get(),
+                &import.name(),
+                module.clone(),
+                &environment,
+            );
+        }
+        module
+    }
+
+    fn eval_module(&self, program: Program, path: &Path) -> Value {
+        let exports = exported_names(&program);
+        let environment = Environment::shared(Environment::default());
+        let result = self.eval(&program, Rc::clone(&environment));
+        if result.is_error() {
+            return result;
+        }
+        let variables = environment.borrow();
+        build_module(path, &exports, &variables)
+    }
+}
+
+pub(crate) fn exported_names(program: &Program) -> Vec<String> {
+    program
+        .statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Stmt::Export(export) => export.name(),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(crate) fn build_module(path: &Path, exports: &[String], environment: &Environment) -> Value {
+    let bindings: HashMap<String, Value> = environment
+        .variables
+        .iter()
+        .filter(|(name, _)| exports.is_empty() || exports.contains(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Value::Module(Rc::new(Module::new(name, bindings)))
+}
+
+fn resolve_path(import: &str, location: &Location) -> PathBuf {
+    let mut path = PathBuf::from(import);
+    if path.extension().is_none() {
+        path.set_extension("mila");
+    }
+    if path.is_absolute() {
+        return path;
+    }
+    let file = location.file().name();
+    let importer = Path::new(&*file);
+    match importer.parent() {
+        Some(dir) if importer.is_file() => dir.join(path),
+        _ => path,
+    }
+}
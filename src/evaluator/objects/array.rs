@@ -1,121 +1,119 @@
-use std::{any::Any, cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
 
-use crate::{builtin_map, downcast, downcast_any, evaluator::BuiltInMap};
+use crate::{builtin_map, evaluator::BuiltInMap};
 
-use super::{built_in::BuiltIn, eval_error::EvalError, integer::Integer, Object, ObjectRef, Type};
+use super::{built_in::BuiltIn, show_once, ErrorKind, Value};
 
 pub struct Array {
-    pub values: RefCell<Vec<ObjectRef>>,
-    pub functions: BuiltInMap,
+    pub values: RefCell<Vec<Value>>,
 }
 
 //TODO: refactoring array functions
 impl Array {
-    pub fn new(values: RefCell<Vec<ObjectRef>>) -> Self {
-        let functions: BuiltInMap = builtin_map!(
-            "push" => Rc::new(BuiltIn::new(push)),
-            "replace" => Rc::new(BuiltIn::new(replace)),
-            "pop" => Rc::new(BuiltIn::new(pop)),
-            "remove" => Rc::new(BuiltIn::new(remove)),
-            "push_array" => Rc::new(BuiltIn::new(push_array))
-        );
-        Self { values, functions }
+    pub fn new(values: RefCell<Vec<Value>>) -> Self {
+        Self { values }
+    }
+
+    pub fn methods() -> BuiltInMap {
+        builtin_map!(
+            "push" => Value::BuiltIn(BuiltIn::new(push)),
+            "replace" => Value::BuiltIn(BuiltIn::new(replace)),
+            "pop" => Value::BuiltIn(BuiltIn::new(pop)),
+            "remove" => Value::BuiltIn(BuiltIn::new(remove)),
+            "push_array" => Value::BuiltIn(BuiltIn::new(push_array))
+        )
     }
 }
 
-fn push(args: &[ObjectRef]) -> ObjectRef {
+fn push(args: &[Value]) -> Value {
     if args.len() < 2 {
-        return Rc::new(EvalError::new("expected at least one argument".to_string()));
+        return Value::error(
+            ErrorKind::Argument,
+            "expected at least one argument".to_string(),
+        );
     }
 
     let mut args_iter = args.iter();
-    let arr = downcast_any!(args_iter.next().unwrap() => Array);
+    let arr = args_iter.next().unwrap().as_array().unwrap();
 
     for arg in args_iter {
-        arr.values.borrow_mut().push(Rc::clone(arg))
+        arr.values.borrow_mut().push(arg.clone())
     }
-    Rc::clone(&args[0])
+    args[0].clone()
 }
 
-fn push_array(args: &[ObjectRef]) -> ObjectRef {
+fn push_array(args: &[Value]) -> Value {
     if args.len() != 2 {
-        return Rc::new(EvalError::new("expected one argument".to_string()));
+        return Value::error(ErrorKind::Argument, "expected one argument".to_string());
     }
 
     let mut args_iter = args.iter();
-    let arr = downcast_any!(args_iter.next().unwrap() => Array);
-    if let Some(other_arr) = downcast!(args_iter.next().unwrap() => Array) {
-        for val in other_arr.values.borrow().iter() {
-            arr.values.borrow_mut().push(Rc::clone(val));
-        }
+    let arr = args_iter.next().unwrap().as_array().unwrap();
+    if let Some(other_arr) = args_iter.next().unwrap().as_array() {
+        let values = other_arr.values.borrow().clone();
+        arr.values.borrow_mut().extend(values);
     }
-    Rc::clone(&args[0])
+    args[0].clone()
 }
 
-fn pop(args: &[ObjectRef]) -> ObjectRef {
+fn pop(args: &[Value]) -> Value {
     if args.len() > 1 {
-        return Rc::new(EvalError::new("expected no arguments".to_string()));
+        return Value::error(ErrorKind::Argument, "expected no arguments".to_string());
     }
-    let arr = downcast_any!(args.iter().next().unwrap() => Array);
+    let arr = args[0].as_array().unwrap();
 
     if let Some(value) = arr.values.borrow_mut().pop() {
         value
     } else {
-        Rc::new(EvalError::new("array is empty".to_string()))
+        Value::error(ErrorKind::Index, "array is empty".to_string())
     }
 }
 
-fn remove(args: &[ObjectRef]) -> ObjectRef {
+fn remove(args: &[Value]) -> Value {
     if args.len() != 2 {
-        return Rc::new(EvalError::new("expected exact one argument".to_string()));
-    }
-    let mut args_iter = args.iter();
-    let arr = downcast_any!(args_iter.next().unwrap() => Array);
-    let position = args_iter.next().unwrap();
-    if position.get_type() != Type::Int {
-        return Rc::new(EvalError::new("index in array only be a int".to_string()));
+        return Value::error(
+            ErrorKind::Argument,
+            "expected exact one argument".to_string(),
+        );
     }
-    let position_cast = downcast_any!(position => Integer).value as usize;
-    if position_cast >= arr.values.borrow().len() {
-        return Rc::new(EvalError::new(format!("invalid position {}", position)));
+    let arr = args[0].as_array().unwrap();
+    let position = match args[1].as_int() {
+        Some(position) => position as usize,
+        None => return Value::error(ErrorKind::Type, "index in array only be a int".to_string()),
+    };
+    if position >= arr.values.borrow().len() {
+        return Value::error(ErrorKind::Index, format!("invalid position {}", args[1]));
     }
-    arr.values.borrow_mut().remove(position_cast)
+    arr.values.borrow_mut().remove(position)
 }
 
-fn replace(args: &[ObjectRef]) -> ObjectRef {
+fn replace(args: &[Value]) -> Value {
     if args.len() != 3 {
-        return Rc::new(EvalError::new("expected two arguments".to_string()));
-    }
-    let mut args_iter = args.iter();
-    let arr = downcast_any!(args_iter.next().unwrap() => Array);
-    let position = args_iter.next().unwrap();
-    if position.get_type() != Type::Int {
-        return Rc::new(EvalError::new("position has to be a int".to_string()));
+        return Value::error(ErrorKind::Argument, "expected two arguments".to_string());
     }
-    let position = downcast_any!(position => Integer).value as usize;
+    let arr = args[0].as_array().unwrap();
+    let position = match args[1].as_int() {
+        Some(position) => position as usize,
+        None => return Value::error(ErrorKind::Type, "position has to be a int".to_string()),
+    };
     if position > arr.values.borrow().len() {
-        return Rc::new(EvalError::new(format!(
-            "invalid index to replace {}",
-            position
-        )));
+        return Value::error(
+            ErrorKind::Index,
+            format!("invalid index to replace {}", position),
+        );
     }
-    let value = args_iter.next().unwrap();
-    arr.values.borrow_mut()[position] = Rc::clone(value);
-    Rc::clone(&args[0])
+    arr.values.borrow_mut()[position] = args[2].clone();
+    args[0].clone()
 }
 
-impl Object for Array {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_type(&self) -> Type {
-        Type::Array
+impl Display for Array {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        show_once(self, f, |f| self.show(f))
     }
 }
 
-impl Display for Array {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Array {
+    fn show(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut buffer = String::new();
         buffer.push('[');
         buffer.push_str(
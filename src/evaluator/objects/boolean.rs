@@ -1,29 +0,0 @@
-use std::{any::Any, fmt::Display};
-
-use super::{Object, Type};
-
-pub struct Boolean {
-    pub value: bool,
-}
-
-impl Boolean {
-    pub fn new(value: bool) -> Self {
-        Self { value }
-    }
-}
-
-impl Object for Boolean {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_type(&self) -> Type {
-        Type::Bool
-    }
-}
-
-impl Display for Boolean {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.value)
-    }
-}
@@ -1,26 +1,42 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use super::{Object, ObjectRef, Type};
+use super::Value;
 
-pub type BuildInFn = fn(&[ObjectRef]) -> ObjectRef;
+pub type BuildInFn = fn(&[Value]) -> Value;
+// calls a function of the script with the given arguments
+pub type Caller<'a> = &'a dyn Fn(Value, Vec<Value>) -> Value;
+pub type CallbackFn = fn(&[Value], Caller) -> Value;
 
+#[derive(Clone, Copy)]
+pub enum Native {
+    Plain(BuildInFn),
+    // built ins that call back into the interpreter running them
+    Callback(CallbackFn),
+}
+
+#[derive(Clone, Copy)]
 pub struct BuiltIn {
-    pub function: BuildInFn,
+    pub function: Native,
 }
 
 impl BuiltIn {
     pub fn new(function: BuildInFn) -> Self {
-        Self { function }
+        Self {
+            function: Native::Plain(function),
+        }
     }
-}
 
-impl Object for BuiltIn {
-    fn get_type(&self) -> Type {
-        Type::BuiltInFn
+    pub fn with_callback(function: CallbackFn) -> Self {
+        Self {
+            function: Native::Callback(function),
+        }
     }
 
-    fn as_any(&self) -> &dyn Any {
-        self
+    pub fn call(&self, arguments: &[Value], caller: Caller) -> Value {
+        match self.function {
+            Native::Plain(function) => function(arguments),
+            Native::Callback(function) => function(arguments, caller),
+        }
     }
 }
 
@@ -1,25 +1,146 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use super::{Object, Type};
+use crate::lexer::Location;
+
+use super::{HashObj, Value};
+
+// what went wrong, scripts read it from the `kind` of a caught error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    // thrown by a script without a kind
+    Error,
+    // an operation or function got a value of the wrong type
+    Type,
+    // the type is right but the value is not, like parsing "a" to int
+    Value,
+    // wrong number of arguments
+    Argument,
+    Index,
+    // unknown variable, member or method
+    Name,
+    Io,
+    Import,
+    Recursion,
+    // a failed assert of a test
+    Assertion,
+    // any other kind thrown by a script
+    Custom(String),
+}
+
+impl ErrorKind {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "error" => Self::Error,
+            "type" => Self::Type,
+            "value" => Self::Value,
+            "argument" => Self::Argument,
+            "index" => Self::Index,
+            "name" => Self::Name,
+            "io" => Self::Io,
+            "import" => Self::Import,
+            "recursion" => Self::Recursion,
+            "assertion" => Self::Assertion,
+            name => Self::Custom(name.to_string()),
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Error => "error",
+            Self::Type => "type",
+            Self::Value => "value",
+            Self::Argument => "argument",
+            Self::Index => "index",
+            Self::Name => "name",
+            Self::Io => "io",
+            Self::Import => "import",
+            Self::Recursion => "recursion",
+            Self::Assertion => "assertion",
+            Self::Custom(name) => name,
+        };
+        write!(f, "{}", name)
+    }
+}
 
 pub struct EvalError {
+    pub kind: ErrorKind,
     pub message: String,
+    pub location: Option<Location>,
+    // what a script gave to throw, none for errors raised by the interpreter
+    pub value: Option<Value>,
 }
 
 impl EvalError {
-    pub fn new(message: String) -> Self {
-        eprintln!("{}", message);
-        Self { message }
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            location: None,
+            value: None,
+        }
+    }
+
+    pub fn with_location(kind: ErrorKind, message: String, location: Location) -> Self {
+        Self {
+            kind,
+            message,
+            location: Some(location),
+            value: None,
+        }
+    }
+
+    pub fn located(&self, location: Location) -> Self {
+        Self {
+            value: self.value.clone(),
+            ..Self::with_location(self.kind.clone(), self.message.clone(), location)
+        }
+    }
+
+    // a thrown hash keeps its message and kind, anything else becomes the message,
+    // the value itself is kept as it was thrown, or as first thrown when a caught
+    // error is thrown again
+    pub fn thrown(value: &Value) -> Self {
+        let field = |name| value.as_hash().and_then(|hash| hash.get(name)?.as_str());
+        let rethrown = value
+            .as_hash()
+            .filter(|hash| hash.get("message").is_some())
+            .and_then(|hash| hash.get("value"))
+            .filter(|value| !matches!(value, Value::Nil));
+        let error = match field("message") {
+            Some(message) => Self::new(
+                field("kind").map_or(ErrorKind::Error, ErrorKind::from_name),
+                message.to_string(),
+            ),
+            None => Self::new(ErrorKind::Error, value.to_string()),
+        };
+        Self {
+            value: Some(rethrown.unwrap_or(value).clone()),
+            ..error
+        }
     }
-}
 
-impl Object for EvalError {
-    fn as_any(&self) -> &dyn Any {
-        self
+    // the value a catch block sees
+    pub fn caught(&self) -> Value {
+        let mut hash = HashObj::default();
+        hash.put("message".to_string(), Value::from(self.message.clone()));
+        hash.put("kind".to_string(), Value::from(self.kind.to_string()));
+        let location = match self.location {
+            Some(ref location) => Value::from(location.to_string()),
+            None => Value::Nil,
+        };
+        hash.put("location".to_string(), location);
+        let value = self.value.clone().unwrap_or(Value::Nil);
+        hash.put("value".to_string(), value);
+        Value::from(hash)
     }
 
-    fn get_type(&self) -> Type {
-        Type::Error
+    pub fn report(&self) -> String {
+        match self.location {
+            Some(ref location) => format!("{} in {}", self.message, location),
+            None => self.message.clone(),
+        }
     }
 }
 
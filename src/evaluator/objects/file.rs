@@ -0,0 +1,245 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    builtin_map,
+    evaluator::{built_in, BuiltInMap},
+};
+
+use super::{built_in::BuiltIn, ErrorKind, Value};
+
+pub enum Stream {
+    Stdin,
+    Stdout,
+    Stderr,
+    Reader(BufReader<File>),
+    Writer(BufWriter<File>),
+}
+
+thread_local! {
+    // files open for writing, exit flushes them as their buffers are never dropped
+    static WRITERS: RefCell<Vec<Weak<FileHandle>>> = const { RefCell::new(Vec::new()) };
+}
+
+// a stream is dropped when closed, files are closed too when the last handle is gone
+pub struct FileHandle {
+    pub name: String,
+    stream: RefCell<Option<Stream>>,
+}
+
+impl FileHandle {
+    pub fn new(name: String, stream: Stream) -> Self {
+        Self {
+            name,
+            stream: RefCell::new(Some(stream)),
+        }
+    }
+
+    // modes are "r" to read, "w" to truncate and write and "a" to append
+    pub fn open(path: &str, mode: &str) -> Result<Rc<Self>, Value> {
+        let file = match mode {
+            "r" => File::open(path).map(|file| Stream::Reader(BufReader::new(file))),
+            "w" => File::create(path).map(|file| Stream::Writer(BufWriter::new(file))),
+            "a" => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map(|file| Stream::Writer(BufWriter::new(file))),
+            mode => {
+                return Err(Value::error(
+                    ErrorKind::Value,
+                    format!("unknown file mode {}, expected r, w or a", mode),
+                ))
+            }
+        };
+        match file {
+            Ok(stream) => {
+                let is_writer = matches!(stream, Stream::Writer(_));
+                let handle = Rc::new(Self::new(path.to_string(), stream));
+                if is_writer {
+                    WRITERS.with(|writers| {
+                        let mut writers = writers.borrow_mut();
+                        writers.retain(|writer| writer.strong_count() > 0);
+                        writers.push(Rc::downgrade(&handle));
+                    });
+                }
+                Ok(handle)
+            }
+            Err(e) => Err(Value::error(ErrorKind::Io, format!("{}: {}", path, e))),
+        }
+    }
+
+    pub fn methods() -> BuiltInMap {
+        builtin_map!(
+            "read_line" => Value::BuiltIn(BuiltIn::new(read_line)),
+            "lines" => Value::BuiltIn(BuiltIn::new(lines)),
+            "write" => Value::BuiltIn(BuiltIn::new(write)),
+            "write_line" => Value::BuiltIn(BuiltIn::new(write_line)),
+            "flush" => Value::BuiltIn(BuiltIn::new(flush)),
+            "close" => Value::BuiltIn(BuiltIn::new(close))
+        )
+    }
+
+    // the line without its line break, none at the end of the stream
+    pub fn read_line(&self) -> Result<Option<String>, Value> {
+        let mut buffer = vec![];
+        let read = match self.stream.borrow_mut().as_mut() {
+            Some(Stream::Stdin) => io::stdin().lock().read_until(b'\n', &mut buffer),
+            Some(Stream::Reader(reader)) => reader.read_until(b'\n', &mut buffer),
+            Some(_) => return Err(self.unsupported("reading")),
+            None => return Err(self.closed()),
+        };
+        match read {
+            Ok(0) => Ok(None),
+            Ok(_) => {
+                if buffer.ends_with(b"\n") {
+                    buffer.pop();
+                    if buffer.ends_with(b"\r") {
+                        buffer.pop();
+                    }
+                }
+                String::from_utf8(buffer).map(Some).map_err(|_| {
+                    Value::error(
+                        ErrorKind::Value,
+                        format!("{} is not valid utf-8", self.name),
+                    )
+                })
+            }
+            Err(e) => Err(self.io_error(e)),
+        }
+    }
+
+    pub fn write(&self, content: &str) -> Result<(), Value> {
+        let written = match self.stream.borrow_mut().as_mut() {
+            Some(Stream::Stdout) => io::stdout().write_all(content.as_bytes()),
+            Some(Stream::Stderr) => io::stderr().write_all(content.as_bytes()),
+            Some(Stream::Writer(writer)) => writer.write_all(content.as_bytes()),
+            Some(_) => return Err(self.unsupported("writing")),
+            None => return Err(self.closed()),
+        };
+        written.map_err(|e| self.io_error(e))
+    }
+
+    pub fn flush(&self) -> Result<(), Value> {
+        let flushed = match self.stream.borrow_mut().as_mut() {
+            Some(Stream::Stdout) => io::stdout().flush(),
+            Some(Stream::Stderr) => io::stderr().flush(),
+            Some(Stream::Writer(writer)) => writer.flush(),
+            Some(_) => Ok(()),
+            None => return Err(self.closed()),
+        };
+        flushed.map_err(|e| self.io_error(e))
+    }
+
+    // errors are dropped, this runs right before the process ends
+    pub fn flush_all() {
+        WRITERS.with(|writers| {
+            for writer in writers.borrow().iter().filter_map(Weak::upgrade) {
+                let _ = writer.flush();
+            }
+        });
+        let _ = io::stdout().flush();
+    }
+
+    // closing twice is not an error
+    pub fn close(&self) -> Result<(), Value> {
+        if self.stream.borrow().is_some() {
+            self.flush()?;
+        }
+        self.stream.borrow_mut().take();
+        Ok(())
+    }
+
+    fn unsupported(&self, operation: &str) -> Value {
+        Value::error(
+            ErrorKind::Io,
+            format!("file {} is not open for {}", self.name, operation),
+        )
+    }
+
+    fn closed(&self) -> Value {
+        Value::error(ErrorKind::Io, format!("file {} is closed", self.name))
+    }
+
+    fn io_error(&self, error: io::Error) -> Value {
+        Value::error(ErrorKind::Io, format!("{}: {}", self.name, error))
+    }
+}
+
+impl Display for FileHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "file {}", self.name)
+    }
+}
+
+fn read_line(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("file.read_line", &args[1..], 0) {
+        return error;
+    }
+    match args[0].as_file().unwrap().read_line() {
+        Ok(Some(line)) => Value::from(line),
+        Ok(None) => Value::Nil,
+        Err(error) => error,
+    }
+}
+
+// the remaining lines
+fn lines(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("file.lines", &args[1..], 0) {
+        return error;
+    }
+    let file = args[0].as_file().unwrap();
+    let mut lines = vec![];
+    loop {
+        match file.read_line() {
+            Ok(Some(line)) => lines.push(Value::from(line)),
+            Ok(None) => return Value::array(lines),
+            Err(error) => return error,
+        }
+    }
+}
+
+// writes every argument like puts
+fn write(args: &[Value]) -> Value {
+    let file = args[0].as_file().unwrap();
+    for arg in args[1..].iter() {
+        if let Err(error) = file.write(&arg.to_string()) {
+            return error;
+        }
+    }
+    Value::Nil
+}
+
+// strings have no escapes, this is how scripts end a line like putsln
+fn write_line(args: &[Value]) -> Value {
+    match write(args) {
+        Value::Nil => write(&[args[0].clone(), Value::from("\n")]),
+        error => error,
+    }
+}
+
+fn flush(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("file.flush", &args[1..], 0) {
+        return error;
+    }
+    match args[0].as_file().unwrap().flush() {
+        Ok(()) => Value::Nil,
+        Err(error) => error,
+    }
+}
+
+fn close(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("file.close", &args[1..], 0) {
+        return error;
+    }
+    match args[0].as_file().unwrap().close() {
+        Ok(()) => Value::Nil,
+        Err(error) => error,
+    }
+}
@@ -1,41 +1,49 @@
-use std::{any::Any, fmt::Display, rc::Rc};
+use std::{fmt::Display, rc::Rc};
 
-use crate::{ast::node::NodeRef, evaluator::environment::EnvironmentRef};
-
-use super::{Object, Type};
+use crate::{
+    ast::node::{write_list, BlockStatement, ParameterExpr},
+    evaluator::environment::EnvironmentRef,
+};
 
 pub struct Function {
-    pub body: Rc<NodeRef>,
-    pub parameters: Rc<Vec<NodeRef>>,
+    pub name: Option<String>,
+    pub body: Rc<BlockStatement>,
+    pub parameters: Rc<Vec<ParameterExpr>>,
+    pub slots: usize,
     pub environment: EnvironmentRef,
 }
 
 impl Function {
     pub fn new(
-        body: Rc<NodeRef>,
-        parameters: Rc<Vec<NodeRef>>,
+        name: Option<String>,
+        body: Rc<BlockStatement>,
+        parameters: Rc<Vec<ParameterExpr>>,
+        slots: usize,
         environment: EnvironmentRef,
     ) -> Self {
         Self {
+            name,
             body,
             parameters,
+            slots,
             environment,
         }
     }
 }
 
-impl Object for Function {
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn get_type(&self) -> Type {
-        Type::Function
+impl Function {
+    pub fn describe(&self) -> String {
+        match self.name {
+            Some(ref name) => format!("function {}", name),
+            None => "anonymous function".to_string(),
+        }
     }
 }
 
 impl Display for Function {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "function(...){{...}}")
+        write!(f, "fn(")?;
+        write_list(f, &self.parameters, 0)?;
+        write!(f, ") {}", self.body)
     }
 }
@@ -1,9 +1,9 @@
 use std::{collections::HashMap, fmt::Display};
 
-use super::{Object, ObjectRef, Type};
+use super::{show_once, Value};
 
 pub struct HashObj {
-    pub pairs: HashMap<String, ObjectRef>,
+    pub pairs: HashMap<String, Value>,
 }
 
 impl HashObj {
@@ -13,25 +13,15 @@ impl HashObj {
         }
     }
 
-    pub fn put(&mut self, key: String, value: ObjectRef) {
+    pub fn put(&mut self, key: String, value: Value) {
         self.pairs.insert(key, value);
     }
 
-    pub fn get(&self, key: &str) -> Option<&ObjectRef> {
+    pub fn get(&self, key: &str) -> Option<&Value> {
         self.pairs.get(key)
     }
 }
 
-impl Object for HashObj {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn get_type(&self) -> Type {
-        Type::Hash
-    }
-}
-
 impl Default for HashObj {
     fn default() -> Self {
         Self::new()
@@ -40,9 +30,18 @@ impl Default for HashObj {
 
 impl Display for HashObj {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        show_once(self, f, |f| self.show(f))
+    }
+}
+
+impl HashObj {
+    fn show(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut buffer = String::new();
         buffer.push_str("|\n");
-        for (key, value) in self.pairs.iter() {
+        // sorted so the same hash always shows the same way
+        let mut pairs: Vec<_> = self.pairs.iter().collect();
+        pairs.sort_by_key(|(key, _)| *key);
+        for (key, value) in pairs {
             buffer.push('\t');
             buffer.push_str(key);
             buffer.push_str(": ");
@@ -1,42 +1,241 @@
-use std::{any::Any, fmt::Display, rc::Rc};
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{evaluator::gc, vm::Closure};
 
 mod array;
-mod boolean;
 mod built_in;
 mod eval_error;
-mod float;
+mod file;
 mod function;
 mod hash;
-mod integer;
+mod module;
 mod ret;
 mod string;
 
 pub use array::*;
-pub use boolean::*;
 pub use built_in::*;
 pub use eval_error::*;
-pub use float::*;
+pub use file::*;
 pub use function::*;
 pub use hash::*;
-pub use integer::*;
+pub use module::*;
 pub use ret::*;
 pub use string::*;
 
-pub type ObjectRef = Rc<dyn Object>;
+thread_local! {
+    static SHOWING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// a container met again while it is being written contains itself, it is written as ...
+pub(crate) fn show_once<T>(
+    container: &T,
+    f: &mut std::fmt::Formatter<'_>,
+    show: impl FnOnce(&mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+) -> std::fmt::Result {
+    let address = container as *const T as usize;
+    if SHOWING.with(|showing| showing.borrow().contains(&address)) {
+        return write!(f, "...");
+    }
+    SHOWING.with(|showing| showing.borrow_mut().push(address));
+    let result = show(f);
+    SHOWING.with(|showing| showing.borrow_mut().pop());
+    result
+}
+
+// numbers, bools and nil are stored inline, everything else is shared
+#[derive(Clone)]
+pub enum Value {
+    Nil,
+    Int(isize),
+    Float(f64),
+    Bool(bool),
+    Str(Rc<Str>),
+    Array(Rc<Array>),
+    Hash(Rc<HashObj>),
+    Function(Rc<Function>),
+    Closure(Rc<Closure>),
+    BuiltIn(BuiltIn),
+    Module(Rc<Module>),
+    File(Rc<FileHandle>),
+    Error(Rc<EvalError>),
+    Return(Rc<Ret>),
+    TailCall(Rc<TailCall>),
+}
+
+impl Value {
+    pub fn error(kind: ErrorKind, message: String) -> Self {
+        Self::Error(Rc::new(EvalError::new(kind, message)))
+    }
+
+    pub fn array(values: Vec<Value>) -> Self {
+        Self::Array(gc::track(Rc::new(Array::new(RefCell::new(values)))))
+    }
+
+    pub fn get_type(&self) -> Type {
+        match self {
+            Self::Nil => Type::Nil,
+            Self::Int(_) => Type::Int,
+            Self::Float(_) => Type::Float,
+            Self::Bool(_) => Type::Bool,
+            Self::Str(_) => Type::String,
+            Self::Array(_) => Type::Array,
+            Self::Hash(_) => Type::Hash,
+            Self::Function(_) | Self::Closure(_) => Type::Function,
+            Self::BuiltIn(_) => Type::BuiltInFn,
+            Self::Module(_) => Type::Module,
+            Self::File(_) => Type::File,
+            Self::Error(_) => Type::Error,
+            Self::Return(_) | Self::TailCall(_) => Type::Return,
+        }
+    }
+
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Self::Nil)
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+
+    pub fn as_int(&self) -> Option<isize> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
 
-pub trait Object: Display {
-    fn as_any(&self) -> &dyn Any;
-    fn get_type(&self) -> Type;
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(string) => Some(&string.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Array> {
+        match self {
+            Self::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_hash(&self) -> Option<&HashObj> {
+        match self {
+            Self::Hash(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    pub fn as_module(&self) -> Option<&Module> {
+        match self {
+            Self::Module(module) => Some(module),
+            _ => None,
+        }
+    }
+
+    pub fn as_file(&self) -> Option<&FileHandle> {
+        match self {
+            Self::File(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    pub fn as_error(&self) -> Option<&EvalError> {
+        match self {
+            Self::Error(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<isize> for Value {
+    fn from(value: isize) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Str(Rc::new(Str::new(value)))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<HashObj> for Value {
+    fn from(hash: HashObj) -> Self {
+        Self::Hash(gc::track(Rc::new(hash)))
+    }
+}
+
+impl From<EvalError> for Value {
+    fn from(error: EvalError) -> Self {
+        Self::Error(Rc::new(error))
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nil => write!(f, "nil"),
+            Self::Int(value) => write!(f, "{}", value),
+            Self::Float(value) => write!(f, "{}", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Str(string) => string.fmt(f),
+            Self::Array(array) => array.fmt(f),
+            Self::Hash(hash) => hash.fmt(f),
+            Self::Function(function) => function.fmt(f),
+            Self::Closure(closure) => closure.fmt(f),
+            Self::BuiltIn(built_in) => built_in.fmt(f),
+            Self::Module(module) => module.fmt(f),
+            Self::File(file) => file.fmt(f),
+            Self::Error(error) => error.fmt(f),
+            Self::Return(ret) => ret.fmt(f),
+            Self::TailCall(tail_call) => tail_call.fmt(f),
+        }
+    }
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Type {
+    Nil,
     Int,
     Bool,
     Array,
     Error,
     Hash,
     Float,
+    Module,
+    File,
     Return,
     String,
     Function,
@@ -46,6 +245,7 @@ pub enum Type {
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let print = match self {
+            Self::Nil => "nil",
             Self::Int => "int",
             Self::Bool => "bool",
             Self::Float => "float",
@@ -56,6 +256,8 @@ impl Display for Type {
             Self::BuiltInFn => "built in function",
             Self::Array => "array",
             Self::Hash => "hash",
+            Self::Module => "module",
+            Self::File => "file",
         };
         write!(f, "{}", print)
     }
@@ -0,0 +1,24 @@
+use std::{collections::HashMap, fmt::Display};
+
+use super::Value;
+
+pub struct Module {
+    pub name: String,
+    pub bindings: HashMap<String, Value>,
+}
+
+impl Module {
+    pub fn new(name: String, bindings: HashMap<String, Value>) -> Self {
+        Self { name, bindings }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.bindings.get(name)
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "module {}", self.name)
+    }
+}
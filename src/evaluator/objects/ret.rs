@@ -1,29 +1,43 @@
-use std::{any::Any, fmt::Display};
+use std::fmt::Display;
 
-use super::{Object, ObjectRef, Type};
+use crate::lexer::Location;
+
+use super::Value;
 
 pub struct Ret {
-    pub val: ObjectRef,
+    pub val: Value,
 }
 
 impl Ret {
-    pub fn new(val: ObjectRef) -> Self {
+    pub fn new(val: Value) -> Self {
         Self { val }
     }
 }
 
-impl Object for Ret {
-    fn get_type(&self) -> Type {
-        Type::Return
+impl Display for Ret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)
     }
+}
 
-    fn as_any(&self) -> &dyn Any {
-        self
+pub struct TailCall {
+    pub function: Value,
+    pub arguments: Vec<Value>,
+    pub location: Location,
+}
+
+impl TailCall {
+    pub fn new(function: Value, arguments: Vec<Value>, location: Location) -> Self {
+        Self {
+            function,
+            arguments,
+            location,
+        }
     }
 }
 
-impl Display for Ret {
+impl Display for TailCall {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.val)
+        write!(f, "{}", self.function)
     }
 }
@@ -1,43 +1,34 @@
-use std::{any::Any, collections::HashMap, fmt::Display, rc::Rc};
+use std::{collections::HashMap, fmt::Display};
 
-use crate::{builtin_map, downcast_any, evaluator::BuiltInMap};
+use crate::{builtin_map, evaluator::BuiltInMap};
 
-use super::{built_in::BuiltIn, eval_error::EvalError, Object, ObjectRef, Type};
+use super::{built_in::BuiltIn, ErrorKind, Value};
 
 pub struct Str {
     pub value: String,
-    pub functions: BuiltInMap,
 }
 
 impl Str {
     pub fn new(value: String) -> Self {
-        let functions: BuiltInMap = builtin_map!(
-        "trim" => Rc::new(BuiltIn::new(trim)));
-        Self { value, functions }
-    }
-}
-
-impl Object for Str {
-    fn as_any(&self) -> &dyn Any {
-        self
+        Self { value }
     }
 
-    fn get_type(&self) -> Type {
-        Type::String
+    pub fn methods() -> BuiltInMap {
+        builtin_map!("trim" => Value::BuiltIn(BuiltIn::new(trim)))
     }
 }
 
-pub(super) fn trim(args: &[ObjectRef]) -> ObjectRef {
+fn trim(args: &[Value]) -> Value {
     if args.len() != 1 {
-        return Rc::new(EvalError::new(format!(
-            "invalid number of arguments, expected 0 got {}",
-            args.len() - 1
-        )));
+        return Value::error(
+            ErrorKind::Argument,
+            format!(
+                "invalid number of arguments, expected 0 got {}",
+                args.len() - 1
+            ),
+        );
     }
-    let first = args.first().unwrap();
-    Rc::new(Str::new(
-        downcast_any!(first => Str).value.trim().to_string(),
-    ))
+    Value::from(args[0].as_str().unwrap().trim())
 }
 
 impl Display for Str {
@@ -0,0 +1,416 @@
+use std::{cmp::Ordering, collections::HashSet, fmt::Display, rc::Rc};
+
+use super::{
+    objects::{Array, ErrorKind, HashObj, Value},
+    MethodTable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    ShiftLeft,
+    ShiftRight,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Greater,
+    Less,
+    GreaterEq,
+    LessEq,
+    NotEq,
+    Eq,
+    And,
+    Or,
+    Dot,
+    Assign,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+}
+
+impl BinaryOperator {
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        let operator = match operator {
+            "+" => Self::Add,
+            "-" => Self::Sub,
+            "*" => Self::Mul,
+            "/" => Self::Div,
+            "%" => Self::Mod,
+            "**" => Self::Pow,
+            "<<" => Self::ShiftLeft,
+            ">>" => Self::ShiftRight,
+            "&" => Self::BitAnd,
+            "|" => Self::BitOr,
+            "^" => Self::BitXor,
+            ">" => Self::Greater,
+            "<" => Self::Less,
+            ">=" => Self::GreaterEq,
+            "<=" => Self::LessEq,
+            "!=" => Self::NotEq,
+            "==" => Self::Eq,
+            "&&" => Self::And,
+            "||" => Self::Or,
+            "." => Self::Dot,
+            "=" => Self::Assign,
+            "+=" => Self::AddAssign,
+            "-=" => Self::SubAssign,
+            "*=" => Self::MulAssign,
+            "/=" => Self::DivAssign,
+            _ => return None,
+        };
+        Some(operator)
+    }
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Mod => "%",
+            Self::Pow => "**",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::Greater => ">",
+            Self::Less => "<",
+            Self::GreaterEq => ">=",
+            Self::LessEq => "<=",
+            Self::NotEq => "!=",
+            Self::Eq => "==",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::Dot => ".",
+            Self::Assign => "=",
+            Self::AddAssign => "+=",
+            Self::SubAssign => "-=",
+            Self::MulAssign => "*=",
+            Self::DivAssign => "/=",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixOperator {
+    Not,
+    Negate,
+}
+
+impl PrefixOperator {
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "!" => Some(Self::Not),
+            "-" => Some(Self::Negate),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PrefixOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Not => write!(f, "!"),
+            Self::Negate => write!(f, "-"),
+        }
+    }
+}
+
+pub fn binary(operator: BinaryOperator, left: Value, right: Value) -> Value {
+    use BinaryOperator::*;
+    let unsupported = |left: &Value, right: &Value| -> Value {
+        Value::error(
+            ErrorKind::Type,
+            format!(
+                "unsoported operation {} {} {}",
+                left.get_type(),
+                operator,
+                right.get_type()
+            ),
+        )
+    };
+    match (&left, &right) {
+        (Value::Int(_) | Value::Float(_), Value::Int(_) | Value::Float(_)) if operator == Pow => {
+            power(&left, &right)
+        }
+        (Value::Int(l), Value::Int(r)) => {
+            let (l, r) = (*l, *r);
+            match operator {
+                Add => checked_int(l.checked_add(r), l, operator, r),
+                Sub => checked_int(l.checked_sub(r), l, operator, r),
+                Mul => checked_int(l.checked_mul(r), l, operator, r),
+                Div => checked_int(l.checked_div(r), l, operator, r),
+                Mod => checked_int(l.checked_rem(r), l, operator, r),
+                // bits shifted out of the left side overflow too
+                ShiftLeft => {
+                    let shifted = u32::try_from(r)
+                        .ok()
+                        .and_then(|r| l.checked_shl(r))
+                        .filter(|shifted| shifted >> r == l);
+                    checked_int(shifted, l, operator, r)
+                }
+                ShiftRight => checked_int(
+                    u32::try_from(r).ok().and_then(|r| l.checked_shr(r)),
+                    l,
+                    operator,
+                    r,
+                ),
+                BitAnd => Value::Int(l & r),
+                BitOr => Value::Int(l | r),
+                BitXor => Value::Int(l ^ r),
+                Greater => Value::Bool(l > r),
+                Less => Value::Bool(l < r),
+                GreaterEq => Value::Bool(l >= r),
+                LessEq => Value::Bool(l <= r),
+                NotEq => Value::Bool(l != r),
+                Eq => Value::Bool(l == r),
+                _ => unsupported(&left, &right),
+            }
+        }
+        (Value::Float(l), Value::Float(r)) => {
+            let (l, r) = (*l, *r);
+            match operator {
+                Add => Value::Float(l + r),
+                Sub => Value::Float(l - r),
+                Mul => Value::Float(l * r),
+                Div => Value::Float(l / r),
+                // truncated like ints, the result has the sign of the left side
+                Mod => Value::Float(l % r),
+                Greater => Value::Bool(l > r),
+                Less => Value::Bool(l < r),
+                GreaterEq => Value::Bool(l >= r),
+                LessEq => Value::Bool(l <= r),
+                NotEq => Value::Bool(l != r),
+                Eq => Value::Bool(l == r),
+                _ => unsupported(&left, &right),
+            }
+        }
+        (Value::Bool(l), Value::Bool(r)) => match operator {
+            And => Value::Bool(*l && *r),
+            Or => Value::Bool(*l || *r),
+            _ => unsupported(&left, &right),
+        },
+        (Value::Str(l), Value::Str(r)) => {
+            let (l, r) = (&l.value, &r.value);
+            match operator {
+                Add => Value::from(format!("{}{}", l, r)),
+                NotEq => Value::Bool(l.cmp(r) != Ordering::Equal),
+                Eq => Value::Bool(l.cmp(r) == Ordering::Equal),
+                Greater => Value::Bool(l.cmp(r) == Ordering::Greater),
+                Less => Value::Bool(l.cmp(r) == Ordering::Less),
+                _ => unsupported(&left, &right),
+            }
+        }
+        // anything can be compared with nil, which only equals itself
+        (Value::Nil, _) | (_, Value::Nil) => match operator {
+            Eq => Value::Bool(left.is_nil() && right.is_nil()),
+            NotEq => Value::Bool(!(left.is_nil() && right.is_nil())),
+            _ => unsupported(&left, &right),
+        },
+        _ => unsupported(&left, &right),
+    }
+}
+
+// dividing by zero or the smallest int by -1 has no int result
+fn checked_int(
+    result: Option<isize>,
+    left: isize,
+    operator: BinaryOperator,
+    right: isize,
+) -> Value {
+    match result {
+        Some(result) => Value::Int(result),
+        None if right == 0 && matches!(operator, BinaryOperator::Div | BinaryOperator::Mod) => {
+            Value::error(
+                ErrorKind::Value,
+                format!("{} {} 0 divides by zero", left, operator),
+            )
+        }
+        None => Value::error(
+            ErrorKind::Value,
+            format!("{} {} {} overflows int", left, operator, right),
+        ),
+    }
+}
+
+// ints give an int unless the exponent is negative, any float makes it a float
+pub fn power(base: &Value, exponent: &Value) -> Value {
+    match (base, exponent) {
+        (Value::Int(base), Value::Int(exponent)) if *exponent >= 0 => {
+            match u32::try_from(*exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+            {
+                Some(result) => Value::Int(result),
+                None => Value::error(
+                    ErrorKind::Value,
+                    format!("{} ** {} overflows int", base, exponent),
+                ),
+            }
+        }
+        (base, exponent) => match (as_float(base), as_float(exponent)) {
+            (Some(base), Some(exponent)) => Value::Float(base.powf(exponent)),
+            _ => Value::error(
+                ErrorKind::Type,
+                format!(
+                    "unsoported operation {} ** {}",
+                    base.get_type(),
+                    exponent.get_type()
+                ),
+            ),
+        },
+    }
+}
+
+fn as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Int(value) => Some(*value as f64),
+        Value::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+pub fn prefix(operator: PrefixOperator, value: Value) -> Value {
+    match (operator, value) {
+        (PrefixOperator::Not, Value::Bool(value)) => Value::Bool(!value),
+        (PrefixOperator::Not, Value::Int(value)) => Value::Int(!value),
+        (PrefixOperator::Negate, Value::Int(value)) => match value.checked_neg() {
+            Some(negated) => Value::Int(negated),
+            None => Value::error(ErrorKind::Value, format!("-({}) overflows int", value)),
+        },
+        (PrefixOperator::Negate, Value::Float(value)) => Value::Float(-value),
+        (operator, value) => Value::error(
+            ErrorKind::Type,
+            format!(
+                "unsuported operation '{}' with '{}'",
+                operator,
+                value.get_type()
+            ),
+        ),
+    }
+}
+
+pub fn index(left: &Value, index: &Value) -> Value {
+    match (left, index) {
+        (Value::Array(array), Value::Int(position)) => array_index(array, *position),
+        (Value::Str(string), Value::Int(position)) => string_index(&string.value, *position),
+        (Value::Hash(hash), Value::Str(key)) => hash_value(hash, &key.value),
+        _ => Value::error(
+            ErrorKind::Type,
+            format!(
+                "unsuported operation {}[{}]",
+                left.get_type(),
+                index.get_type()
+            ),
+        ),
+    }
+}
+
+// negative positions count from the end
+pub fn position(position: isize, length: usize) -> Option<usize> {
+    let position = if position < 0 {
+        length as isize + position
+    } else {
+        position
+    };
+    (0..length as isize)
+        .contains(&position)
+        .then_some(position as usize)
+}
+
+pub fn array_index(array: &Array, index: isize) -> Value {
+    let values = array.values.borrow();
+    match position(index, values.len()) {
+        Some(position) => values[position].clone(),
+        None => Value::error(ErrorKind::Index, format!("invalid index {}", index)),
+    }
+}
+
+pub fn string_index(string: &str, index: isize) -> Value {
+    match position(index, string.chars().count()) {
+        Some(position) => Value::from(string.chars().nth(position).unwrap().to_string()),
+        None => Value::error(
+            ErrorKind::Index,
+            format!("invalid string position {}", index),
+        ),
+    }
+}
+
+pub fn hash_value(hash: &HashObj, key: &str) -> Value {
+    match hash.get(key) {
+        Some(value) => value.clone(),
+        None => Value::error(ErrorKind::Index, format!("unknown key {}", key)),
+    }
+}
+
+pub fn method(methods: &MethodTable, object: &Value, name: &str) -> Value {
+    if let Value::Module(module) = object {
+        return match module.get(name) {
+            Some(function) => function.clone(),
+            None => Value::error(
+                ErrorKind::Name,
+                format!("module {} has no member '{}'", module.name, name),
+            ),
+        };
+    }
+    match methods.get(&object.get_type()) {
+        Some(table) => match table.get(name) {
+            Some(function) => function.clone(),
+            None => Value::error(ErrorKind::Name, format!("unknown function {}", name)),
+        },
+        None => Value::error(
+            ErrorKind::Type,
+            format!("{} does not support functions for now", object.get_type()),
+        ),
+    }
+}
+
+// structural equality used by assertions, arrays and hashes are equal when
+// their values are
+pub fn equal(left: &Value, right: &Value) -> bool {
+    equal_within(left, right, &mut HashSet::new())
+}
+
+// a pair of containers already being compared is taken as equal, so values
+// that contain themselves end instead of recursing forever
+fn equal_within(left: &Value, right: &Value, visited: &mut HashSet<(usize, usize)>) -> bool {
+    match (left, right) {
+        (Value::Nil, Value::Nil) => true,
+        (Value::Int(l), Value::Int(r)) => l == r,
+        (Value::Float(l), Value::Float(r)) => l == r,
+        (Value::Bool(l), Value::Bool(r)) => l == r,
+        (Value::Str(l), Value::Str(r)) => l.value == r.value,
+        (Value::Array(l), Value::Array(r)) => {
+            if Rc::ptr_eq(l, r) || !visited.insert((Rc::as_ptr(l) as usize, Rc::as_ptr(r) as usize))
+            {
+                return true;
+            }
+            let (l, r) = (l.values.borrow(), r.values.borrow());
+            l.len() == r.len()
+                && l.iter()
+                    .zip(r.iter())
+                    .all(|(l, r)| equal_within(l, r, visited))
+        }
+        (Value::Hash(l), Value::Hash(r)) => {
+            if Rc::ptr_eq(l, r) || !visited.insert((Rc::as_ptr(l) as usize, Rc::as_ptr(r) as usize))
+            {
+                return true;
+            }
+            l.pairs.len() == r.pairs.len()
+                && l.pairs
+                    .iter()
+                    .all(|(key, l)| r.get(key).is_some_and(|r| equal_within(l, r, visited)))
+        }
+        _ => false,
+    }
+}
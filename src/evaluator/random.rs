@@ -0,0 +1,157 @@
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::builtin_map;
+
+use super::{
+    built_in,
+    objects::{BuiltIn, ErrorKind, Module, Value},
+};
+
+// xoshiro256** seeded with splitmix64, the same seed gives the same numbers everywhere
+pub(super) fn module() -> Value {
+    let bindings = builtin_map![
+        "random" => Value::BuiltIn(BuiltIn::new(random)),
+        "int" => Value::BuiltIn(BuiltIn::new(int)),
+        "choice" => Value::BuiltIn(BuiltIn::new(choice)),
+        "shuffle" => Value::BuiltIn(BuiltIn::new(shuffle)),
+        "seed" => Value::BuiltIn(BuiltIn::new(seed))
+    ];
+    Value::Module(Rc::new(Module::new("random".to_string(), bindings)))
+}
+
+thread_local! {
+    static STATE: Cell<[u64; 4]> = Cell::new(seeded(clock_seed()));
+}
+
+fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64)
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn seeded(mut seed: u64) -> [u64; 4] {
+    [
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+        splitmix64(&mut seed),
+    ]
+}
+
+fn next_u64() -> u64 {
+    STATE.with(|state| {
+        let mut s = state.get();
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        state.set(s);
+        result
+    })
+}
+
+// uniform in 0..bound, values past the last whole multiple of bound are drawn again
+fn below(bound: u64) -> u64 {
+    let limit = u64::MAX - u64::MAX % bound;
+    loop {
+        let value = next_u64();
+        if value < limit {
+            return value % bound;
+        }
+    }
+}
+
+// a float in [0, 1) from the upper 53 bits
+fn random(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("random.random", args, 0) {
+        return error;
+    }
+    Value::Float((next_u64() >> 11) as f64 / (1u64 << 53) as f64)
+}
+
+// both bounds are included
+fn int(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("random.int", args, 2) {
+        return error;
+    }
+    let (low, high) = match (&args[0], &args[1]) {
+        (Value::Int(low), Value::Int(high)) => (*low as i64, *high as i64),
+        (Value::Int(_), value) | (value, _) => {
+            return built_in::type_error("random.int", "ints", value)
+        }
+    };
+    if low > high {
+        return Value::error(
+            ErrorKind::Value,
+            format!("random.int lower bound {} is greater than {}", low, high),
+        );
+    }
+    let span = high.wrapping_sub(low) as u64;
+    let offset = match span.checked_add(1) {
+        Some(bound) => below(bound),
+        None => next_u64(),
+    };
+    Value::Int(low.wrapping_add(offset as i64) as isize)
+}
+
+fn choice(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("random.choice", args, 1) {
+        return error;
+    }
+    let array = match args[0].as_array() {
+        Some(array) => array,
+        None => return built_in::type_error("random.choice", "an array", &args[0]),
+    };
+    let values = array.values.borrow();
+    if values.is_empty() {
+        return Value::error(ErrorKind::Index, "array is empty".to_string());
+    }
+    values[below(values.len() as u64) as usize].clone()
+}
+
+// fisher-yates in place, returns the same array
+fn shuffle(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("random.shuffle", args, 1) {
+        return error;
+    }
+    let array = match args[0].as_array() {
+        Some(array) => array,
+        None => return built_in::type_error("random.shuffle", "an array", &args[0]),
+    };
+    let mut values = array.values.borrow_mut();
+    for i in (1..values.len()).rev() {
+        let j = below(i as u64 + 1) as usize;
+        values.swap(i, j);
+    }
+    args[0].clone()
+}
+
+fn seed(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("random.seed", args, 1) {
+        return error;
+    }
+    match &args[0] {
+        Value::Int(seed) => {
+            STATE.with(|state| state.set(seeded(*seed as i64 as u64)));
+            Value::Nil
+        }
+        value => built_in::type_error("random.seed", "an int", value),
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    rc::Rc,
+    sync::OnceLock,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::builtin_map;
+
+use super::{
+    built_in,
+    objects::{BuiltIn, ErrorKind, HashObj, Module, Value},
+};
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+// dates are utc only, offsets in parsed dates are applied and then dropped
+pub(super) fn module() -> Value {
+    start();
+    let bindings = builtin_map![
+        "now" => Value::BuiltIn(BuiltIn::new(now)),
+        "monotonic" => Value::BuiltIn(BuiltIn::new(monotonic)),
+        "sleep" => Value::BuiltIn(BuiltIn::new(sleep)),
+        "format" => Value::BuiltIn(BuiltIn::new(format)),
+        "parse" => Value::BuiltIn(BuiltIn::new(parse)),
+        "utc" => Value::BuiltIn(BuiltIn::new(utc)),
+        "from_utc" => Value::BuiltIn(BuiltIn::new(from_utc))
+    ];
+    Value::Module(Rc::new(Module::new("time".to_string(), bindings)))
+}
+
+fn start() -> Instant {
+    static START: OnceLock<Instant> = OnceLock::new();
+    *START.get_or_init(Instant::now)
+}
+
+// seconds since the unix epoch as a float
+fn now(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.now", args, 0) {
+        return error;
+    }
+    let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs_f64(),
+        Err(before) => -before.duration().as_secs_f64(),
+    };
+    Value::Float(seconds)
+}
+
+// seconds since the interpreter started, never goes back
+fn monotonic(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.monotonic", args, 0) {
+        return error;
+    }
+    Value::Float(start().elapsed().as_secs_f64())
+}
+
+fn sleep(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.sleep", args, 1) {
+        return error;
+    }
+    let millis = match &args[0] {
+        Value::Int(millis) => *millis as f64,
+        Value::Float(millis) => *millis,
+        value => return built_in::type_error("time.sleep", "a number", value),
+    };
+    match Duration::try_from_secs_f64(millis / 1000.0) {
+        Ok(duration) => {
+            thread::sleep(duration);
+            Value::Nil
+        }
+        Err(_) => Value::error(
+            ErrorKind::Value,
+            format!("time.sleep expects a positive duration but got {}", millis),
+        ),
+    }
+}
+
+// 2024-02-29T13:05:09Z, milliseconds are only written when there are any
+fn format(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.format", args, 1) {
+        return error;
+    }
+    let date = match millis("format", &args[0]).and_then(Date::from_millis) {
+        Ok(date) => date,
+        Err(error) => return error,
+    };
+    let mut formatted = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        date.year, date.month, date.day, date.hour, date.minute, date.second
+    );
+    if date.millisecond > 0 {
+        formatted.push_str(&format!(".{:03}", date.millisecond));
+    }
+    formatted.push('Z');
+    Value::from(formatted)
+}
+
+// accepts a date alone, a time with optional seconds and fraction, and Z or a +hh:mm offset
+fn parse(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.parse", args, 1) {
+        return error;
+    }
+    let text = match args[0].as_str() {
+        Some(text) => text,
+        None => return built_in::type_error("time.parse", "a string", &args[0]),
+    };
+    match Parser::new(text).parse() {
+        Some(millis) => seconds(millis),
+        None => Value::error(
+            ErrorKind::Value,
+            format!("invalid ISO-8601 date '{}'", text),
+        ),
+    }
+}
+
+// the components of a time, weekday goes from 1 on monday to 7 on sunday
+fn utc(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.utc", args, 1) {
+        return error;
+    }
+    let date = match millis("utc", &args[0]).and_then(Date::from_millis) {
+        Ok(date) => date,
+        Err(error) => return error,
+    };
+    let mut hash = HashObj::default();
+    let components = [
+        ("year", date.year),
+        ("month", date.month),
+        ("day", date.day),
+        ("hour", date.hour),
+        ("minute", date.minute),
+        ("second", date.second),
+        ("millisecond", date.millisecond),
+        ("weekday", date.weekday),
+        ("yday", date.yday),
+    ];
+    for (name, value) in components {
+        hash.put(name.to_string(), Value::Int(value as isize));
+    }
+    Value::from(hash)
+}
+
+// year, month and day are required, the time of the day defaults to midnight
+fn from_utc(args: &[Value]) -> Value {
+    if let Some(error) = built_in::arguments("time.from_utc", args, 1) {
+        return error;
+    }
+    let hash = match args[0].as_hash() {
+        Some(hash) => hash,
+        None => return built_in::type_error("time.from_utc", "a hash", &args[0]),
+    };
+    let component = |name: &str, default: Option<i64>| match (hash.get(name), default) {
+        (Some(Value::Int(value)), _) => Ok(*value as i64),
+        (Some(value), _) => Err(built_in::type_error(
+            "time.from_utc",
+            &format!("an int {}", name),
+            value,
+        )),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err(Value::error(
+            ErrorKind::Value,
+            format!("time.from_utc expects a {}", name),
+        )),
+    };
+    let date = (|| {
+        Ok::<_, Value>(Date {
+            year: component("year", None)?,
+            month: component("month", None)?,
+            day: component("day", None)?,
+            hour: component("hour", Some(0))?,
+            minute: component("minute", Some(0))?,
+            second: component("second", Some(0))?,
+            millisecond: component("millisecond", Some(0))?,
+            weekday: 0,
+            yday: 0,
+        })
+    })();
+    match date {
+        Ok(date) => match date.to_millis() {
+            Some(millis) => seconds(millis),
+            None => Value::error(
+                ErrorKind::Value,
+                format!(
+                    "invalid date {:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+                    date.year,
+                    date.month,
+                    date.day,
+                    date.hour,
+                    date.minute,
+                    date.second,
+                    date.millisecond
+                ),
+            ),
+        },
+        Err(error) => error,
+    }
+}
+
+fn millis(name: &str, value: &Value) -> Result<i64, Value> {
+    let millis = match value {
+        Value::Int(seconds) => (*seconds as i64).checked_mul(1000),
+        Value::Float(seconds) => {
+            let millis = (seconds * 1000.0).round();
+            (millis.is_finite() && millis.abs() < i64::MAX as f64).then_some(millis as i64)
+        }
+        value => {
+            return Err(built_in::type_error(
+                &format!("time.{}", name),
+                "a number",
+                value,
+            ))
+        }
+    };
+    millis.ok_or_else(|| {
+        Value::error(
+            ErrorKind::Value,
+            format!("time.{} cannot represent {}", name, value),
+        )
+    })
+}
+
+fn seconds(millis: i64) -> Value {
+    Value::Float(millis as f64 / 1000.0)
+}
+
+struct Date {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millisecond: i64,
+    weekday: i64,
+    yday: i64,
+}
+
+impl Date {
+    // only the four digit years of ISO-8601
+    fn from_millis(millis: i64) -> Result<Self, Value> {
+        let days = millis.div_euclid(MILLIS_PER_DAY);
+        let time = millis.rem_euclid(MILLIS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        if !(0..=9999).contains(&year) {
+            return Err(Value::error(
+                ErrorKind::Value,
+                format!("year {} is out of the range 0 to 9999", year),
+            ));
+        }
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour: time / 3_600_000,
+            minute: time / 60_000 % 60,
+            second: time / 1000 % 60,
+            millisecond: time % 1000,
+            // 1970-01-01 was a thursday
+            weekday: (days + 3).rem_euclid(7) + 1,
+            yday: days - days_from_civil(year, 1, 1) + 1,
+        })
+    }
+
+    fn to_millis(&self) -> Option<i64> {
+        let valid = (0..=9999).contains(&self.year)
+            && (1..=12).contains(&self.month)
+            && (1..=days_in_month(self.year, self.month)).contains(&self.day)
+            && (0..24).contains(&self.hour)
+            && (0..60).contains(&self.minute)
+            && (0..60).contains(&self.second)
+            && (0..1000).contains(&self.millisecond);
+        valid.then(|| {
+            days_from_civil(self.year, self.month, self.day) * MILLIS_PER_DAY
+                + self.hour * 3_600_000
+                + self.minute * 60_000
+                + self.second * 1000
+                + self.millisecond
+        })
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// days since 1970-01-01 in the proleptic gregorian calendar, from howard hinnant's date algorithms
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            bytes: text.as_bytes(),
+            position: 0,
+        }
+    }
+
+    fn parse(&mut self) -> Option<i64> {
+        let year = self.number(4)?;
+        self.expect(b'-')?;
+        let month = self.number(2)?;
+        self.expect(b'-')?;
+        let day = self.number(2)?;
+        let mut date = Date {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            millisecond: 0,
+            weekday: 0,
+            yday: 0,
+        };
+        let mut offset = 0;
+        if self.accept(b"Tt ").is_some() {
+            date.hour = self.number(2)?;
+            self.expect(b':')?;
+            date.minute = self.number(2)?;
+            if self.accept(b":").is_some() {
+                date.second = self.number(2)?;
+                if self.accept(b".,").is_some() {
+                    date.millisecond = self.fraction()?;
+                }
+            }
+            offset = self.offset()?;
+        }
+        if self.position != self.bytes.len() {
+            return None;
+        }
+        Some(date.to_millis()? - offset)
+    }
+
+    // Z, +hh:mm, +hhmm or nothing, in milliseconds
+    fn offset(&mut self) -> Option<i64> {
+        let sign = match self.accept(b"Zz+-") {
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Some(0),
+        };
+        let hours = self.number(2)?;
+        self.accept(b":");
+        let minutes = self.number(2)?;
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        Some(sign * (hours * 3_600_000 + minutes * 60_000))
+    }
+
+    // digits past the milliseconds are dropped
+    fn fraction(&mut self) -> Option<i64> {
+        let start = self.position;
+        let mut millis = 0;
+        while let Some(digit) = self.digit() {
+            if self.position - start <= 3 {
+                millis = millis * 10 + digit;
+            }
+        }
+        match self.position - start {
+            0 => None,
+            1 => Some(millis * 100),
+            2 => Some(millis * 10),
+            _ => Some(millis),
+        }
+    }
+
+    fn number(&mut self, digits: usize) -> Option<i64> {
+        let mut value = 0;
+        for _ in 0..digits {
+            value = value * 10 + self.digit()?;
+        }
+        Some(value)
+    }
+
+    fn digit(&mut self) -> Option<i64> {
+        match self.bytes.get(self.position) {
+            Some(byte) if byte.is_ascii_digit() => {
+                self.position += 1;
+                Some(i64::from(byte - b'0'))
+            }
+            _ => None,
+        }
+    }
+
+    fn accept(&mut self, options: &[u8]) -> Option<u8> {
+        let byte = *self.bytes.get(self.position)?;
+        if options.contains(&byte) {
+            self.position += 1;
+            Some(byte)
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.accept(&[byte]).map(|_| ())
+    }
+}
@@ -0,0 +1,17 @@
+use std::rc::Rc;
+
+use crate::{lexer::Lexer, parser::Parser};
+
+pub fn format_source(source: String, file: Rc<String>) -> Result<String, Vec<String>> {
+    let lexer = Lexer::with_trivia(source, file);
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_program();
+    if !program.errors.is_empty() {
+        return Err(program.errors);
+    }
+    let mut formatted = program.to_string();
+    if !formatted.is_empty() {
+        formatted.push('\n');
+    }
+    Ok(formatted)
+}
@@ -1,155 +1,232 @@
-use std::rc::Rc;
+use std::{collections::VecDeque, io::BufRead, rc::Rc};
 
-pub use self::token::{Location, Token, TokenType};
+pub use self::token::{FileId, Location, Span, Token, TokenType};
 
 mod token;
 
-pub use token::*;
+// text read before this many bytes behind the oldest token in use is dropped
+const DISCARD_SIZE: usize = 64 * 1024;
 
 pub struct Lexer {
-    source: String,
-    current_peek: usize,
-    next_peek: usize,
+    // files and pipes are lexed while they are read, a line at a time
+    reader: Option<Box<dyn BufRead>>,
+    // the bytes of the line being read, the same buffer is used for every line
+    buffer: Vec<u8>,
+    lookahead: VecDeque<char>,
+    // the input starting at `offset`, token spans point into it
+    text: String,
+    offset: usize,
+    // where the next character not looked at yet starts
+    pulled: usize,
+    // where the next character starts
+    position: usize,
     line: usize,
     line_position: usize,
     current_char: char,
-    file: Rc<String>,
+    file: FileId,
+    keep_trivia: bool,
 }
 
 impl Lexer {
     pub fn new(source: String, file: Rc<String>) -> Self {
         Self {
-            source,
-            current_peek: 0,
-            next_peek: 1,
+            reader: None,
+            buffer: Vec::new(),
+            lookahead: VecDeque::new(),
+            text: source,
+            offset: 0,
+            pulled: 0,
+            position: 0,
             line: 1,
             line_position: 1,
-            file,
+            file: FileId::new(&file),
             current_char: '\0',
+            keep_trivia: false,
+        }
+    }
+
+    pub fn with_trivia(source: String, file: Rc<String>) -> Self {
+        Self {
+            keep_trivia: true,
+            ..Self::new(source, file)
+        }
+    }
+
+    // the characters are collected before lexing
+    pub fn from_chars(chars: impl Iterator<Item = char>, file: Rc<String>) -> Self {
+        Self::new(chars.collect(), file)
+    }
+
+    // invalid utf-8 is read as the replacement character and a read error ends the input
+    pub fn from_reader(reader: impl BufRead + 'static, file: Rc<String>) -> Self {
+        Self {
+            reader: Some(Box::new(reader)),
+            ..Self::new(String::new(), file)
+        }
+    }
+
+    pub fn keeps_trivia(&self) -> bool {
+        self.keep_trivia
+    }
+
+    // the value of a token, as long as its text was not discarded
+    pub fn text(&self, span: Span) -> &str {
+        &self.text[span.start - self.offset..span.end - self.offset]
+    }
+
+    // tokens starting before `position` are not resolved anymore, a source given
+    // as a string is kept whole
+    pub fn discard_before(&mut self, position: usize) {
+        if self.reader.is_some() && position - self.offset >= DISCARD_SIZE {
+            self.text.drain(..position - self.offset);
+            self.offset = position;
+        }
+    }
+
+    fn token(&self, token_type: TokenType, location: Location, start: usize) -> Token {
+        Token::new(token_type, location, Span::new(start, self.position))
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &str {
+        self.text(Span::new(start, end))
+    }
+
+    // the end of the input takes no room
+    fn width(current_char: char) -> usize {
+        match current_char {
+            '\0' => 0,
+            current_char => current_char.len_utf8(),
         }
     }
 
     pub fn next_token(&mut self) -> Token {
         let current_char = self.skip_whitespaces();
-        let file = Rc::clone(&self.file);
-        let location = Location::new(self.line, self.line_position - 1, file);
+        let start = self.position - Self::width(current_char);
+        let location = Location::new(self.line, self.line_position - 1, self.file);
         match current_char {
-            '.' => Token::new(TokenType::Dot, location, current_char.to_string()),
-            ',' => Token::new(TokenType::Comma, location, current_char.to_string()),
-            ':' => Token::new(TokenType::Colon, location, current_char.to_string()),
+            '.' if self.check_next() == '.' && self.peek(1) == '.' => {
+                self.next_char();
+                self.next_char();
+                self.token(TokenType::Ellipsis, location, start)
+            }
+            '.' => self.token(TokenType::Dot, location, start),
+            ',' => self.token(TokenType::Comma, location, start),
+            ':' => self.token(TokenType::Colon, location, start),
             '+' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::PlusAssign, location, "+=".to_string())
+                self.token(TokenType::PlusAssign, location, start)
             }
-            '+' => Token::new(TokenType::Plus, location, current_char.to_string()),
+            '+' => self.token(TokenType::Plus, location, start),
             '-' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::MinusAssign, location, "-=".to_string())
+                self.token(TokenType::MinusAssign, location, start)
             }
-            '-' => Token::new(TokenType::Minus, location, current_char.to_string()),
+            '-' => self.token(TokenType::Minus, location, start),
             '*' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::AsteriskAssign, location, "*=".to_string())
+                self.token(TokenType::AsteriskAssign, location, start)
             }
-            '*' => Token::new(TokenType::Asterisk, location, current_char.to_string()),
+            '*' if self.check_next() == '*' => {
+                self.next_char();
+                self.token(TokenType::Power, location, start)
+            }
+            '*' => self.token(TokenType::Asterisk, location, start),
             '/' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::SlashAssign, location, "/=".to_string())
+                self.token(TokenType::SlashAssign, location, start)
+            }
+            '/' if self.check_next() == '/' && self.keep_trivia => {
+                let end = self.read_comment(start);
+                Token::new(TokenType::Comment, location, Span::new(start, end))
             }
             '/' if self.check_next() == '/' => {
                 self.skip_comment();
                 self.next_token()
             }
-            '/' => Token::new(TokenType::Slash, location, current_char.to_string()),
-            '?' => Token::new(TokenType::Question, location, current_char.to_string()),
-            '^' => Token::new(TokenType::Caret, location, current_char.to_string()),
+            '/' => self.token(TokenType::Slash, location, start),
+            '?' => self.token(TokenType::Question, location, start),
+            '^' => self.token(TokenType::Caret, location, start),
             '&' if self.check_next() == '&' => {
                 self.next_char();
-                Token::new(TokenType::And, location, "&&".to_string())
+                self.token(TokenType::And, location, start)
             }
-            '&' => Token::new(TokenType::BitWiseAnd, location, current_char.to_string()),
+            '&' => self.token(TokenType::BitWiseAnd, location, start),
             '|' if self.check_next() == '|' => {
                 self.next_char();
-                Token::new(TokenType::Or, location, "||".to_string())
-            }
-            '|' => Token::new(TokenType::Pipe, location, current_char.to_string()),
-            ';' => Token::new(TokenType::Semicolon, location, current_char.to_string()),
-            '{' => Token::new(TokenType::LBrace, location, current_char.to_string()),
-            '}' => Token::new(TokenType::RBrace, location, current_char.to_string()),
-            '[' => Token::new(TokenType::LBracket, location, current_char.to_string()),
-            ']' => Token::new(TokenType::RBracket, location, current_char.to_string()),
-            '(' => Token::new(TokenType::LParen, location, current_char.to_string()),
-            ')' => Token::new(TokenType::RParen, location, current_char.to_string()),
-            '%' => Token::new(TokenType::Mod, location, current_char.to_string()),
-            '\0' => Token::new(TokenType::Eof, location, current_char.to_string()),
+                self.token(TokenType::Or, location, start)
+            }
+            '|' => self.token(TokenType::Pipe, location, start),
+            ';' => self.token(TokenType::Semicolon, location, start),
+            '{' => self.token(TokenType::LBrace, location, start),
+            '}' => self.token(TokenType::RBrace, location, start),
+            '[' => self.token(TokenType::LBracket, location, start),
+            ']' => self.token(TokenType::RBracket, location, start),
+            '(' => self.token(TokenType::LParen, location, start),
+            ')' => self.token(TokenType::RParen, location, start),
+            '%' => self.token(TokenType::Mod, location, start),
+            '\0' => self.token(TokenType::Eof, location, start),
             '>' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::GreaterThanOrEq, location, ">=".to_string())
+                self.token(TokenType::GreaterThanOrEq, location, start)
             }
             '>' if self.check_next() == '>' => {
                 self.next_char();
-                Token::new(TokenType::ShiftRight, location, ">>".to_string())
+                self.token(TokenType::ShiftRight, location, start)
             }
-            '>' => Token::new(TokenType::Greater, location, current_char.to_string()),
+            '>' => self.token(TokenType::Greater, location, start),
             '<' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::LessThanOrEq, location, "<=".to_string())
+                self.token(TokenType::LessThanOrEq, location, start)
             }
             '<' if self.check_next() == '<' => {
                 self.next_char();
-                Token::new(TokenType::ShiftLeft, location, "<<".to_string())
+                self.token(TokenType::ShiftLeft, location, start)
             }
-            '<' => Token::new(TokenType::Less, location, current_char.to_string()),
+            '<' => self.token(TokenType::Less, location, start),
             '!' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::NotEq, location, "!=".to_string())
+                self.token(TokenType::NotEq, location, start)
             }
-            '!' => Token::new(TokenType::Bang, location, current_char.to_string()),
+            '!' => self.token(TokenType::Bang, location, start),
             '=' if self.check_next() == '=' => {
                 self.next_char();
-                Token::new(TokenType::Eq, location, "==".to_string())
+                self.token(TokenType::Eq, location, start)
             }
-            '=' => Token::new(TokenType::Assign, location, current_char.to_string()),
+            '=' => self.token(TokenType::Assign, location, start),
             '"' => {
-                let string = self.read_string();
+                self.read_string();
                 if self.current_char == '"' {
                     self.next_char();
-                    Token::new(TokenType::String, location, string)
+                    // the quotes are not part of the value
+                    let span = Span::new(start + 1, self.position - 1);
+                    Token::new(TokenType::String, location, span)
                 } else {
-                    Token::new(TokenType::Illegal, location, format!("\"{}", string))
+                    self.token(TokenType::Illegal, location, start)
                 }
             }
             _ => {
-                let value = self.read_char_sequence();
-                let word_token = Token::word_token(&value, location.clone());
-                if word_token.token_type == TokenType::Illegal {
-                    let next_chart = self.current_char;
-                    if Self::only_digits(&value) && next_chart != '.' {
-                        Token::new(TokenType::Number, location, value)
-                    } else if Self::valid_identifier(&value) {
-                        Token::new(TokenType::Identifier, location, value)
-                    } else if next_chart == '.' {
-                        self.next_char();
-                        let after_dot = self.next_token();
-                        let formated = format!("{}.{}", value, after_dot.value);
-                        if after_dot.token_type == TokenType::Number {
-                            Token::new(TokenType::FloatingPointNumber, location, formated)
-                        } else {
-                            Token::new(TokenType::Illegal, location, formated)
-                        }
+                self.read_char_sequence();
+                let value = self.slice(start, self.position);
+                if let Some(keyword) = TokenType::keyword(value) {
+                    return self.token(keyword, location, start);
+                }
+                let next_chart = self.current_char;
+                if Self::only_digits(value) && next_chart != '.' {
+                    self.token(TokenType::Number, location, start)
+                } else if Self::valid_identifier(value) {
+                    self.token(TokenType::Identifier, location, start)
+                } else if next_chart == '.' {
+                    self.next_char();
+                    let after_dot_start = self.position;
+                    let after_dot = self.next_token();
+                    let adjacent = after_dot.span.start == after_dot_start;
+                    if after_dot.token_type == TokenType::Number && adjacent {
+                        self.token(TokenType::FloatingPointNumber, location, start)
                     } else {
-                        Token::new(
-                            TokenType::Illegal,
-                            location,
-                            if value.is_empty() {
-                                self.current_char.to_string()
-                            } else {
-                                value
-                            },
-                        )
+                        self.token(TokenType::Illegal, location, start)
                     }
                 } else {
-                    word_token
+                    self.token(TokenType::Illegal, location, start)
                 }
             }
         }
@@ -164,7 +241,11 @@ impl Lexer {
     }
 
     fn next_char(&mut self) -> char {
-        let current_char = self.char_at(self.current_peek);
+        let current_char = match self.lookahead.pop_front() {
+            Some(current_char) => current_char,
+            None => self.pull(),
+        };
+        self.position += Self::width(current_char);
         if current_char == '\n' {
             self.line += 1;
             self.line_position = 1;
@@ -172,21 +253,52 @@ impl Lexer {
             self.line_position += 1;
         }
         self.current_char = current_char;
-        self.current_peek = self.next_peek;
-        self.next_peek += 1;
         current_char
     }
 
-    fn char_at(&mut self, pos: usize) -> char {
-        if pos >= self.source.len() {
-            '\0'
-        } else {
-            self.source[pos..pos + 1].chars().next().unwrap()
+    // the character `distance` places after the next one, without reading it
+    fn peek(&mut self, distance: usize) -> char {
+        while self.lookahead.len() <= distance {
+            let next = self.pull();
+            self.lookahead.push_back(next);
         }
+        self.lookahead[distance]
     }
 
-    fn read_char_sequence(&mut self) -> String {
-        let start_peek = self.current_peek - 1;
+    // a nul character ends the input like the end of the stream
+    fn pull(&mut self) -> char {
+        loop {
+            match self.text[self.pulled - self.offset..].chars().next() {
+                Some('\0') => return '\0',
+                Some(next) => {
+                    self.pulled += next.len_utf8();
+                    return next;
+                }
+                None if !self.read_line() => return '\0',
+                None => {}
+            }
+        }
+    }
+
+    // appends the next line of the reader to the text, false at the end of the input
+    fn read_line(&mut self) -> bool {
+        let Some(reader) = self.reader.as_mut() else {
+            return false;
+        };
+        self.buffer.clear();
+        match reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) | Err(_) => {
+                self.reader = None;
+                false
+            }
+            Ok(_) => {
+                self.text.push_str(&String::from_utf8_lossy(&self.buffer));
+                true
+            }
+        }
+    }
+
+    fn read_char_sequence(&mut self) {
         if !Self::is_valid_char(self.current_char) && !self.current_char.is_alphanumeric() {
             while !self.current_char.is_whitespace() && self.current_char != '\0' {
                 self.next_char();
@@ -196,14 +308,13 @@ impl Lexer {
                 self.next_char();
             }
         }
-        let final_peek = self.current_peek;
         self.back_peek();
-        String::from(&self.source[start_peek..final_peek - 1])
     }
 
+    // gives the current character back, it is read again by the next token
     fn back_peek(&mut self) {
-        self.current_peek -= 1;
-        self.next_peek -= 1;
+        self.lookahead.push_front(self.current_char);
+        self.position -= Self::width(self.current_char);
         if self.line > 0 && self.line_position == 1 {
             self.line -= 1;
         } else if self.line_position > 1 {
@@ -212,7 +323,7 @@ impl Lexer {
     }
 
     fn is_valid_char(ch: char) -> bool {
-        ('a'..='z').contains(&ch) || ('A'..='Z').contains(&ch) || ch == '_'
+        ch.is_ascii_lowercase() || ch.is_ascii_uppercase() || ch == '_'
     }
 
     fn only_digits(value: &str) -> bool {
@@ -231,18 +342,23 @@ impl Lexer {
     }
 
     fn check_next(&mut self) -> char {
-        self.char_at(self.current_peek)
+        self.peek(0)
     }
 
-    fn read_string(&mut self) -> String {
-        let start_peek = self.current_peek;
+    fn read_string(&mut self) {
         self.next_char();
         while self.current_char != '"' && self.current_char != '\0' {
             self.next_char();
         }
-        let final_peek = self.current_peek;
         self.back_peek();
-        String::from(&self.source[start_peek..final_peek - 1])
+    }
+
+    // returns where the comment ends without its trailing whitespace
+    fn read_comment(&mut self, start: usize) -> usize {
+        while self.check_next() != '\n' && self.check_next() != '\0' {
+            self.next_char();
+        }
+        start + self.slice(start, self.position).trim_end().len()
     }
 
     fn skip_comment(&mut self) {
@@ -1,15 +1,79 @@
-use std::{fmt::Display, rc::Rc};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+// file names are stored once, locations only carry their index
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub struct FileId(u32);
+
+#[derive(Default)]
+struct Files {
+    names: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, FileId>,
+}
+
+fn files() -> &'static Mutex<Files> {
+    static FILES: OnceLock<Mutex<Files>> = OnceLock::new();
+    FILES.get_or_init(Default::default)
+}
+
+impl FileId {
+    // the same name always gets the same id
+    pub fn new(name: &str) -> Self {
+        let mut files = files().lock().unwrap();
+        if let Some(id) = files.ids.get(name) {
+            return *id;
+        }
+        let id = Self(files.names.len() as u32);
+        let name: Arc<str> = Arc::from(name);
+        files.names.push(Arc::clone(&name));
+        files.ids.insert(name, id);
+        id
+    }
+
+    pub fn name(&self) -> Arc<str> {
+        Arc::clone(&files().lock().unwrap().names[self.0 as usize])
+    }
+}
+
+impl Display for FileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
 
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Location {
-    line: usize,
-    column: usize,
-    file: Rc<String>,
+    line: u32,
+    column: u32,
+    file: FileId,
 }
 
 impl Location {
-    pub fn new(line: usize, column: usize, file: Rc<String>) -> Self {
-        Self { line, column, file }
+    pub fn new(line: usize, column: usize, file: FileId) -> Self {
+        Self {
+            line: line as u32,
+            column: column as u32,
+            file,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line as usize
+    }
+
+    pub fn column(&self) -> usize {
+        self.column as usize
+    }
+
+    pub fn file(&self) -> FileId {
+        self.file
+    }
+
+    pub fn is_before(&self, other: &Location) -> bool {
+        (self.line, self.column) < (other.line, other.column)
     }
 }
 
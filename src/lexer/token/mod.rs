@@ -1,49 +1,97 @@
-use std::fmt::Display;
-
-pub use self::{location::Location, token_type::TokenType};
+pub use self::{
+    location::{FileId, Location},
+    token_type::TokenType,
+};
 
 mod location;
 mod token_type;
 
-pub use location::*;
-pub use token_type::*;
+// byte offsets of the token text in the lexed input, the lexer resolves it
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub location: Location,
-    pub value: String,
+    pub span: Span,
 }
 
 impl Token {
-    pub fn new(token_type: TokenType, location: Location, value: String) -> Self {
+    pub fn new(token_type: TokenType, location: Location, span: Span) -> Self {
         Self {
             token_type,
             location,
-            value,
+            span,
         }
     }
 }
 
-impl Token {
-    pub(super) fn word_token(word: &str, location: Location) -> Token {
-        match word {
-            "let" => Token::new(TokenType::Let, location, word.to_string()),
-            "if" => Token::new(TokenType::If, location, word.to_string()),
-            "var" => Token::new(TokenType::Var, location, word.to_string()),
-            "while" => Token::new(TokenType::While, location, word.to_string()),
-            "true" => Token::new(TokenType::True, location, word.to_string()),
-            "false" => Token::new(TokenType::False, location, word.to_string()),
-            "ret" => Token::new(TokenType::Ret, location, word.to_string()),
-            "fn" => Token::new(TokenType::Fn, location, word.to_string()),
-            "else" => Token::new(TokenType::Else, location, word.to_string()),
-            _ => Token::new(TokenType::Illegal, location, word.to_string()),
-        }
+impl TokenType {
+    pub(super) fn keyword(word: &str) -> Option<TokenType> {
+        let token_type = match word {
+            "let" => TokenType::Let,
+            "if" => TokenType::If,
+            "var" => TokenType::Var,
+            "while" => TokenType::While,
+            "true" => TokenType::True,
+            "false" => TokenType::False,
+            "nil" => TokenType::Nil,
+            "ret" => TokenType::Ret,
+            "try" => TokenType::Try,
+            "catch" => TokenType::Catch,
+            "finally" => TokenType::Finally,
+            "throw" => TokenType::Throw,
+            "fn" => TokenType::Fn,
+            "else" => TokenType::Else,
+            "import" => TokenType::Import,
+            "export" => TokenType::Export,
+            "as" => TokenType::As,
+            _ => return None,
+        };
+        Some(token_type)
     }
-}
 
-impl Display for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "'{}' in {}", self.value, self.location)
+    // the text of an operator, the syntax tree keeps it without copying the token
+    pub fn operator(&self) -> Option<&'static str> {
+        let operator = match self {
+            TokenType::Plus => "+",
+            TokenType::PlusAssign => "+=",
+            TokenType::Minus => "-",
+            TokenType::MinusAssign => "-=",
+            TokenType::Asterisk => "*",
+            TokenType::AsteriskAssign => "*=",
+            TokenType::Slash => "/",
+            TokenType::SlashAssign => "/=",
+            TokenType::Pipe => "|",
+            TokenType::Caret => "^",
+            TokenType::BitWiseAnd => "&",
+            TokenType::Mod => "%",
+            TokenType::Power => "**",
+            TokenType::Or => "||",
+            TokenType::And => "&&",
+            TokenType::LessThanOrEq => "<=",
+            TokenType::Less => "<",
+            TokenType::Greater => ">",
+            TokenType::GreaterThanOrEq => ">=",
+            TokenType::Assign => "=",
+            TokenType::NotEq => "!=",
+            TokenType::Eq => "==",
+            TokenType::ShiftLeft => "<<",
+            TokenType::ShiftRight => ">>",
+            TokenType::Dot => ".",
+            TokenType::Bang => "!",
+            _ => return None,
+        };
+        Some(operator)
     }
 }
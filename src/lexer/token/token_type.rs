@@ -3,15 +3,19 @@ use std::fmt::Display;
 #[derive(PartialEq, Eq, Debug, Hash, Copy, Clone)]
 pub enum TokenType {
     Or,
+    As,
     Eq,
     If,
     Fn,
     Mod,
+    Nil,
     Eof,
     Var,
     And,
     Ret,
+    Try,
     Dot,
+    Ellipsis,
     Let,
     Else,
     Bang,
@@ -19,13 +23,19 @@ pub enum TokenType {
     Plus,
     Less,
     Pipe,
+    Import,
+    Export,
+    Finally,
     NotEq,
     While,
+    Catch,
+    Throw,
     False,
     Caret,
     Comma,
     Colon,
     Slash,
+    Comment,
     Minus,
     Number,
     Assign,
@@ -43,6 +53,7 @@ pub enum TokenType {
     Question,
     RBracket,
     Asterisk,
+    Power,
     ShiftLeft,
     Semicolon,
     AsteriskAssign,
@@ -58,15 +69,21 @@ impl Display for TokenType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let literal = match self {
             Self::Or => "||",
+            Self::As => "as",
             Self::If => "if",
             Self::Eq => "==",
             Self::Fn => "fn",
             Self::Mod => "%",
+            Self::Nil => "nil",
             Self::Dot => ".",
+            Self::Ellipsis => "...",
             Self::Bang => "!",
             Self::Plus => "+",
             Self::Less => "<",
             Self::Pipe => "|",
+            Self::Import => "import",
+            Self::Export => "export",
+            Self::Finally => "finally",
             Self::Else => "else",
             Self::Caret => "^",
             Self::Eof => "eof",
@@ -75,7 +92,9 @@ impl Display for TokenType {
             Self::Colon => ":",
             Self::And => "and",
             Self::Slash => "/",
+            Self::Comment => "comment",
             Self::Ret => "ret",
+            Self::Try => "try",
             Self::Let => "let",
             Self::Minus => "-",
             Self::Assign => "=",
@@ -90,8 +109,11 @@ impl Display for TokenType {
             Self::Question => "?",
             Self::RBracket => "]",
             Self::Asterisk => "*",
+            Self::Power => "**",
             Self::False => "false",
             Self::While => "while",
+            Self::Catch => "catch",
+            Self::Throw => "throw",
             Self::Semicolon => ";",
             Self::ShiftLeft => "<<",
             Self::BitWiseAnd => "&",
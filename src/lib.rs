@@ -1,14 +1,19 @@
 pub mod ast;
+pub mod compiler;
+pub mod dump;
 pub mod evaluator;
+pub mod formatter;
 pub mod lexer;
 
 pub mod parser;
+pub mod resolver;
+pub mod vm;
 
 #[macro_export]
 macro_rules! builtin_map {
     ($($key:expr => $value:expr),*) => {
         {
-            let mut hashmap: HashMap<_, ObjectRef> = std::collections::HashMap::new();
+            let mut hashmap: HashMap<_, $crate::evaluator::objects::Value> = std::collections::HashMap::new();
             $(
                 hashmap.insert($key.to_string(), $value);
             )*
@@ -16,24 +21,3 @@ macro_rules! builtin_map {
         }
     };
 }
-
-#[macro_export]
-macro_rules! downcast {
-    ($val:expr => $ty:ty) => {
-        $val.as_any().downcast_ref::<$ty>()
-    };
-}
-
-#[macro_export]
-macro_rules! downcast_any {
-    ($val:expr => $ty:ty) => {
-        crate::downcast!($val => $ty).unwrap()
-    };
-}
-
-#[macro_export]
-macro_rules! downcast_option  {
-    ($val:expr => $ty:ty) => {
-        crate::downcast!($val.unwrap() => $ty)
-    };
-}
@@ -1,39 +1,16 @@
-use std::{cell::RefCell, env, fs, process, rc::Rc};
+mod cli;
 
-use mila::{
-    ast::node::NodeRef,
-    evaluator::{environment::Environment, Evaluator},
-    lexer::Lexer,
-    parser::Parser,
-};
+use std::{env, process, thread};
+
+// deep mila recursion runs on the rust stack, the evaluator limits the depth
+// but the main thread stack is too small for the default limit
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
 
 fn main() {
-    let filename = if let Some(filename) = env::args().nth(1) {
-        filename
-    } else {
-        eprintln!("please use mila filename.mila");
-        process::exit(1);
-    };
-    let source = match fs::read_to_string(&filename) {
-        Ok(input) => input,
-        Err(e) => {
-            eprintln!("Error on openfile: {:?}", e.kind());
-            process::exit(1);
-        }
-    };
-    let lexer = Lexer::new(source, Rc::new(filename));
-    let mut parser = Parser::new(lexer);
-    let program = parser.parse_program();
-    if program.errors.is_empty() {
-        let eval = Evaluator::new();
-        let program: NodeRef = Box::new(program);
-        eval.eval(
-            Some(&program),
-            Rc::new(RefCell::new(Environment::default())),
-        );
-    } else {
-        for error in program.errors {
-            eprintln!("{}", error)
-        }
-    }
+    let args: Vec<String> = env::args().skip(1).collect();
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || cli::run(args))
+        .expect("failed to start interpreter thread");
+    process::exit(interpreter.join().unwrap_or(cli::EXIT_FAILURE));
 }
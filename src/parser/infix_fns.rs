@@ -1,6 +1,6 @@
 use crate::{
-    ast::node::{CallExpr, IndexExpr, InfixExpr, NodeRef},
-    lexer::TokenType,
+    ast::node::{CallExpr, Expr, IndexExpr, InfixExpr},
+    lexer::{Location, TokenType},
     parser::precedence::Precedence,
 };
 
@@ -8,42 +8,67 @@ use super::{error::ParseError, ParseResult, Parser};
 
 use crate::precedence;
 
-pub(super) fn parse_infix_expression(parser: &mut Parser, left: NodeRef) -> ParseResult {
+pub(super) fn parse_infix_expression(parser: &mut Parser, left: Expr) -> ParseResult {
     parser.next_token();
-    let precedence = precedence!(parser.current_token.token_type);
-    let operator = parser.current_token.value.clone();
+    // a member takes its call arguments but leaves indexing to the whole expression,
+    // ** is right associative so its right side takes the next ** too
+    let precedence = match parser.current_token.token_type {
+        TokenType::Dot => Precedence::Index,
+        TokenType::Power => Precedence::Prefix,
+        token_type => precedence!(token_type),
+    };
+    let operator = parser
+        .current_token
+        .token_type
+        .operator()
+        .expect("infix parsing is only registered for operators");
+    let location = parser.current_token.location.clone();
     parser.next_token();
     let right = parser.parse_expression(precedence)?;
-    Ok(Box::new(InfixExpr::new(operator, right, left)))
+    Ok(Expr::Infix(InfixExpr::new(operator, right, left, location)))
 }
 
-pub(super) fn parse_call_expression(parser: &mut Parser, function: NodeRef) -> ParseResult {
+pub(super) fn parse_call_expression(parser: &mut Parser, function: Expr) -> ParseResult {
     parser.next_token();
-    let args = parse_expr_list(parser, TokenType::RParen)?;
-    Ok(Box::new(CallExpr::new(function, args)))
+    let location = parser.current_token.location.clone();
+    let (args, locations) = parse_located_expr_list(parser, TokenType::RParen)?;
+    Ok(Expr::Call(CallExpr::new(
+        function, args, locations, location,
+    )))
 }
 
-pub(super) fn parse_index_expression(parser: &mut Parser, left: NodeRef) -> ParseResult {
+pub(super) fn parse_index_expression(parser: &mut Parser, left: Expr) -> ParseResult {
     parser.next_token();
+    let location = parser.current_token.location.clone();
     parser.next_token();
     let index = parser.parse_expression(Precedence::Lowest)?;
     parser.expected_peek(TokenType::RBracket)?;
-    Ok(Box::new(IndexExpr::new(left, index)))
+    Ok(Expr::Index(IndexExpr::new(left, index, location)))
 }
 
-pub fn parse_expr_list(parser: &mut Parser, end: TokenType) -> Result<Vec<NodeRef>, ParseError> {
+pub fn parse_expr_list(parser: &mut Parser, end: TokenType) -> Result<Vec<Expr>, ParseError> {
+    Ok(parse_located_expr_list(parser, end)?.0)
+}
+
+fn parse_located_expr_list(
+    parser: &mut Parser,
+    end: TokenType,
+) -> Result<(Vec<Expr>, Vec<Location>), ParseError> {
     let mut exprs = Vec::with_capacity(3);
+    let mut locations = Vec::with_capacity(3);
     if parser.peek_token_is(end) {
         parser.next_token();
-        return Ok(exprs);
+        return Ok((exprs, locations));
     }
     parser.next_token();
+    locations.push(parser.current_token.location.clone());
     exprs.push(parser.parse_expression(Precedence::Lowest)?);
     while parser.peek_token_is(TokenType::Comma) {
         parser.next_token();
         parser.next_token();
+        locations.push(parser.current_token.location.clone());
         exprs.push(parser.parse_expression(Precedence::Lowest)?);
     }
     parser.expected_peek(end)?;
-    Ok(exprs)
+    Ok((exprs, locations))
 }
@@ -1,11 +1,17 @@
 mod error;
 mod infix_fns;
-mod precedence;
+pub(crate) mod precedence;
 mod prefix_fns;
 
+use crate::ast::node::ExportStmt;
+use crate::ast::node::Expr;
+use crate::ast::node::ImportStmt;
 use crate::ast::node::LetStatement;
-use crate::ast::node::NodeRef;
 use crate::ast::node::RetStatement;
+use crate::ast::node::Stmt;
+use crate::ast::node::TestStmt;
+use crate::ast::node::ThrowStmt;
+use crate::ast::node::Trivia;
 use crate::ast::node::VarStatement;
 use crate::precedence;
 use crate::{
@@ -17,8 +23,9 @@ use std::collections::HashMap;
 use self::{error::ParseError, precedence::Precedence};
 
 pub type ParsePrefixFn = fn(&mut Parser) -> ParseResult;
-pub type ParseInfixFn = fn(&mut Parser, NodeRef) -> ParseResult;
-pub type ParseResult = Result<NodeRef, ParseError>;
+pub type ParseInfixFn = fn(&mut Parser, Expr) -> ParseResult;
+pub type ParseResult = Result<Expr, ParseError>;
+pub type StmtResult = Result<Stmt, ParseError>;
 
 pub struct Parser {
     lexer: Lexer,
@@ -26,18 +33,23 @@ pub struct Parser {
     pub peek_token: Token,
     pub parse_prefix_fns: HashMap<TokenType, ParsePrefixFn>,
     pub parse_infix_fns: HashMap<TokenType, ParseInfixFn>,
+    comments: Vec<Token>,
+    last_line: usize,
+    errors: Vec<String>,
 }
 
 impl Parser {
     pub fn new(mut lexer: Lexer) -> Self {
-        let current_token = lexer.next_token();
-        let peek_token = lexer.next_token();
+        let mut comments = Vec::new();
+        let current_token = Self::next_significant_token(&mut lexer, &mut comments);
+        let peek_token = Self::next_significant_token(&mut lexer, &mut comments);
         let mut parse_prefix_fns: HashMap<TokenType, ParsePrefixFn> = HashMap::new();
         let mut parse_infix_fns: HashMap<TokenType, ParseInfixFn> = HashMap::new();
         parse_prefix_fns.insert(TokenType::Bang, prefix_fns::parse_prefix_expr);
         parse_prefix_fns.insert(TokenType::Minus, prefix_fns::parse_prefix_expr);
         parse_prefix_fns.insert(TokenType::True, prefix_fns::parse_boolean_expr);
         parse_prefix_fns.insert(TokenType::False, prefix_fns::parse_boolean_expr);
+        parse_prefix_fns.insert(TokenType::Nil, prefix_fns::parse_nil_expr);
         parse_prefix_fns.insert(TokenType::Number, prefix_fns::parse_int_expr);
         parse_prefix_fns.insert(TokenType::String, prefix_fns::parse_string_expr);
         parse_prefix_fns.insert(TokenType::Identifier, prefix_fns::parse_identifier_expr);
@@ -46,6 +58,7 @@ impl Parser {
         // parse_prefix_fns.insert(TokenType::LBrace, prefix_fns::parse_block_stmt);
         parse_prefix_fns.insert(TokenType::If, prefix_fns::parse_if_expr);
         parse_prefix_fns.insert(TokenType::While, prefix_fns::parse_while_expr);
+        parse_prefix_fns.insert(TokenType::Try, prefix_fns::parse_try_expr);
         parse_prefix_fns.insert(TokenType::Fn, prefix_fns::parse_fn_expr);
         parse_prefix_fns.insert(TokenType::LBracket, prefix_fns::parse_array_expr);
         parse_prefix_fns.insert(TokenType::Pipe, prefix_fns::parse_hash_expr);
@@ -62,6 +75,7 @@ impl Parser {
         parse_infix_fns.insert(TokenType::Caret, infix_fns::parse_infix_expression);
         parse_infix_fns.insert(TokenType::BitWiseAnd, infix_fns::parse_infix_expression);
         parse_infix_fns.insert(TokenType::Mod, infix_fns::parse_infix_expression);
+        parse_infix_fns.insert(TokenType::Power, infix_fns::parse_infix_expression);
         parse_infix_fns.insert(TokenType::Or, infix_fns::parse_infix_expression);
         parse_infix_fns.insert(TokenType::And, infix_fns::parse_infix_expression);
         parse_infix_fns.insert(TokenType::LessThanOrEq, infix_fns::parse_infix_expression);
@@ -85,39 +99,108 @@ impl Parser {
             lexer,
             parse_prefix_fns,
             parse_infix_fns,
+            comments,
+            last_line: 0,
+            errors: Vec::new(),
         }
     }
 
     pub fn parse_program(&mut self) -> Program {
         let mut program = Program::new();
-        loop {
+        while !self.current_token_is(TokenType::Eof) {
+            self.push_leading_trivia(&mut program.statements);
             match self.parse_statement() {
-                Ok(stmt) => program.push_statements(stmt),
+                Ok(stmt) => self.push_statement(&mut program.statements, stmt),
                 Err(ParseError::Message(e)) => program.push_error(e),
             }
             self.next_token();
-            if self.current_token.token_type == TokenType::Eof {
-                break;
-            }
+        }
+        self.push_comments(&mut program.statements);
+        for error in self.errors.drain(..) {
+            program.push_error(error);
         }
         program
     }
 
-    fn parse_statement(&mut self) -> ParseResult {
+    fn push_leading_trivia(&mut self, stmts: &mut Vec<Stmt>) {
+        self.push_comments(stmts);
+        let line = self.current_token.location.line();
+        if self.lexer.keeps_trivia() && !stmts.is_empty() && line > self.last_line + 1 {
+            stmts.push(Stmt::Trivia(Trivia::BlankLine));
+        }
+    }
+
+    fn push_comments(&mut self, stmts: &mut Vec<Stmt>) {
+        while !self.comments.is_empty()
+            && self.comments[0]
+                .location
+                .is_before(&self.current_token.location)
+        {
+            let comment = self.comments.remove(0);
+            let line = comment.location.line();
+            if line == self.last_line {
+                stmts.push(Stmt::Trivia(Trivia::TrailingComment(
+                    self.comment_text(&comment),
+                )));
+            } else {
+                if !stmts.is_empty() && line > self.last_line + 1 {
+                    stmts.push(Stmt::Trivia(Trivia::BlankLine));
+                }
+                stmts.push(Stmt::Trivia(Trivia::Comment(self.comment_text(&comment))));
+            }
+            self.last_line = line;
+        }
+    }
+
+    fn push_statement(&mut self, stmts: &mut Vec<Stmt>, stmt: Stmt) {
+        // comments inside of a statement are moved before it
+        while !self.comments.is_empty()
+            && self.comments[0]
+                .location
+                .is_before(&self.current_token.location)
+        {
+            let comment = self.comments.remove(0);
+            stmts.push(Stmt::Trivia(Trivia::Comment(self.comment_text(&comment))));
+        }
+        stmts.push(stmt);
+        self.last_line = self.current_token.location.line();
+        while !self.comments.is_empty() && self.comments[0].location.line() == self.last_line {
+            let comment = self.comments.remove(0);
+            stmts.push(Stmt::Trivia(Trivia::TrailingComment(
+                self.comment_text(&comment),
+            )));
+        }
+    }
+
+    fn push_block_error(&mut self, error: ParseError) {
+        self.errors.push(error.to_string());
+    }
+
+    fn parse_statement(&mut self) -> StmtResult {
         match self.current_token.token_type {
             TokenType::Let => self.parse_let_var(true),
             TokenType::Var => self.parse_let_var(false),
             TokenType::Ret => self.parse_return(),
+            TokenType::Throw => self.parse_throw(),
+            // test is only a keyword when a name follows it
+            TokenType::Identifier
+                if self.current_value() == "test"
+                    && self.peek_token.token_type == TokenType::String =>
+            {
+                self.parse_test()
+            }
+            TokenType::Import => self.parse_import(),
+            TokenType::Export => self.parse_export(),
             _ => self.parse_expr_estatement(),
         }
     }
 
-    fn parse_expr_estatement(&mut self) -> ParseResult {
+    fn parse_expr_estatement(&mut self) -> StmtResult {
         let expr = self.parse_expression(Precedence::Lowest)?;
         if self.peek_token_is(TokenType::Semicolon) {
             self.next_token();
         }
-        Ok(Box::new(ExpressionStmt::new(expr)))
+        Ok(Stmt::Expression(ExpressionStmt::new(expr)))
     }
 
     pub fn parse_expression(&mut self, precedence: Precedence) -> ParseResult {
@@ -125,7 +208,7 @@ impl Parser {
         let mut left_expr = match self.parse_prefix_fns.get(&current_token_type) {
             Some(function) => function(self)?,
             None => {
-                let msg = format!("syntax error got {}", self.current_token);
+                let msg = format!("syntax error got {}", self.show(&self.current_token));
                 return Err(ParseError::Message(msg));
             }
         };
@@ -142,9 +225,9 @@ impl Parser {
         Ok(left_expr)
     }
 
-    fn parse_let_var(&mut self, is_let: bool) -> ParseResult {
+    fn parse_let_var(&mut self, is_let: bool) -> StmtResult {
         self.expected_peek(TokenType::Identifier)?;
-        let identifier = prefix_fns::parse_identifier_expr(self)?;
+        let identifier = prefix_fns::parse_identifier(self)?;
         self.expected_peek(TokenType::Assign)?;
         self.next_token();
         let expr = self.parse_expression(Precedence::Lowest)?;
@@ -152,21 +235,77 @@ impl Parser {
             self.next_token()
         }
         if is_let {
-            Ok(Box::new(LetStatement::new(identifier, expr)))
+            Ok(Stmt::Let(LetStatement::new(identifier, expr)))
         } else {
-            Ok(Box::new(VarStatement::new(identifier, expr)))
+            Ok(Stmt::Var(VarStatement::new(identifier, expr)))
         }
     }
 
-    fn parse_return(&mut self) -> ParseResult {
+    fn parse_return(&mut self) -> StmtResult {
         self.next_token();
         if self.current_token_is(TokenType::Semicolon) {
-            Ok(Box::new(RetStatement::new(None)))
+            Ok(Stmt::Ret(RetStatement::new(None)))
         } else {
             let ret_expr = self.parse_expression(Precedence::Lowest)?;
             self.expected_peek(TokenType::Semicolon)?;
-            Ok(Box::new(RetStatement::new(Some(ret_expr))))
+            Ok(Stmt::Ret(RetStatement::new(Some(ret_expr))))
+        }
+    }
+
+    fn parse_throw(&mut self) -> StmtResult {
+        let location = self.current_token.location.clone();
+        self.next_token();
+        let value = self.parse_expression(Precedence::Lowest)?;
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
         }
+        Ok(Stmt::Throw(ThrowStmt::new(value, location)))
+    }
+
+    fn parse_test(&mut self) -> StmtResult {
+        let location = self.current_token.location.clone();
+        self.next_token();
+        let name = self.current_value().to_string();
+        self.expected_peek(TokenType::LBrace)?;
+        let body = prefix_fns::parse_block_stmt(self)?;
+        Ok(Stmt::Test(TestStmt::new(name, body, location)))
+    }
+
+    fn parse_import(&mut self) -> StmtResult {
+        let location = self.current_token.location.clone();
+        self.next_token();
+        let path = match self.current_token.token_type {
+            TokenType::String | TokenType::Identifier => self.current_value().to_string(),
+            _ => {
+                let msg = format!(
+                    "expected module path, got {}",
+                    self.show(&self.current_token)
+                );
+                return Err(ParseError::Message(msg));
+            }
+        };
+        let alias = if self.peek_token_is(TokenType::As) {
+            self.next_token();
+            self.expected_peek(TokenType::Identifier)?;
+            Some(self.current_value().to_string())
+        } else {
+            None
+        };
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+        Ok(Stmt::Import(ImportStmt::new(path, alias, location)))
+    }
+
+    fn parse_export(&mut self) -> StmtResult {
+        let location = self.current_token.location.clone();
+        self.next_token();
+        let export = ExportStmt::new(self.parse_statement()?);
+        if export.name().is_none() {
+            let msg = format!("only let, var and named fn can be exported in {}", location);
+            return Err(ParseError::Message(msg));
+        }
+        Ok(Stmt::Export(export))
     }
 
     pub fn current_token_is(&mut self, token_type: TokenType) -> bool {
@@ -182,13 +321,51 @@ impl Parser {
             self.next_token();
             Ok(())
         } else {
-            let msg = format!("expected '{}', got {}", token_type, self.peek_token);
+            let msg = format!(
+                "expected '{}', got {}",
+                token_type,
+                self.show(&self.peek_token)
+            );
             Err(ParseError::Message(msg))
         }
     }
 
     fn next_token(&mut self) {
         std::mem::swap(&mut self.current_token, &mut self.peek_token);
-        self.peek_token = self.lexer.next_token();
+        self.peek_token = Self::next_significant_token(&mut self.lexer, &mut self.comments);
+        let oldest = match self.comments.first() {
+            Some(comment) => comment.span.start,
+            None => self.current_token.span.start,
+        };
+        self.lexer.discard_before(oldest);
+    }
+
+    // token values are read from the source only when the parser needs them
+    pub fn token_value(&self, token: &Token) -> &str {
+        self.lexer.text(token.span)
+    }
+
+    pub fn current_value(&self) -> &str {
+        self.token_value(&self.current_token)
+    }
+
+    pub fn show(&self, token: &Token) -> String {
+        match token.token_type {
+            TokenType::Eof => format!("end of input in {}", token.location),
+            _ => format!("'{}' in {}", self.token_value(token), token.location),
+        }
+    }
+
+    fn comment_text(&self, comment: &Token) -> String {
+        self.token_value(comment).to_string()
+    }
+
+    fn next_significant_token(lexer: &mut Lexer, comments: &mut Vec<Token>) -> Token {
+        let mut token = lexer.next_token();
+        while token.token_type == TokenType::Comment {
+            comments.push(token);
+            token = lexer.next_token();
+        }
+        token
     }
 }
@@ -9,9 +9,10 @@ pub enum Precedence {
     Sum = 5,
     Product = 6,
     Prefix = 7,
-    Dot = 8,
-    Call = 9,
+    Power = 8,
+    Dot = 9,
     Index = 10,
+    Call = 11,
 }
 
 impl PartialEq for Precedence {
@@ -31,11 +32,26 @@ impl PartialOrd for Precedence {
     }
 }
 
+impl Precedence {
+    pub(crate) fn of_operator(operator: &str) -> Self {
+        match operator {
+            "==" | "!=" => Precedence::Equals,
+            "||" | "&&" => Precedence::AndOr,
+            "<" | ">" | "<=" | ">=" | "=" => Precedence::LessGreater,
+            "+" | "-" | "+=" | "-=" => Precedence::Sum,
+            "/" | "%" | "*" | "&" | "|" | "<<" | ">>" | "*=" | "/=" | "^" => Precedence::Product,
+            "**" => Precedence::Power,
+            "." => Precedence::Dot,
+            _ => Precedence::Lowest,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! precedence {
     ($val: expr) => {{
-        use crate::lexer::TokenType::*;
-        use crate::parser::precedence::Precedence;
+        use $crate::lexer::TokenType::*;
+        use $crate::parser::precedence::Precedence;
         match $val {
             Eq | NotEq => Precedence::Equals,
             Or | And => Precedence::AndOr,
@@ -43,6 +59,7 @@ macro_rules! precedence {
             Plus | Minus | PlusAssign | MinusAssign => Precedence::Sum,
             Slash | Mod | Asterisk | BitWiseAnd | Pipe | ShiftLeft | ShiftRight
             | AsteriskAssign | SlashAssign | Caret => Precedence::Product,
+            Power => Precedence::Power,
             LParen => Precedence::Call,
             LBracket => Precedence::Index,
             Dot => Precedence::Dot,
@@ -2,8 +2,9 @@ use std::rc::Rc;
 
 use crate::{
     ast::node::{
-        ArrayExpr, BlockStatement, BoolExpr, FloatExpr, FnExpr, HashExpr, IdentifierExpr, IfExpr,
-        IntExpr, NodeRef, PrefixExpr, StringExpr, WhileExpr,
+        ArrayExpr, BlockStatement, BoolExpr, CatchClause, Expr, FloatExpr, FnExpr, HashExpr,
+        IdentifierExpr, IfExpr, IntExpr, NilExpr, ParameterExpr, PrefixExpr, StringExpr, TryExpr,
+        WhileExpr,
     },
     lexer::TokenType,
     parser::precedence::Precedence,
@@ -12,10 +13,15 @@ use crate::{
 use super::{error::ParseError, infix_fns, ParseResult, Parser};
 
 pub(super) fn parse_prefix_expr(parser: &mut Parser) -> ParseResult {
-    let operator = parser.current_token.value.clone();
+    let operator = parser
+        .current_token
+        .token_type
+        .operator()
+        .expect("prefix parsing is only registered for operators");
+    let location = parser.current_token.location.clone();
     parser.next_token();
     let right = parser.parse_expression(Precedence::Prefix)?;
-    Ok(Box::new(PrefixExpr::new(operator, right)))
+    Ok(Expr::Prefix(PrefixExpr::new(operator, right, location)))
 }
 
 pub(super) fn parse_boolean_expr(parser: &mut Parser) -> ParseResult {
@@ -23,22 +29,29 @@ pub(super) fn parse_boolean_expr(parser: &mut Parser) -> ParseResult {
         TokenType::True => true,
         TokenType::False => false,
         _ => {
-            let msg = format!("expected boolean value got: {}", parser.current_token);
+            let msg = format!(
+                "expected boolean value got: {}",
+                parser.show(&parser.current_token)
+            );
             return Err(ParseError::Message(msg));
         }
     };
-    Ok(Box::new(BoolExpr::new(value)))
+    Ok(Expr::Bool(BoolExpr::new(value)))
+}
+
+pub(super) fn parse_nil_expr(_parser: &mut Parser) -> ParseResult {
+    Ok(Expr::Nil(NilExpr))
 }
 
 pub(super) fn parse_int_expr(parser: &mut Parser) -> ParseResult {
     match parser.current_token.token_type {
         TokenType::Number => {
-            let value = parser.current_token.value.parse()?;
-            Ok(Box::new(IntExpr::new(value)))
+            let value = parser.current_value().parse()?;
+            Ok(Expr::Int(IntExpr::new(value)))
         }
         _ => Err(ParseError::Message(format!(
             "expected number got {}",
-            parser.current_token
+            parser.show(&parser.current_token)
         ))),
     }
 }
@@ -46,12 +59,12 @@ pub(super) fn parse_int_expr(parser: &mut Parser) -> ParseResult {
 pub(super) fn parse_float_expr(parser: &mut Parser) -> ParseResult {
     match parser.current_token.token_type {
         TokenType::FloatingPointNumber => {
-            let value = parser.current_token.value.parse()?;
-            Ok(Box::new(FloatExpr::new(value)))
+            let value = parser.current_value().parse()?;
+            Ok(Expr::Float(FloatExpr::new(value)))
         }
         _ => Err(ParseError::Message(format!(
             "expected float got {}",
-            parser.current_token
+            parser.show(&parser.current_token)
         ))),
     }
 }
@@ -59,25 +72,30 @@ pub(super) fn parse_float_expr(parser: &mut Parser) -> ParseResult {
 pub(super) fn parse_string_expr(parser: &mut Parser) -> ParseResult {
     match parser.current_token.token_type {
         TokenType::String => {
-            let value = parser.current_token.value.clone();
-            Ok(Box::new(StringExpr::new(value)))
+            let value = parser.current_value().to_string();
+            Ok(Expr::String(StringExpr::new(value)))
         }
         _ => Err(ParseError::Message(format!(
             "expected string got {}",
-            parser.current_token
+            parser.show(&parser.current_token)
         ))),
     }
 }
 
 pub(super) fn parse_identifier_expr(parser: &mut Parser) -> ParseResult {
+    Ok(Expr::Identifier(parse_identifier(parser)?))
+}
+
+pub(super) fn parse_identifier(parser: &mut Parser) -> Result<IdentifierExpr, ParseError> {
     match parser.current_token.token_type {
         TokenType::Identifier => {
-            let value = parser.current_token.value.clone();
-            Ok(Box::new(IdentifierExpr::new(value)))
+            let value = parser.current_value().to_string();
+            let location = parser.current_token.location.clone();
+            Ok(IdentifierExpr::new(value, location))
         }
         _ => Err(ParseError::Message(format!(
             "expected identifier got {}",
-            parser.current_token
+            parser.show(&parser.current_token)
         ))),
     }
 }
@@ -89,20 +107,28 @@ pub(super) fn parse_group_expr(parser: &mut Parser) -> ParseResult {
     expr
 }
 
-pub(super) fn parse_block_stmt(parser: &mut Parser) -> ParseResult {
+pub(super) fn parse_block_stmt(parser: &mut Parser) -> Result<BlockStatement, ParseError> {
     let mut stmts = Vec::new();
+    parser.last_line = parser.current_token.location.line();
+    parser.next_token();
     while !parser.current_token_is(TokenType::Eof) && !parser.current_token_is(TokenType::RBrace) {
-        if let Ok(stmt) = parser.parse_statement() {
-            stmts.push(stmt);
+        parser.push_leading_trivia(&mut stmts);
+        match parser.parse_statement() {
+            Ok(stmt) => parser.push_statement(&mut stmts, stmt),
+            Err(e) => parser.push_block_error(e),
         }
         parser.next_token();
     }
+    parser.push_comments(&mut stmts);
 
-    let block_stmt = BlockStatement::new(Rc::new(stmts));
-    Ok(Box::new(block_stmt))
+    Ok(BlockStatement::new(Rc::new(stmts)))
 }
 
 pub(super) fn parse_if_expr(parser: &mut Parser) -> ParseResult {
+    Ok(Expr::If(parse_if(parser)?))
+}
+
+fn parse_if(parser: &mut Parser) -> Result<IfExpr, ParseError> {
     parser.next_token();
     let condition = parser.parse_expression(Precedence::Lowest)?;
     parser.expected_peek(TokenType::LBrace)?;
@@ -117,13 +143,13 @@ pub(super) fn parse_if_expr(parser: &mut Parser) -> ParseResult {
             }
             Err(_) => match parser.expected_peek(TokenType::If) {
                 Ok(_) => {
-                    if_expr.el_if = Some(parse_if_expr(parser)?);
+                    if_expr.el_if = Some(Box::new(parse_if(parser)?));
                 }
                 Err(e) => return Err(e),
             },
         }
     }
-    Ok(Box::new(if_expr))
+    Ok(if_expr)
 }
 
 pub(super) fn parse_while_expr(parser: &mut Parser) -> ParseResult {
@@ -131,54 +157,121 @@ pub(super) fn parse_while_expr(parser: &mut Parser) -> ParseResult {
     let condition = parser.parse_expression(Precedence::Lowest)?;
     parser.expected_peek(TokenType::LBrace)?;
     let consequence = parse_block_stmt(parser)?;
-    let while_expr = WhileExpr::new(condition, consequence);
-    Ok(Box::new(while_expr))
+    Ok(Expr::While(WhileExpr::new(condition, consequence)))
+}
+
+pub(super) fn parse_try_expr(parser: &mut Parser) -> ParseResult {
+    let location = parser.current_token.location.clone();
+    parser.expected_peek(TokenType::LBrace)?;
+    let body = parse_block_stmt(parser)?;
+    let catch = if parser.peek_token_is(TokenType::Catch) {
+        parser.next_token();
+        parser.expected_peek(TokenType::Identifier)?;
+        let name = parse_identifier(parser)?;
+        parser.expected_peek(TokenType::LBrace)?;
+        Some(CatchClause::new(name, parse_block_stmt(parser)?))
+    } else {
+        None
+    };
+    let finally = if parser.peek_token_is(TokenType::Finally) {
+        parser.next_token();
+        parser.expected_peek(TokenType::LBrace)?;
+        Some(parse_block_stmt(parser)?)
+    } else {
+        None
+    };
+    if catch.is_none() && finally.is_none() {
+        let msg = format!("expected catch or finally after try in {}", location);
+        return Err(ParseError::Message(msg));
+    }
+    Ok(Expr::Try(TryExpr::new(body, catch, finally)))
 }
 
 pub(super) fn parse_fn_expr(parser: &mut Parser) -> ParseResult {
     let name = match parser.expected_peek(TokenType::Identifier) {
-        Ok(_) => {
-            let name = Some(Rc::new(parse_identifier_expr(parser)?));
-            name
-        }
+        Ok(_) => Some(parse_identifier(parser)?),
         Err(_) => None,
     };
     parser.expected_peek(TokenType::LParen)?;
     let parameters = Rc::new(parse_function_parameters(parser)?);
     parser.expected_peek(TokenType::LBrace)?;
     let body = Rc::new(parse_block_stmt(parser)?);
-    Ok(Box::new(FnExpr::new(body, name, parameters)))
+    Ok(Expr::Function(FnExpr::new(body, name, parameters)))
 }
 
-fn parse_function_parameters(parser: &mut Parser) -> Result<Vec<NodeRef>, ParseError> {
+fn parse_function_parameters(parser: &mut Parser) -> Result<Vec<ParameterExpr>, ParseError> {
     let mut parameters = Vec::with_capacity(4);
     if parser.peek_token_is(TokenType::RParen) {
         parser.next_token();
         return Ok(parameters);
     }
     parser.next_token();
-    let parameter = parse_identifier_expr(parser)?;
-    parameters.push(parameter);
+    parameters.push(parse_parameter(parser, &parameters)?);
     while parser.peek_token_is(TokenType::Comma) {
         parser.next_token();
         parser.next_token();
-        let parameter = parse_identifier_expr(parser)?;
-        parameters.push(parameter);
+        parameters.push(parse_parameter(parser, &parameters)?);
     }
     parser.expected_peek(TokenType::RParen)?;
     Ok(parameters)
 }
 
+// the parameters before it are checked for duplicated names and default values
+fn parse_parameter(
+    parser: &mut Parser,
+    previous: &[ParameterExpr],
+) -> Result<ParameterExpr, ParseError> {
+    let rest = parser.current_token.token_type == TokenType::Ellipsis;
+    if rest {
+        parser.expected_peek(TokenType::Identifier)?;
+    }
+    let name = parse_identifier(parser)?.value;
+    if previous.iter().any(|parameter| parameter.name == name) {
+        let msg = format!(
+            "duplicated parameter {}",
+            parser.show(&parser.current_token)
+        );
+        return Err(ParseError::Message(msg));
+    }
+    if rest {
+        if !parser.peek_token_is(TokenType::RParen) {
+            let msg = format!(
+                "rest parameter must be the last one {}",
+                parser.show(&parser.current_token)
+            );
+            return Err(ParseError::Message(msg));
+        }
+        return Ok(ParameterExpr::new(name, None, true));
+    }
+    let after_default = previous
+        .last()
+        .is_some_and(|parameter| parameter.default.is_some());
+    let default = if parser.peek_token_is(TokenType::Assign) {
+        parser.next_token();
+        parser.next_token();
+        Some(parser.parse_expression(Precedence::Lowest)?)
+    } else if after_default {
+        let msg = format!(
+            "parameter without default value after default one {}",
+            parser.show(&parser.current_token)
+        );
+        return Err(ParseError::Message(msg));
+    } else {
+        None
+    };
+    Ok(ParameterExpr::new(name, default, false))
+}
+
 pub(super) fn parse_array_expr(parser: &mut Parser) -> ParseResult {
     let elements = infix_fns::parse_expr_list(parser, TokenType::RBracket)?;
-    Ok(Box::new(ArrayExpr::new(elements)))
+    Ok(Expr::Array(ArrayExpr::new(elements)))
 }
 
 pub(super) fn parse_hash_expr(parser: &mut Parser) -> ParseResult {
     let mut hash = HashExpr::new();
     while !parser.peek_token_is(TokenType::Pipe) {
         parser.next_token();
-        let key = parse_identifier_expr(parser)?.to_string();
+        let key = parse_identifier(parser)?.value;
         parser.expected_peek(TokenType::Colon)?;
         parser.next_token();
         let value = parser.parse_expression(Precedence::Lowest)?;
@@ -186,5 +279,5 @@ pub(super) fn parse_hash_expr(parser: &mut Parser) -> ParseResult {
         parser.expected_peek(TokenType::Comma)?;
     }
     parser.expected_peek(TokenType::Pipe)?;
-    Ok(Box::new(hash))
+    Ok(Expr::Hash(hash))
 }
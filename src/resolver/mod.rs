@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{
+        node::{BlockStatement, Expr, ExpressionStmt, FnExpr, IdentifierExpr, Stmt},
+        visit::{walk_block, walk_expr, walk_program, walk_stmt, Visitor},
+        Program,
+    },
+    evaluator::environment::Environment,
+    lexer::Location,
+};
+
+// where a variable lives, locals are addressed by how many function frames
+// up they are and their index in that frame, globals by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Slot {
+    #[default]
+    Global,
+    Local(usize, usize),
+}
+
+// annotates every identifier, import and function of the program with its
+// slots and returns the names read that no scope or global declares
+pub fn resolve(program: &Program, environment: &Environment) -> Vec<(String, Location)> {
+    let declared: HashSet<String> = environment.variables.keys().cloned().collect();
+    let mut globals = declared.clone();
+    globals.extend(program.statements.iter().filter_map(declaration));
+    let mut resolver = Resolver {
+        functions: vec![Function::default()],
+        globals,
+        declared,
+        unresolved: Vec::new(),
+    };
+    walk_program(&mut resolver, program);
+    program.slots.set(resolver.functions[0].slots);
+    resolver.unresolved
+}
+
+struct Binding {
+    index: usize,
+    declared: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: HashMap<String, Binding>,
+}
+
+#[derive(Default)]
+struct Function {
+    scopes: Vec<Scope>,
+    slots: usize,
+}
+
+impl Function {
+    fn bind(&mut self, name: String, declared: bool) -> usize {
+        let index = self.slots;
+        self.slots += 1;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .bindings
+            .insert(name, Binding { index, declared });
+        index
+    }
+}
+
+struct Resolver {
+    functions: Vec<Function>,
+    // every global a function body may see, top level declarations are hoisted for them
+    globals: HashSet<String>,
+    // globals declared so far by the top level code
+    declared: HashSet<String>,
+    unresolved: Vec<(String, Location)>,
+}
+
+impl Resolver {
+    fn current(&mut self) -> &mut Function {
+        self.functions.last_mut().unwrap()
+    }
+
+    // gives a slot to every name the scope declares and that does not name
+    // something already visible, so closures can refer to it before the
+    // declaration runs
+    fn declare_all(&mut self, statements: &[Stmt]) {
+        for name in statements.iter().filter_map(declaration) {
+            if self.lookup(&name).is_some() || self.is_global(&name) {
+                continue;
+            }
+            let function = self.current();
+            if !function.scopes.last().unwrap().bindings.contains_key(&name) {
+                function.bind(name, false);
+            }
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Slot> {
+        for (depth, function) in self.functions.iter().rev().enumerate() {
+            for scope in function.scopes.iter().rev() {
+                match scope.bindings.get(name) {
+                    Some(binding) if binding.declared || depth > 0 => {
+                        return Some(Slot::Local(depth, binding.index))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    fn is_global(&self, name: &str) -> bool {
+        if self.functions.len() == 1 {
+            self.declared.contains(name)
+        } else {
+            self.globals.contains(name)
+        }
+    }
+
+    fn read(&mut self, identifier: &IdentifierExpr) {
+        let slot = self.lookup(&identifier.value).unwrap_or_default();
+        if slot == Slot::Global && !self.globals.contains(&identifier.value) {
+            self.unresolved
+                .push((identifier.value.clone(), identifier.location.clone()));
+        }
+        identifier.slot.set(slot);
+    }
+
+    fn assign(&mut self, name: &IdentifierExpr) {
+        name.slot.set(self.target(&name.value));
+    }
+
+    fn target(&mut self, name: &str) -> Slot {
+        if let Some(slot) = self.lookup(name) {
+            return slot;
+        }
+        if self.is_global(name) {
+            return Slot::Global;
+        }
+        let script = self.functions.len() == 1;
+        let function = self.current();
+        if script && function.scopes.is_empty() {
+            self.declared.insert(name.to_string());
+            return Slot::Global;
+        }
+        let scope = function.scopes.last_mut().unwrap();
+        if let Some(binding) = scope.bindings.get_mut(name) {
+            binding.declared = true;
+            return Slot::Local(0, binding.index);
+        }
+        Slot::Local(0, function.bind(name.to_string(), true))
+    }
+}
+
+impl Visitor for Resolver {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let(let_stmt) => {
+                self.visit_expr(&let_stmt.value);
+                self.assign(&let_stmt.name);
+            }
+            Stmt::Var(var_stmt) => {
+                self.visit_expr(&var_stmt.value);
+                self.assign(&var_stmt.name);
+            }
+            Stmt::Import(import) => import.slot.set(self.target(&import.name())),
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Identifier(identifier) => self.read(identifier),
+            Expr::Try(try_expr) => {
+                self.visit_block(&try_expr.body);
+                if let Some(catch) = &try_expr.catch {
+                    // the error is only visible inside of the catch block
+                    self.current().scopes.push(Scope::default());
+                    let index = self.current().bind(catch.name.value.clone(), true);
+                    catch.name.slot.set(Slot::Local(0, index));
+                    self.visit_block(&catch.body);
+                    self.current().scopes.pop();
+                }
+                if let Some(finally) = &try_expr.finally {
+                    self.visit_block(finally);
+                }
+            }
+            Expr::Infix(infix) if infix.operator == "." => {
+                self.visit_expr(&infix.left);
+                match &*infix.right {
+                    Expr::Identifier(_) => {}
+                    Expr::Call(call) => {
+                        for argument in call.arguments.iter() {
+                            self.visit_expr(argument);
+                        }
+                    }
+                    right => self.visit_expr(right),
+                }
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockStatement) {
+        self.current().scopes.push(Scope::default());
+        self.declare_all(&block.statements);
+        walk_block(self, block);
+        self.current().scopes.pop();
+    }
+
+    fn visit_function(&mut self, function: &FnExpr) {
+        let mut frame = Function::default();
+        frame.scopes.push(Scope::default());
+        for parameter in function.parameters.iter() {
+            frame.bind(parameter.name.clone(), true);
+        }
+        self.functions.push(frame);
+        self.declare_all(&function.body.statements);
+        for default in function
+            .parameters
+            .iter()
+            .filter_map(|parameter| parameter.default.as_ref())
+        {
+            self.visit_expr(default);
+        }
+        walk_block(self, &function.body);
+        let frame = self.functions.pop().unwrap();
+        function.slots.set(frame.slots);
+        if let Some(name) = &function.name {
+            self.assign(name);
+        }
+    }
+}
+
+fn declaration(statement: &Stmt) -> Option<String> {
+    match statement {
+        Stmt::Let(let_stmt) => Some(let_stmt.name.to_string()),
+        Stmt::Var(var_stmt) => Some(var_stmt.name.to_string()),
+        Stmt::Import(import) => Some(import.name()),
+        Stmt::Export(export) => export.name(),
+        Stmt::Expression(ExpressionStmt {
+            expression: Expr::Function(function),
+        }) => function.name.as_ref().map(|name| name.to_string()),
+        _ => None,
+    }
+}
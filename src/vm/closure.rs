@@ -0,0 +1,45 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    ast::node::write_list,
+    compiler::Proto,
+    evaluator::{environment::EnvironmentRef, objects::Value},
+};
+
+pub struct Frame {
+    pub slots: RefCell<Vec<Option<Value>>>,
+    pub outer: Option<Rc<Frame>>,
+}
+
+impl Frame {
+    pub fn new(slots: usize, outer: Option<Rc<Frame>>) -> Self {
+        Self {
+            slots: RefCell::new(vec![None; slots]),
+            outer,
+        }
+    }
+}
+
+pub struct Closure {
+    pub proto: Rc<Proto>,
+    pub frame: Rc<Frame>,
+    pub globals: EnvironmentRef,
+}
+
+impl Closure {
+    pub fn new(proto: Rc<Proto>, frame: Rc<Frame>, globals: EnvironmentRef) -> Self {
+        Self {
+            proto,
+            frame,
+            globals,
+        }
+    }
+}
+
+impl Display for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fn(")?;
+        write_list(f, &self.proto.parameters, 0)?;
+        write!(f, ") {}", self.proto.body)
+    }
+}
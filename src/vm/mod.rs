@@ -0,0 +1,480 @@
+mod closure;
+
+pub use closure::*;
+
+use std::{cell::Cell, path::Path, rc::Rc};
+
+use crate::{
+    ast::Program,
+    compiler::{compile, Chunk, Op},
+    evaluator::{
+        environment::{Environment, EnvironmentRef},
+        gc, global_built_ins,
+        modules::{build_module, exported_names, ModuleLoader},
+        objects::{ErrorKind, EvalError, HashObj, Ret, Type, Value},
+        operations, type_methods, BuiltInMap, MethodTable, DEFAULT_MAX_DEPTH,
+    },
+    lexer::Location,
+};
+
+pub struct Vm {
+    built_in: BuiltInMap,
+    methods: MethodTable,
+    modules: ModuleLoader,
+    max_depth: usize,
+    // frames of the calls waiting for a built in to call back into the script
+    outer_depth: Cell<usize>,
+}
+
+struct CallFrame {
+    chunk: Rc<Chunk>,
+    ip: usize,
+    frame: Rc<Frame>,
+    globals: EnvironmentRef,
+    base: usize,
+    site: Option<Location>,
+    handlers: Vec<Handler>,
+}
+
+// where a try block continues when an error is raised inside of it
+struct Handler {
+    target: usize,
+    height: usize,
+}
+
+type Stack = Vec<Value>;
+
+fn error(kind: ErrorKind, message: impl Into<String>) -> Value {
+    Value::error(kind, message.into())
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Self {
+            built_in: global_built_ins(),
+            methods: type_methods(),
+            modules: Default::default(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            outer_depth: Cell::new(0),
+        }
+    }
+
+    pub fn with_max_depth(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            ..Self::new()
+        }
+    }
+}
+
+impl Vm {
+    pub fn run(&self, program: &Program, environment: EnvironmentRef) -> Value {
+        let chunk = compile(program, &environment.borrow());
+        let mut frames = vec![CallFrame {
+            frame: gc::track(Rc::new(Frame::new(chunk.slots, None))),
+            chunk,
+            ip: 0,
+            globals: gc::track(environment),
+            base: 0,
+            site: None,
+            handlers: Vec::new(),
+        }];
+        self.drive(&mut frames, &mut Vec::with_capacity(256))
+    }
+
+    // runs until the first frame returns or an error leaves it
+    fn drive(&self, frames: &mut Vec<CallFrame>, stack: &mut Stack) -> Value {
+        loop {
+            match self.execute(frames, stack) {
+                Ok(result) => return result,
+                Err(error) => {
+                    if let Err(error) = unwind(frames, stack, error) {
+                        return error;
+                    }
+                }
+            }
+        }
+    }
+
+    // calls a function of the script for a built in, on frames of its own below
+    // a base frame that ends with the result, the frames of the caller still count
+    // for the recursion depth
+    fn invoke(&self, function: Value, arguments: Vec<Value>, depth: usize) -> Value {
+        let base = Chunk {
+            code: vec![Op::End],
+            locations: vec![None],
+            ..Chunk::default()
+        };
+        let mut frames = vec![CallFrame {
+            chunk: Rc::new(base),
+            ip: 0,
+            frame: Rc::new(Frame::new(0, None)),
+            globals: Environment::shared(Environment::default()),
+            base: 0,
+            site: None,
+            handlers: Vec::new(),
+        }];
+        let mut stack = Vec::new();
+        let outer_depth = self.outer_depth.get();
+        self.outer_depth.set(outer_depth + depth);
+        let result = match self.call(&mut frames, &mut stack, function, arguments, None, false) {
+            Ok(()) => self.drive(&mut frames, &mut stack),
+            Err(error) => error,
+        };
+        self.outer_depth.set(outer_depth);
+        result
+    }
+
+    fn execute(&self, frames: &mut Vec<CallFrame>, stack: &mut Stack) -> Result<Value, Value> {
+        // only calls and returns change the running chunk, they load it again
+        let mut chunk = Rc::clone(&frames.last().unwrap().chunk);
+        loop {
+            let op = {
+                let current = frames.last_mut().unwrap();
+                current.ip += 1;
+                chunk.code[current.ip - 1]
+            };
+            match op {
+                Op::Constant(index) => stack.push(chunk.constants[index].clone()),
+                Op::Nothing => stack.push(Value::Nil),
+                Op::Pop => {
+                    stack.pop();
+                }
+                Op::GetLocal(depth, slot, name) => {
+                    let current = frames.last().unwrap();
+                    let value = frame_at(&current.frame, depth).slots.borrow()[slot].clone();
+                    match value {
+                        Some(value) => stack.push(value),
+                        None => stack.push(self.global(&chunk.names[name], &current.globals)?),
+                    }
+                }
+                Op::SetLocal(depth, slot) => {
+                    let value = stack.last().unwrap().clone();
+                    let current = frames.last().unwrap();
+                    frame_at(&current.frame, depth).slots.borrow_mut()[slot] = Some(value);
+                }
+                Op::GetGlobal(name) => {
+                    let current = frames.last().unwrap();
+                    stack.push(self.global(&chunk.names[name], &current.globals)?);
+                }
+                Op::SetGlobal(name) => {
+                    let value = stack.last().unwrap().clone();
+                    let current = frames.last().unwrap();
+                    current
+                        .globals
+                        .borrow_mut()
+                        .set_variable(chunk.names[name].clone(), value);
+                }
+                Op::Binary(operator) => {
+                    let right = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    stack.push(checked(operations::binary(operator, left, right))?);
+                }
+                Op::Prefix(operator) => {
+                    let value = stack.pop().unwrap();
+                    stack.push(checked(operations::prefix(operator, value))?);
+                }
+                Op::Jump(target) => frames.last_mut().unwrap().ip = target,
+                Op::Branch(otherwise, end) => match stack.pop().unwrap() {
+                    Value::Bool(true) => {}
+                    Value::Bool(false) => frames.last_mut().unwrap().ip = otherwise,
+                    _ => {
+                        stack.push(Value::Nil);
+                        frames.last_mut().unwrap().ip = end;
+                    }
+                },
+                Op::JumpUnless(end) => {
+                    if !matches!(stack.pop().unwrap(), Value::Bool(true)) {
+                        frames.last_mut().unwrap().ip = end;
+                    }
+                }
+                Op::Array(length) => {
+                    let values = stack.split_off(stack.len() - length);
+                    stack.push(Value::array(values));
+                }
+                Op::Hash(index) => {
+                    let keys = &chunk.hashes[index];
+                    let values = stack.split_off(stack.len() - keys.len());
+                    let mut hash = HashObj::default();
+                    for (key, value) in keys.iter().zip(values) {
+                        hash.put(key.clone(), value);
+                    }
+                    stack.push(Value::from(hash));
+                }
+                Op::Index => {
+                    let index = stack.pop().unwrap();
+                    let left = stack.pop().unwrap();
+                    stack.push(checked(operations::index(&left, &index))?);
+                }
+                Op::Closure(index) => {
+                    let current = frames.last().unwrap();
+                    stack.push(Value::Closure(gc::track(Rc::new(Closure::new(
+                        Rc::clone(&chunk.functions[index]),
+                        Rc::clone(&current.frame),
+                        Rc::clone(&current.globals),
+                    )))));
+                }
+                Op::Call(site) | Op::TailCall(site) => {
+                    let call = &chunk.calls[site];
+                    let arguments = stack.split_off(stack.len() - call.arguments);
+                    let function = stack.pop().unwrap();
+                    let tail = matches!(op, Op::TailCall(_));
+                    let location = Some(&call.location);
+                    self.call(frames, stack, function, arguments, location, tail)?;
+                    chunk = Rc::clone(&frames.last().unwrap().chunk);
+                }
+                Op::LookupMethod(name) => {
+                    let name = &chunk.names[name];
+                    let object = stack.last().unwrap();
+                    let function = operations::method(&self.methods, object, name);
+                    stack.push(checked(function)?);
+                }
+                Op::CallMethod(site) => {
+                    let mut arguments = stack.split_off(stack.len() - chunk.calls[site].arguments);
+                    let function = stack.pop().unwrap();
+                    let object = stack.pop().unwrap();
+                    if object.get_type() != Type::Module {
+                        arguments.insert(0, object);
+                    }
+                    let location = Some(&chunk.calls[site].location);
+                    self.call(frames, stack, function, arguments, location, false)?;
+                    chunk = Rc::clone(&frames.last().unwrap().chunk);
+                }
+                Op::Member(name) => {
+                    let name = &chunk.names[name];
+                    let object = stack.pop().unwrap();
+                    match object.as_module() {
+                        Some(module) => match module.get(name) {
+                            Some(value) => stack.push(value.clone()),
+                            None => {
+                                return Err(error(
+                                    ErrorKind::Name,
+                                    format!("module {} has no member '{}'", module.name, name),
+                                ))
+                            }
+                        },
+                        None => {
+                            return Err(error(
+                                ErrorKind::Type,
+                                format!("unsoported operation {} . {}", object.get_type(), name),
+                            ))
+                        }
+                    }
+                }
+                Op::NotFunction => {
+                    if stack.last().unwrap().get_type() == Type::Function {
+                        return Err(error(ErrorKind::Type, "function only be set with let"));
+                    }
+                }
+                Op::Default(slot, skip) => {
+                    let current = frames.last_mut().unwrap();
+                    if current.frame.slots.borrow()[slot].is_some() {
+                        current.ip = skip;
+                    }
+                }
+                Op::Import(index) => {
+                    let import = &chunk.imports[index];
+                    let module =
+                        self.modules
+                            .load(&import.path, &import.location, |program, path| {
+                                self.eval_module(program, path)
+                            });
+                    stack.push(checked(module)?);
+                }
+                Op::Try(target) => {
+                    let height = stack.len();
+                    let current = frames.last_mut().unwrap();
+                    current.handlers.push(Handler { target, height });
+                }
+                Op::EndTry => {
+                    frames.last_mut().unwrap().handlers.pop();
+                }
+                Op::Catch => {
+                    if let Some(Value::Error(error)) = stack.pop() {
+                        stack.push(error.caught());
+                    }
+                }
+                Op::Throw => {
+                    return match stack.pop().unwrap() {
+                        error @ Value::Error(_) => Err(error),
+                        value => Err(Value::from(EvalError::thrown(&value))),
+                    };
+                }
+                Op::Return => {
+                    let value = stack.pop().unwrap();
+                    let finished = frames.pop().unwrap();
+                    if frames.is_empty() {
+                        return Ok(Value::Return(Rc::new(Ret::new(value))));
+                    }
+                    stack.truncate(finished.base);
+                    stack.push(value);
+                    chunk = Rc::clone(&frames.last().unwrap().chunk);
+                }
+                Op::End => return Ok(stack.pop().unwrap()),
+            }
+        }
+    }
+
+    fn call(
+        &self,
+        frames: &mut Vec<CallFrame>,
+        stack: &mut Stack,
+        function: Value,
+        arguments: Vec<Value>,
+        location: Option<&Location>,
+        tail: bool,
+    ) -> Result<(), Value> {
+        if let Value::Closure(closure) = &function {
+            let proto = &closure.proto;
+            if !tail && self.outer_depth.get() + frames.len() > self.max_depth {
+                return Err(error(
+                    ErrorKind::Recursion,
+                    format!(
+                        "maximum recursion depth exceeded calling {}",
+                        proto.describe()
+                    ),
+                ));
+            }
+            if arguments.len() < proto.required
+                || (!proto.rest && arguments.len() > proto.positional)
+            {
+                let expected = match (proto.rest, proto.required == proto.positional) {
+                    (true, _) => format!("at least {}", proto.required),
+                    (false, true) => proto.required.to_string(),
+                    (false, false) => format!("{} to {}", proto.required, proto.positional),
+                };
+                return Err(error(
+                    ErrorKind::Argument,
+                    format!(
+                        "{} expects {} arguments but got {}",
+                        proto.describe(),
+                        expected,
+                        arguments.len()
+                    ),
+                ));
+            }
+            let frame = Frame::new(proto.chunk.slots, Some(Rc::clone(&closure.frame)));
+            {
+                let mut slots = frame.slots.borrow_mut();
+                let mut arguments = arguments.into_iter();
+                for slot in slots.iter_mut().take(proto.positional) {
+                    *slot = arguments.next();
+                }
+                if proto.rest {
+                    slots[proto.positional] = Some(Value::array(arguments.collect()));
+                }
+            }
+            let base = if tail {
+                let finished = frames.pop().unwrap();
+                stack.truncate(finished.base);
+                finished.base
+            } else {
+                stack.len()
+            };
+            frames.push(CallFrame {
+                chunk: Rc::clone(&proto.chunk),
+                ip: 0,
+                frame: gc::track(Rc::new(frame)),
+                globals: Rc::clone(&closure.globals),
+                base,
+                site: location.cloned(),
+                handlers: Vec::new(),
+            });
+            Ok(())
+        } else if let Value::BuiltIn(built_in) = &function {
+            let depth = frames.len();
+            let result = built_in.call(&arguments, &|function, arguments| {
+                self.invoke(function, arguments, depth)
+            });
+            let result = checked(result)?;
+            if tail {
+                // tail calls only exist inside functions, a script or the base frame
+                // of a call back is always left below them
+                let finished = frames.pop().unwrap();
+                stack.truncate(finished.base);
+            }
+            stack.push(result);
+            Ok(())
+        } else {
+            Err(error(
+                ErrorKind::Type,
+                format!("{} is not a function", function.get_type()),
+            ))
+        }
+    }
+
+    fn global(&self, name: &str, globals: &EnvironmentRef) -> Result<Value, Value> {
+        if let Some(value) = globals.borrow().get_variable(name) {
+            Ok(value)
+        } else if let Some(value) = self.built_in.get(name) {
+            Ok(value.clone())
+        } else {
+            Err(error(ErrorKind::Name, format!("unknown word '{}'", name)))
+        }
+    }
+
+    fn eval_module(&self, program: Program, path: &Path) -> Value {
+        let exports = exported_names(&program);
+        let environment = Environment::shared(Environment::default());
+        let result = self.run(&program, Rc::clone(&environment));
+        if result.is_error() {
+            return result;
+        }
+        let variables = environment.borrow();
+        build_module(path, &exports, &variables)
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn frame_at(frame: &Rc<Frame>, depth: usize) -> &Rc<Frame> {
+    let mut frame = frame;
+    for _ in 0..depth {
+        frame = frame.outer.as_ref().unwrap();
+    }
+    frame
+}
+
+fn checked(value: Value) -> Result<Value, Value> {
+    if value.is_error() {
+        Err(value)
+    } else {
+        Ok(value)
+    }
+}
+
+// errors are located where they are raised and then at the call of every
+// function they leave, until a try block catches them
+fn unwind(frames: &mut Vec<CallFrame>, stack: &mut Stack, error: Value) -> Result<(), Value> {
+    let current = frames.last().unwrap();
+    let mut error = locate(error, current.chunk.location(current.ip - 1));
+    loop {
+        let current = frames.last_mut().unwrap();
+        if let Some(handler) = current.handlers.pop() {
+            stack.truncate(handler.height);
+            stack.push(error);
+            current.ip = handler.target;
+            return Ok(());
+        }
+        let site = current.site.clone();
+        error = locate(error, site.as_ref());
+        if frames.len() == 1 {
+            return Err(error);
+        }
+        let finished = frames.pop().unwrap();
+        stack.truncate(finished.base);
+    }
+}
+
+fn locate(error: Value, location: Option<&Location>) -> Value {
+    match (&error, location) {
+        (Value::Error(eval_error), Some(location)) if eval_error.location.is_none() => {
+            Value::from(eval_error.located(location.clone()))
+        }
+        _ => error,
+    }
+}
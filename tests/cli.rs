@@ -0,0 +1,213 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn mila(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mila"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_run_inline_code_with_args() {
+    let output = mila(&["-e", "putsln(args, len(args))", "a", "b"], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("[a,b]2\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_run_from_stdin() {
+    let output = mila(&["run", "-"], "putsln(\"stdin\")");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("stdin\n", String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn test_exit_codes() {
+    let tests = vec![
+        (vec!["-e", "let a = ;"], 65),
+        (vec!["-e", "1 + true"], 70),
+        (vec!["-e", "exit(7)"], 7),
+        (vec!["not_found.mila"], 66),
+        (vec!["run"], 64),
+        (vec!["check", "examples/fizzbuz.mila"], 0),
+        (vec!["check", "-e", "putsln(1)", "examples/fizzbuz.mila"], 0),
+        (vec!["check", "-e", "putsln(nope)"], 65),
+        (vec!["check", "-e"], 64),
+    ];
+    for (args, expected) in tests {
+        let output = mila(&args, "");
+        assert_eq!(Some(expected), output.status.code(), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_exit_flushes_files() {
+    let path = std::env::temp_dir().join(format!("mila_cli_exit_{}.txt", std::process::id()));
+    let source = format!(
+        "let f = fs.open(\"{}\", \"w\"); f.write_line(\"hello\"); puts(\"bye\"); exit(3)",
+        path.display()
+    );
+    for engine in [vec!["-e"], vec!["--vm", "-e"]] {
+        let mut args = engine.clone();
+        args.push(&source);
+        let output = mila(&args, "");
+        assert_eq!(Some(3), output.status.code());
+        assert_eq!("bye", String::from_utf8_lossy(&output.stdout));
+        assert_eq!("hello\n", std::fs::read_to_string(&path).unwrap());
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_runtime_error_reports_location() {
+    let output = mila(&["-e", "putsln(1, to_int(\"x\"))"], "");
+    assert_eq!(Some(70), output.status.code());
+    assert_eq!(
+        "x is not parsable to int in <eval>:1:17\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_max_depth_flag() {
+    let source = "fn deep(n) { if n == 0 { ret 0; } ret 1 + deep(n - 1); } putsln(deep(20))";
+    let output = mila(&["run", "--max-depth", "10", "-e", source], "");
+    assert_eq!(Some(70), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("maximum recursion depth exceeded calling function deep"));
+    let output = mila(&["run", "--max-depth", "30", "-e", source], "");
+    assert_eq!("20\n", String::from_utf8_lossy(&output.stdout));
+    let source = source.replace("deep(20)", "deep(1000000)");
+    for engine in [vec!["run"], vec!["run", "--vm"]] {
+        let mut args = engine.clone();
+        args.extend(["--max-depth", "10000000", "-e", &source]);
+        let output = mila(&args, "");
+        assert_eq!(Some(70), output.status.code(), "{:?}", engine);
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .starts_with("maximum recursion depth exceeded calling function deep"));
+    }
+}
+
+#[test]
+fn test_vm_flag() {
+    let source = "fn fib(n) { if n < 2 { ret n; } ret fib(n - 1) + fib(n - 2); } putsln(fib(15))";
+    let output = mila(&["run", "--vm", "-e", source], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!("610\n", String::from_utf8_lossy(&output.stdout));
+    let output = mila(&["run", "--vm", "-e", "putsln(to_int(\"x\"))"], "");
+    assert_eq!(Some(70), output.status.code());
+    assert_eq!(
+        "x is not parsable to int in <eval>:1:14\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_check_reports_unknown_words() {
+    let output = mila(
+        &["check", "-"],
+        "fn f(a) { ret a + b; }\nputsln(f(1), args, c);",
+    );
+    assert_eq!(Some(65), output.status.code());
+    assert_eq!(
+        "unknown word 'b' in <stdin>:1:19\nunknown word 'c' in <stdin>:2:20\n",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_repl() {
+    let output = mila(&["repl"], "let a = 2\nfn f(x) {\n    x * a\n}\nf(21)\n");
+    assert_eq!(Some(0), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("42"));
+}
+
+#[test]
+fn test_repl_options() {
+    let source = "let a = 2\nfn f(x) {\n    x * a\n}\nf(21)\n";
+    let output = mila(&["repl", "--vm"], source);
+    assert_eq!(Some(0), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("42"));
+    let output = mila(
+        &["repl", "--max-depth", "10"],
+        "fn deep(n) { ret 1 + deep(n); }\ndeep(1)\n",
+    );
+    assert_eq!(Some(0), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("maximum recursion depth exceeded calling function deep"));
+    assert_eq!(Some(64), mila(&["repl", "file.mila"], "").status.code());
+}
+
+#[test]
+fn test_fmt_from_stdin() {
+    let output = mila(&["fmt"], "let a=1 // one");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        "let a = 1; // one\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_test_command() {
+    let dir = std::env::temp_dir().join(format!("mila_cli_test_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("math_test.mila"),
+        "let two = 2;\ntest \"adds\" { assert_eq(1 + 1, two); }\ntest \"fails\" {\n    assert_eq(1, two);\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("ignored.mila"), "assert(false);").unwrap();
+    let dir = dir.to_string_lossy().to_string();
+    for engine in [vec!["test"], vec!["test", "--vm"]] {
+        let output = mila(&[engine, vec![&dir]].concat(), "");
+        assert_eq!(Some(1), output.status.code());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(&format!("ok   {}/math_test.mila > adds\n", dir)));
+        assert!(stdout.contains(&format!(
+            "FAIL {0}/math_test.mila > fails\n    expected 2 but got 1 in {0}/math_test.mila:4:14\n",
+            dir
+        )));
+        assert!(stdout.ends_with("\n1 passed, 1 failed\n"));
+    }
+    let output = mila(&["test", "examples"], "");
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn test_golden_examples() {
+    for engine in [vec!["test", "--golden"], vec!["test", "--golden", "--vm"]] {
+        let output = mila(&[engine, vec!["examples"]].concat(), "");
+        assert_eq!(
+            Some(0),
+            output.status.code(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+    }
+}
+
+#[test]
+fn test_stdin_end_of_input() {
+    let source = "putsln(fs.stdin.read_line(), fs.stdin.lines(), fs.stdin.read_line(), read());";
+    let output = mila(&["-e", source], "one\ntwo\nthree");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        "one[two,three]nilnil\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
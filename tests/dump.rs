@@ -0,0 +1,68 @@
+use std::rc::Rc;
+
+use mila::{
+    dump::{collect_tokens, dump_tokens, dump_tokens_json, dump_tree, dump_tree_json},
+    lexer::Lexer,
+    parser::Parser,
+};
+
+#[test]
+fn test_dump_tokens() {
+    let lexer = Lexer::with_trivia("a.b // c".to_string(), Rc::new("foo.mila".to_string()));
+    let tokens = collect_tokens(lexer);
+    assert_eq!(5, tokens.len(), "wrong number of tokens");
+    let dumped = dump_tokens(&tokens);
+    let lines: Vec<&str> = dumped.lines().collect();
+    assert_eq!(
+        "foo.mila:1:2             Dot                  \".\"",
+        lines[1]
+    );
+    assert_eq!(
+        "foo.mila:1:5             Comment              \"// c\"",
+        lines[3]
+    );
+}
+
+#[test]
+fn test_dump_tokens_json() {
+    let lexer = Lexer::new("let".to_string(), Rc::new("foo.mila".to_string()));
+    let tokens = collect_tokens(lexer);
+    assert_eq!(
+        "[{\"type\":\"Let\",\"value\":\"let\",\"file\":\"foo.mila\",\"line\":1,\"column\":1},\
+        {\"type\":\"Eof\",\"value\":\"\",\"file\":\"foo.mila\",\"line\":1,\"column\":4}]",
+        dump_tokens_json(&tokens)
+    );
+}
+
+#[test]
+fn test_dump_tree() {
+    let program = make_program("a.b(1) + -2");
+    let expected = "Program
+  statements[0]: Expression
+    expression: Infix \"+\"
+      left: Infix \".\"
+        left: Identifier \"a\"
+        right: Call
+          function: Identifier \"b\"
+          arguments[0]: Int \"1\"
+      right: Prefix \"-\"
+        right: Int \"2\"
+";
+    assert_eq!(expected, dump_tree(&program));
+}
+
+#[test]
+fn test_dump_tree_json() {
+    let program = make_program("let a = [\"x\"];");
+    let expected = "{\"op\":\"Program\",\"statements\":[{\"op\":\"Let\",\
+        \"name\":{\"op\":\"Identifier\",\"text\":\"a\"},\
+        \"value\":{\"op\":\"Array\",\"values\":[{\"op\":\"String\",\"text\":\"x\"}]}}]}";
+    assert_eq!(expected, dump_tree_json(&program));
+}
+
+fn make_program(source: &str) -> mila::ast::Program {
+    let lexer = Lexer::new(source.to_string(), Rc::new("foo.mila".to_string()));
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!(0, program.errors.len(), "wrong number of errors");
+    program
+}
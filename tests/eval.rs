@@ -1,13 +1,13 @@
 use std::{cell::RefCell, rc::Rc};
 
 use mila::{
-    ast::node::NodeRef,
     evaluator::{
-        objects::{Array, Boolean, EvalError, Float, Function, Integer, ObjectRef, Str},
-        Evaluator,
+        objects::{ErrorKind, Value},
+        Evaluator, DEFAULT_MAX_DEPTH,
     },
     lexer::Lexer,
     parser::Parser,
+    vm::Vm,
 };
 
 #[test]
@@ -19,8 +19,7 @@ fn test_eval_int_expr() {
     tests.push(("2".to_string(), 2));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -34,8 +33,7 @@ fn test_block_expr() {
     tests.push(("{ 2 }".to_string(), 2));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -49,8 +47,7 @@ fn test_eval_prefix_expr() {
     tests.push(("!2".to_string(), -3));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -69,11 +66,17 @@ fn test_eval_infix_expr() {
     tests.push(("7 | 1".to_string(), 7));
     tests.push(("7 ^ 1".to_string(), 6));
     tests.push(("10 % 2".to_string(), 0));
+    tests.push(("2 ** 10".to_string(), 1024));
+    tests.push(("2 ** 3 ** 2".to_string(), 512));
+    tests.push(("-2 ** 2".to_string(), -4));
+    tests.push(("2 * 3 ** 2".to_string(), 18));
+    tests.push(("7 ** 0".to_string(), 1));
+    tests.push(("-7 / 2".to_string(), -3));
+    tests.push(("-7 % 2".to_string(), -1));
 
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -137,13 +140,64 @@ fn test_eval_infix_expr_error() {
         "10.0 | 10.0".to_string(),
         "unsoported operation float | float".to_string(),
     ));
+    tests.push(("10 ** 30".to_string(), "10 ** 30 overflows int".to_string()));
+    tests.push(("1 / 0".to_string(), "1 / 0 divides by zero".to_string()));
+    tests.push(("1 % 0".to_string(), "1 % 0 divides by zero".to_string()));
+    tests.push((
+        "(-9223372036854775807 - 1) % -1".to_string(),
+        "-9223372036854775808 % -1 overflows int".to_string(),
+    ));
+    tests.push((
+        "(-9223372036854775807 - 1) / -1".to_string(),
+        "-9223372036854775808 / -1 overflows int".to_string(),
+    ));
+    tests.push((
+        "\"a\" ** 2".to_string(),
+        "unsoported operation string ** int".to_string(),
+    ));
+    tests.push((
+        "10.0 % 3".to_string(),
+        "unsoported operation float % int".to_string(),
+    ));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<EvalError>().unwrap();
+        let evaluated = evaluated.as_error().unwrap();
         assert_eq!(expected, evaluated.message, "invalid value")
     }
 }
 
+#[test]
+fn test_eval_int_overflow() {
+    let tests = [
+        (
+            "9223372036854775807 + 1",
+            "9223372036854775807 + 1 overflows int",
+        ),
+        (
+            "(-9223372036854775807 - 1) - 1",
+            "-9223372036854775808 - 1 overflows int",
+        ),
+        (
+            "4611686018427387904 * 2",
+            "4611686018427387904 * 2 overflows int",
+        ),
+        ("1 << 64", "1 << 64 overflows int"),
+        ("3 << 62", "3 << 62 overflows int"),
+        ("1 << -1", "1 << -1 overflows int"),
+        ("1 >> 64", "1 >> 64 overflows int"),
+        (
+            "-(-9223372036854775807 - 1)",
+            "-(-9223372036854775808) overflows int",
+        ),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        let evaluated = evaluated.as_error().unwrap();
+        assert_eq!(expected, evaluated.message, "{}", source);
+        assert_eq!(ErrorKind::Value, evaluated.kind, "{}", source);
+    }
+}
+
 #[test]
 fn test_eval_infix_with_float_expr() {
     let mut tests: Vec<(String, f64)> = Vec::new();
@@ -153,11 +207,17 @@ fn test_eval_infix_with_float_expr() {
     tests.push(("5.0 / 5.0".to_string(), 1.0));
     tests.push(("5.0 * 5.0".to_string(), 25.0));
     tests.push(("5.0 * 5.0 + 10.0 / 10.0".to_string(), 26.0));
+    tests.push(("2.0 ** 3.0".to_string(), 8.0));
+    tests.push(("4 ** 0.5".to_string(), 2.0));
+    tests.push(("2.5 ** 2".to_string(), 6.25));
+    tests.push(("2 ** -2".to_string(), 0.25));
+    tests.push(("7.5 % 2.0".to_string(), 1.5));
+    tests.push(("-7.5 % 2.0".to_string(), -1.5));
+    tests.push(("7.5 % -2.0".to_string(), 1.5));
 
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Float>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_float().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -180,8 +240,7 @@ fn test_eval_infix_comparator_expr() {
 
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Boolean>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_bool().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -194,8 +253,7 @@ fn test_eval_float_expr() {
     tests.push(("1.0".to_string(), 1.0));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Float>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_float().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -208,8 +266,7 @@ fn test_eval_float_prefix_expr() {
     tests.push(("1.0".to_string(), 1.0));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Float>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_float().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -221,8 +278,7 @@ fn test_eval_boolean_expr() {
     tests.push(("false".to_string(), false));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Boolean>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_bool().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -236,8 +292,7 @@ fn test_eval_prefix_boolean_expr() {
     tests.push(("!!true".to_string(), true));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Boolean>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_bool().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -255,8 +310,7 @@ fn test_eval_infix_boolean_expr() {
     tests.push(("true && !false || false".to_string(), true));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Boolean>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_bool().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -272,8 +326,7 @@ fn test_eval_str_expr() {
     tests.push(("\"10.0\"".to_string(), "10.0".to_string()));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Str>().unwrap();
-        assert_eq!(expected, evaluated.value, "invalid value")
+        assert_eq!(expected, evaluated.as_str().unwrap(), "invalid value")
     }
 }
 
@@ -285,8 +338,7 @@ fn test_let_stmt() {
     tests.push(("let a = 1; let b = 4; let c = 10; a;".to_string(), 1));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -299,8 +351,7 @@ fn test_var_stmt() {
     tests.push(("var a = 1; let b = 4; let c = 10; a;".to_string(), 1));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -313,7 +364,7 @@ fn test_get_unnexistent_identifier_should_be_error() {
     tests.push(("mila;".to_string(), "unknown word 'mila'".to_string()));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<EvalError>().unwrap();
+        let evaluated = evaluated.as_error().unwrap();
         assert_eq!(expected, evaluated.message, "invalid value")
     }
 }
@@ -327,7 +378,7 @@ fn test_eval_function() {
     tests.push("let sum = fn (a, b) { a + b } sum;".to_string());
     for source in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Function>().is_some();
+        let evaluated = matches!(evaluated, Value::Function(_));
         assert!(evaluated, "invalid value")
     }
 }
@@ -340,7 +391,7 @@ fn test_eval_array() {
     tests.push(("[1,1,3]".to_string(), 3));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Array>().unwrap();
+        let evaluated = evaluated.as_array().unwrap();
         let value = evaluated.values.borrow().len();
         assert_eq!(expected, value, "invalid value")
     }
@@ -357,8 +408,7 @@ fn test_eval_if_expr() {
     ));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -377,8 +427,7 @@ fn test_eval_call_expr() {
     tests.push(("let sum = fn (a, b) { a + b }} sum(1, 2);".to_string(), 3));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -389,8 +438,7 @@ fn test_built_in_expr() {
     tests.push(("len(\"abc\")".to_string(), 3));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -404,8 +452,7 @@ fn test_built_str_in_expr() {
     tests.push(("eputsln(\"abc\")".to_string(), "abc".to_string()));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Str>().unwrap();
-        let value = evaluated.value.clone();
+        let value = evaluated.as_str().unwrap().to_string();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -419,8 +466,7 @@ fn test_eval_while_expr() {
     ));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -432,8 +478,7 @@ fn test_eval_index_arr() {
     tests.push(("[1, 2, 3, 10, 4][3]".to_string(), 10));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -445,12 +490,41 @@ fn test_eval_index_string() {
     tests.push(("\"abcd\"[3]".to_string(), "d".to_string()));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Str>().unwrap();
-        let value = evaluated.value.clone();
+        let value = evaluated.as_str().unwrap().to_string();
         assert_eq!(expected, value, "invalid value")
     }
 }
 
+#[test]
+fn test_eval_index_expressions() {
+    let tests = vec![
+        ("fn f() { ret [1, 2]; } f()[1]", "2"),
+        ("let matrix = [[1, 2], [3, 4]]; matrix[1][0]", "3"),
+        ("\"  ab \".trim()[1]", "b"),
+        (
+            "let people = [|name: \"mila\",|]; people[0][\"name\"]",
+            "mila",
+        ),
+        ("let arr = [1, 2, 3]; arr[-1] + arr[-3]", "4"),
+        ("\"abcd\"[-2]", "c"),
+        ("([1] + [2])[0]", "unsoported operation array + array"),
+        ("[1, 2][2]", "invalid index 2"),
+        ("[1, 2][-3]", "invalid index -3"),
+        ("\"ab\"[-3]", "invalid string position -3"),
+        ("1[0]", "unsuported operation int[int]"),
+        ("[1][\"a\"]", "unsuported operation array[string]"),
+        (
+            "let log = []; fn f() { log.push(\"left\"); ret [1]; } fn g() { log.push(\"index\"); ret 0; } f()[g()]; log",
+            "[left,index]",
+        ),
+        ("missing[also_missing]", "unknown word 'missing'"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
 #[test]
 fn test_eval_arr_function() {
     let mut tests: Vec<(String, isize)> = Vec::new();
@@ -472,8 +546,7 @@ fn test_eval_arr_function() {
     ));
     for (source, expected) in tests {
         let evaluated = test_eval(source);
-        let evaluated = evaluated.as_any().downcast_ref::<Integer>().unwrap();
-        let value = evaluated.value;
+        let value = evaluated.as_int().unwrap();
         assert_eq!(expected, value, "invalid value")
     }
 }
@@ -483,6 +556,14 @@ fn test_eval_hash_obj() {
     let tests = vec![
         ("| fruit: \"banana\", sum: 1 + 2, |[\"fruit\"]", "banana"),
         ("let obj = | array: [1, 2, 3], |; obj[\"array\"]", "[1,2,3]"),
+        (
+            "| b: 1, c: 2, a: 3, |",
+            "|\n\ta: 3, \n\tb: 1, \n\tc: 2, \n|",
+        ),
+        (
+            "let log = []; | d: log.push(\"d\"), a: log.push(\"a\"), c: log.push(\"c\"), b: log.push(\"b\"), |; log",
+            "[d,a,c,b]",
+        ),
     ];
     for (source, expected) in tests {
         let evalueted = test_eval(source.to_string());
@@ -490,11 +571,651 @@ fn test_eval_hash_obj() {
     }
 }
 
-fn test_eval(source: String) -> ObjectRef {
-    let lexer = Lexer::new(source, Rc::new("foo.bzr".to_string()));
-    let mut parser = Parser::new(lexer);
-    let program: NodeRef = Box::new(parser.parse_program());
-    let eval: Evaluator = Evaluator::new();
-    let env = Rc::new(RefCell::new(Default::default()));
-    eval.eval(Some(&program), env).unwrap()
+#[test]
+fn test_eval_fn_parameters() {
+    let tests = vec![
+        ("fn f(a, b = 2) { ret a + b; } f(1)", "3"),
+        ("fn f(a, b = 2) { ret a + b; } f(1, 5)", "6"),
+        ("fn f(a, b = a * 3) { ret b; } f(2)", "6"),
+        ("fn f(first, ...rest) { ret rest; } f(1, 2, 3)", "[2,3]"),
+        ("fn f(first, ...rest) { ret rest; } f(1)", "[]"),
+        ("let a = 1; fn f(a) { ret a; } f(5); a", "1"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string())
+    }
+}
+
+#[test]
+fn test_eval_fn_arity_error() {
+    let tests = vec![
+        (
+            "fn f(a, b) { ret a; } f(1, 2, 3)",
+            "function f expects 2 arguments but got 3",
+        ),
+        (
+            "fn f(a, b = 1) { ret a; } f()",
+            "function f expects 1 to 2 arguments but got 0",
+        ),
+        (
+            "let f = fn(a, ...rest) { ret a; }; f()",
+            "anonymous function expects at least 1 arguments but got 0",
+        ),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        let error = evaluated.as_error().unwrap();
+        assert_eq!(expected, error.message)
+    }
+}
+
+#[test]
+fn test_eval_argument_error_propagation() {
+    let tests = vec![
+        (
+            "fn f(a, b) { ret a; } f(1, x)",
+            "unknown word 'x'",
+            "foo.bzr:1:28",
+        ),
+        (
+            "putsln(1, 2 + true)",
+            "unsoported operation int + bool",
+            "foo.bzr:1:13",
+        ),
+        ("[1].push(2, y)", "unknown word 'y'", "foo.bzr:1:13"),
+        ("\"a\".trim(z)", "unknown word 'z'", "foo.bzr:1:10"),
+        (
+            "\"a\".trim(to_int(\"b\"))",
+            "b is not parsable to int",
+            "foo.bzr:1:16",
+        ),
+        (
+            "len(1, 2)",
+            "invalid number of arguments, len expected 1 arguments but got 2",
+            "foo.bzr:1:4",
+        ),
+        (
+            "to_str()",
+            "invalid number of arguments, to_str expected 1 arguments but got 0",
+            "foo.bzr:1:7",
+        ),
+        (
+            "read(1)",
+            "invalid number of arguments, read expected 0 arguments but got 1",
+            "foo.bzr:1:5",
+        ),
+    ];
+    for (source, message, location) in tests {
+        let evaluated = test_eval(source.to_string());
+        let error = evaluated.as_error().unwrap();
+        assert_eq!(message, error.message);
+        assert_eq!(location, error.location.as_ref().unwrap().to_string());
+    }
+}
+
+#[test]
+fn test_eval_tail_calls() {
+    let tests = vec![
+        (
+            "fn count(n, acc) { if n == 0 { ret acc; } ret count(n - 1, acc + 1); } count(50000, 0)",
+            "50000",
+        ),
+        (
+            "fn even(n) { if n == 0 { ret true; } ret odd(n - 1); }
+            fn odd(n) { if n == 0 { ret false; } ret even(n - 1); }
+            even(30001)",
+            "false",
+        ),
+        ("fn f() { ret len([1, 2]); } f()", "2"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string())
+    }
+}
+
+#[test]
+fn test_eval_nil() {
+    let tests = vec![
+        ("nil", "nil"),
+        ("if false { 1 }", "nil"),
+        ("fn f() { ret; } f()", "nil"),
+        ("fn f() {} f()", "nil"),
+        ("let x = nil; [x, 1]", "[nil,1]"),
+        ("nil == nil", "true"),
+        ("\"a\" == nil", "false"),
+        ("1 != nil", "true"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+    let evaluated = test_eval("nil + 1".to_string());
+    assert_eq!(
+        "unsoported operation nil + int",
+        evaluated.as_error().unwrap().message
+    );
+}
+
+#[test]
+fn test_eval_try_catch() {
+    let tests =
+        vec![
+        ("try { 1 } catch e { 2 }", "1"),
+        ("try { to_int(\"a\") } catch e { e[\"kind\"] }", "value"),
+        ("try { to_int(\"a\") } catch e { e[\"message\"] }", "a is not parsable to int"),
+        ("try { to_int(\"a\") } catch e { e[\"location\"] }", "foo.bzr:1:13"),
+        ("try { 1 + \"a\" } catch e { e[\"kind\"] }", "type"),
+        ("try { 1 + \"a\" } catch e { e[\"location\"] }", "foo.bzr:1:9"),
+        ("try { [1][3] } catch e { e[\"kind\"] }", "index"),
+        ("try { unknown } catch e { e[\"kind\"] }", "name"),
+        ("try { len(1, 2) } catch e { e[\"kind\"] }", "argument"),
+        ("try { read_file_as_string(\"/no/such/file\") } catch e { e[\"kind\"] }", "io"),
+        ("try { throw \"boom\"; } catch e { e[\"kind\"] + \" \" + e[\"message\"] }", "error boom"),
+        ("try { throw 1 + 2; } catch e { e[\"message\"] }", "3"),
+        ("try { throw 5; } catch e { e[\"value\"] + 1 }", "6"),
+        ("try { throw [1, 2]; } catch e { e[\"value\"][1] }", "2"),
+        ("try { try { throw 5; } catch e { throw e; } } catch e { e[\"value\"] }", "5"),
+        ("try { throw |message: \"no\",|; } catch e { e[\"value\"][\"message\"] }", "no"),
+        ("try { 1 + nil } catch e { e[\"value\"] == nil }", "true"),
+        ("try { throw |message: \"no\", kind: \"auth\",|; } catch e { e[\"kind\"] }", "auth"),
+        ("fn f() { throw \"inner\"; } try { f() } catch e { e[\"location\"] }", "foo.bzr:1:10"),
+        ("fn f() { ret 1 + nil; } try { f() } catch e { e[\"location\"] }", "foo.bzr:1:16"),
+        ("try { try { throw \"a\"; } catch e { throw e; } } catch e { e[\"message\"] }", "a"),
+        ("try { throw \"a\"; } catch e { e } putsln(e)", "unknown word 'e'"),
+        ("fn f(n) { if n == 0 { throw \"zero\"; } ret f(n - 1); } try { f(3) } catch e { 0 }", "0"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_error_locations() {
+    let tests = vec![
+        (
+            "try { 1 + \"a\" } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[type,foo.bzr:1:9]",
+        ),
+        (
+            "try { -\"a\" } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[type,foo.bzr:1:7]",
+        ),
+        (
+            "try { [1, 2][3] } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[index,foo.bzr:1:13]",
+        ),
+        (
+            "try { missing } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[name,foo.bzr:1:7]",
+        ),
+        (
+            "try { math.nope } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[name,foo.bzr:1:11]",
+        ),
+        (
+            "try { | a: 1, |.kind } catch e { [e[\"kind\"], e[\"message\"], e[\"location\"]] }",
+            "[type,unsoported operation hash . kind,foo.bzr:1:16]",
+        ),
+        (
+            "try { 10 ** 30 } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[value,foo.bzr:1:10]",
+        ),
+        (
+            "try { var f = fn() {}; } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[type,foo.bzr:1:11]",
+        ),
+        (
+            "try { len(1, 2) } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[argument,foo.bzr:1:10]",
+        ),
+        (
+            "try { to_int(\"a\") } catch e { [e[\"kind\"], e[\"location\"]] }",
+            "[value,foo.bzr:1:13]",
+        ),
+        (
+            "to_str(try { [][0] } catch e { e[\"location\"] })",
+            "foo.bzr:1:16",
+        ),
+        (
+            "fn f(a) { ret a[\"x\"]; }\ntry { f(1) } catch e { e[\"location\"] }",
+            "foo.bzr:1:16",
+        ),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+    let evaluated = test_eval("[1, 2][3]".to_string());
+    assert_eq!(
+        "invalid index 3 in foo.bzr:1:7",
+        evaluated.as_error().unwrap().report()
+    );
+}
+
+#[test]
+fn test_eval_finally() {
+    let tests = vec![
+        ("let log = []; let v = try { log.push(1); 5 } finally { log.push(2) }; [v, log]", "[5,[1,2]]"),
+        ("let log = []; try { throw \"a\"; } catch e { log.push(1) } finally { log.push(2) }", "[1,2]"),
+        ("fn f(log) { try { ret 1; } finally { log.push(2); } } let log = []; [f(log), log]", "[1,[2]]"),
+        ("fn f(log) { try { throw \"a\"; } catch e { ret 1; } finally { log.push(2); } } let log = []; [f(log), log]", "[1,[2]]"),
+        ("fn f() { try { ret 1; } finally { ret 2; } } f()", "2"),
+        ("try { try { throw \"a\"; } finally { 1 } } catch e { e[\"message\"] }", "a"),
+        ("try { try { 1 } finally { throw \"b\"; } } catch e { e[\"message\"] }", "b"),
+        ("try { throw \"a\"; } catch e { throw \"b\"; } finally { 1 }", "b"),
+        ("fn count(n) { try { if n == 0 { ret 0; } ret count(n - 1); } finally { 1 } } count(10)", "0"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_uncaught_throw() {
+    let evaluated =
+        test_eval("putsln(1);\nthrow |message: \"oops\", kind: \"custom\",|;".to_string());
+    let error = evaluated.as_error().unwrap();
+    assert_eq!("oops in foo.bzr:2:1", error.report());
+    assert_eq!(ErrorKind::Custom("custom".to_string()), error.kind);
+}
+
+#[test]
+fn test_eval_assertions() {
+    let tests = vec![
+        ("assert(1 == 1)", "nil"),
+        ("assert(1 == 2)", "assertion failed"),
+        ("assert(false, \"custom\")", "custom"),
+        ("assert(1)", "assert expects a bool but got int"),
+        ("assert()", "expected a condition and an optional message"),
+        ("assert_eq([1, |a: 2,|], [1, |a: 2,|])", "nil"),
+        ("assert_eq(1, 1.0)", "expected 1 but got 1"),
+        ("assert_eq(\"a\", \"b\")", "expected \"b\" but got \"a\""),
+        ("let a = [1]; a.push(a); assert_eq(a, a)", "nil"),
+        (
+            "let a = [1]; a.push(a); let b = [1]; b.push(b); assert_eq(a, b)",
+            "nil",
+        ),
+        (
+            "let a = [1]; a.push(a); let b = [2]; b.push(b); assert_eq(a, b)",
+            "expected [2,...] but got [1,...]",
+        ),
+        ("let a = [1]; a.push([a]); a", "[1,[...]]"),
+        ("assert_error(fn() { 1 + nil })[\"kind\"]", "type"),
+        (
+            "assert_error(fn() { throw \"no\"; }, \"error\")[\"message\"]",
+            "no",
+        ),
+        ("assert_error(fn() { 1 })", "expected an error but got 1"),
+        (
+            "assert_error(fn() { [][1] }, \"type\")",
+            "expected a type error but got index: invalid index 1",
+        ),
+        ("assert_error(1)", "int is not a function"),
+        (
+            "assert_error(fn() { ret len([1]); })",
+            "expected an error but got 1",
+        ),
+        ("test \"skipped\" { throw \"no\"; } 1", "1"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+    let evaluated = test_eval("let a = 1;\nassert_eq(a, 2)".to_string());
+    let error = evaluated.as_error().unwrap();
+    assert_eq!("expected 2 but got 1 in foo.bzr:2:10", error.report());
+    assert_eq!(ErrorKind::Assertion, error.kind);
+}
+
+#[test]
+fn test_eval_fs() {
+    let dir = write_modules("fs", &[("data.txt", "data")]);
+    let dir = dir.display();
+    let tests = vec![
+        (format!("fs.read_file(\"{}/data.txt\")", dir), "data".to_string()),
+        (
+            format!("let p = \"{}/out.txt\"; fs.write_file(p, \"a\"); fs.append_file(p, \"b\"); fs.read_file(p)", dir),
+            "ab".to_string(),
+        ),
+        (
+            format!("let d = \"{0}/x/y\"; fs.mkdir(d); [fs.exists(d), fs.is_dir(d), fs.is_dir(\"{0}/data.txt\")]", dir),
+            "[true,true,false]".to_string(),
+        ),
+        (
+            format!("let size = fs.copy(\"{0}/data.txt\", \"{0}/copy.txt\"); fs.rename(\"{0}/copy.txt\", \"{0}/moved.txt\"); [size, fs.list_dir(\"{0}\")]", dir),
+            "[4,[data.txt,moved.txt,out.txt,x]]".to_string(),
+        ),
+        (
+            format!("let m = fs.metadata(\"{}/data.txt\"); [m[\"size\"], m[\"is_dir\"], m[\"mtime\"] > 0]", dir),
+            "[4,false,true]".to_string(),
+        ),
+        (
+            format!("let f = \"{0}/gone.txt\"; fs.write_file(f, \"\"); fs.remove(f); let d = \"{0}/x/z\"; fs.mkdir(d); fs.remove(d); [fs.exists(f), fs.exists(d)]", dir),
+            "[false,false]".to_string(),
+        ),
+        (
+            format!("try {{ fs.remove(\"{}/x/missing\") }} catch e {{ e[\"kind\"] }}", dir),
+            "io".to_string(),
+        ),
+        (
+            "fs.exists(1)".to_string(),
+            "fs.exists expects strings but got int".to_string(),
+        ),
+        (
+            "fs.copy(\"a\")".to_string(),
+            "invalid number of arguments, fs.copy expected 2 arguments but got 1".to_string(),
+        ),
+        ("fs.nope".to_string(), "module fs has no member 'nope'".to_string()),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.clone());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_math() {
+    let tests = vec![
+        ("math.sqrt(16)", "4"),
+        ("math.sqrt(2.25)", "1.5"),
+        ("[math.pow(2, 10), math.pow(2, -1), math.pow(4, 0.5)]", "[1024,0.5,2]"),
+        ("math.pow(10, 30)", "10 ** 30 overflows int"),
+        ("[math.abs(-3), math.abs(-2.5)]", "[3,2.5]"),
+        (
+            "[math.floor(2.7), math.ceil(2.1), math.round(-2.5), math.trunc(-2.7), math.floor(3)]",
+            "[2,3,-3,-2,3]",
+        ),
+        ("math.floor(math.inf)", "cannot convert inf to int"),
+        ("[math.min(3, 1.5, 2), math.max(1, 7, 2), math.min(4)]", "[1.5,7,4]"),
+        ("math.max()", "math.max expects at least one argument"),
+        ("[math.max(9007199254740993, 9007199254740992), math.min(9007199254740993, 9007199254740992)]", "[9007199254740993,9007199254740992]"),
+        ("[math.max(1, math.nan, 2), math.min(math.nan, 1), math.max(2, 1, math.nan)]", "[NaN,NaN,NaN]"),
+        ("[math.max(1, 1.0), math.min(1.0, 1), math.max(-0.0, 0)]", "[1,1,0]"),
+        ("[math.clamp(15, 0, 10), math.clamp(-1, 0, 10), math.clamp(0.5, 0, 1)]", "[10,0,0.5]"),
+        ("math.clamp(1, 2, 0)", "math.clamp lower bound 2 is greater than 0"),
+        ("[math.sin(0), math.cos(0), math.tan(0), math.exp(0)]", "[0,1,0,1]"),
+        ("math.atan2(1, 1) * 4.0 == math.pi", "true"),
+        ("[math.log(math.e), math.log(8, 2)]", "[1,3]"),
+        ("math.log(8, 2, 1)", "invalid number of arguments, math.log expected 1 or 2 arguments but got 3"),
+        ("math.log()", "invalid number of arguments, math.log expected 1 or 2 arguments but got 0"),
+        ("[math.pi, math.e]", "[3.141592653589793,2.718281828459045]"),
+        ("[math.inf, math.nan]", "[inf,NaN]"),
+        ("[math.is_nan(math.nan), math.is_nan(1), math.is_finite(math.inf), math.is_finite(1.5)]", "[true,false,false,true]"),
+        ("math.sqrt(\"4\")", "math.sqrt expects numbers but got string"),
+        ("math.atan2(1)", "invalid number of arguments, math.atan2 expected 2 arguments but got 1"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_random() {
+    let tests = vec![
+        ("random.seed(42); random.random()", "0.08386297105988216"),
+        (
+            "random.seed(42); [random.int(1, 6), random.choice([\"a\", \"b\", \"c\"]), random.shuffle([1, 2, 3, 4, 5])]",
+            "[1,a,[3,1,4,2,5]]",
+        ),
+        (
+            "random.seed(7); let a = [random.random(), random.int(0, 100)]; random.seed(7); [a[0] == random.random(), a[1] == random.int(0, 100)]",
+            "[true,true]",
+        ),
+        (
+            "let seen = [0, 0, 0]; let i = 0; while i < 300 { let n = random.int(-1, 1); seen.replace(n + 1, seen[n + 1] + 1); let i = i + 1; }; [seen[0] > 0, seen[1] > 0, seen[2] > 0]",
+            "[true,true,true]",
+        ),
+        ("let r = random.random(); r >= 0.0 && r < 1.0", "true"),
+        ("[random.int(5, 5), random.choice([1])]", "[5,1]"),
+        ("random.int(3, 1)", "random.int lower bound 3 is greater than 1"),
+        ("random.int(1, 2.0)", "random.int expects ints but got float"),
+        ("random.choice([])", "array is empty"),
+        ("random.shuffle(\"abc\")", "random.shuffle expects an array but got string"),
+        ("random.seed()", "invalid number of arguments, random.seed expected 1 arguments but got 0"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_time() {
+    let tests = vec![
+        ("time.format(0)", "1970-01-01T00:00:00Z"),
+        ("time.format(951782400)", "2000-02-29T00:00:00Z"),
+        ("time.format(1709211909.5)", "2024-02-29T13:05:09.500Z"),
+        ("time.format(-1)", "1969-12-31T23:59:59Z"),
+        ("time.parse(\"2024-02-29T13:05:09Z\")", "1709211909"),
+        ("time.parse(\"2024-02-29\")", "1709164800"),
+        ("time.parse(\"2024-02-29 15:05:09.25+02:00\")", "1709211909.25"),
+        ("time.parse(\"2024-02-29T08:35-0430\")", "1709211900"),
+        (
+            "time.format(time.parse(\"9999-12-31T23:59:59.999Z\"))",
+            "9999-12-31T23:59:59.999Z",
+        ),
+        ("time.parse(\"2023-02-29\")", "invalid ISO-8601 date '2023-02-29'"),
+        ("time.parse(\"2024-02-29T25:00Z\")", "invalid ISO-8601 date '2024-02-29T25:00Z'"),
+        ("time.parse(\"2024-2-29\")", "invalid ISO-8601 date '2024-2-29'"),
+        (
+            "let d = time.utc(1709211909.5); [d[\"year\"], d[\"month\"], d[\"day\"], d[\"hour\"], d[\"minute\"], d[\"second\"], d[\"millisecond\"], d[\"weekday\"], d[\"yday\"]]",
+            "[2024,2,29,13,5,9,500,4,60]",
+        ),
+        ("time.from_utc(time.utc(1709211909.5))", "1709211909.5"),
+        ("time.from_utc(|year: 2000, month: 3, day: 1,|)", "951868800"),
+        (
+            "time.from_utc(|year: 2023, month: 2, day: 29,|)",
+            "invalid date 2023-02-29 00:00:00.000",
+        ),
+        ("time.from_utc(|year: 2023,|)", "time.from_utc expects a month"),
+        (
+            "time.from_utc(|year: 2023, month: \"1\", day: 1,|)",
+            "time.from_utc expects an int month but got string",
+        ),
+        ("time.format(400000000000)", "year 14645 is out of the range 0 to 9999"),
+        (
+            "let start = time.monotonic(); time.sleep(5); time.monotonic() - start >= 0.005",
+            "true",
+        ),
+        ("time.now() > 1700000000.0", "true"),
+        ("time.sleep(-1)", "time.sleep expects a positive duration but got -1"),
+        (
+            "time.sleep(10.0 ** 30)",
+            "time.sleep expects a positive duration but got 1000000000000000000000000000000",
+        ),
+        ("time.sleep(math.nan)", "time.sleep expects a positive duration but got NaN"),
+        ("time.format(\"now\")", "time.format expects a number but got string"),
+        ("time.now(1)", "invalid number of arguments, time.now expected 0 arguments but got 1"),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.to_string());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_file_handles() {
+    let dir = write_modules("files", &[("log.txt", "a\r\nb\n\nc")]);
+    let log = dir.join("log.txt").display().to_string();
+    let out = dir.join("out.txt").display().to_string();
+    let tests = vec![
+        (format!("fs.open(\"{}\", \"r\").lines()", log), "[a,b,,c]".to_string()),
+        (
+            format!("let f = fs.open(\"{}\", \"r\"); let seen = []; let line = f.read_line(); while line != nil {{ seen.push(line); let line = f.read_line(); }}; [seen, f.read_line()]", log),
+            "[[a,b,,c],nil]".to_string(),
+        ),
+        (
+            format!("let f = fs.open(\"{0}\", \"w\"); f.write(1, \"-\"); f.write_line(true); f.close(); let f = fs.open(\"{0}\", \"a\"); f.write_line(\"x\"); f.flush(); fs.open(\"{0}\", \"r\").lines()", out),
+            "[1-true,x]".to_string(),
+        ),
+        (
+            format!("let f = fs.open(\"{}\", \"r\"); f.close(); f.close(); try {{ f.read_line() }} catch e {{ e[\"message\"] }}", log),
+            format!("file {} is closed", log),
+        ),
+        (
+            format!("fs.open(\"{}\", \"r\").write(\"a\")", log),
+            format!("file {} is not open for writing", log),
+        ),
+        (
+            "fs.stdout.read_line()".to_string(),
+            "file <stdout> is not open for reading".to_string(),
+        ),
+        (
+            format!("fs.open(\"{}\", \"rw\")", log),
+            "unknown file mode rw, expected r, w or a".to_string(),
+        ),
+        (
+            format!("try {{ fs.open(\"{}/missing\", \"r\") }} catch e {{ e[\"kind\"] }}", dir.display()),
+            "io".to_string(),
+        ),
+        (format!("fs.open(\"{}\", \"r\").lines(1)", log), "invalid number of arguments, file.lines expected 0 arguments but got 1".to_string()),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval(source.clone());
+        assert_eq!(expected, evaluated.to_string(), "{}", source)
+    }
+}
+
+#[test]
+fn test_eval_max_recursion_depth() {
+    let deep = "fn deep(n) { if n == 0 { ret 0; } ret 1 + deep(n - 1); }";
+    let evaluated = test_eval_with(&format!("{} deep(100)", deep), "foo.bzr", 50);
+    let error = evaluated.as_error().unwrap();
+    assert_eq!(
+        "maximum recursion depth exceeded calling function deep",
+        error.message
+    );
+    let evaluated = test_eval_with(&format!("{} deep(49)", deep), "foo.bzr", 50);
+    assert_eq!("49", evaluated.to_string());
+    // calls back from built ins count for the depth too
+    let through = "fn f() { ret assert_error(fn() { f() }, \"recursion\")[\"kind\"]; }";
+    let evaluated = test_eval_with(
+        &format!("{} try {{ f() }} catch e {{ e[\"kind\"] }}", through),
+        "foo.bzr",
+        50,
+    );
+    assert_eq!("assertion", evaluated.to_string());
+}
+
+#[test]
+fn test_eval_import() {
+    let dir = write_modules(
+        "import",
+        &[
+            (
+                "lib.mila",
+                "export fn double(x) { ret x * 2; } fn hidden() { ret 1; } export let name = \"lib\";",
+            ),
+            ("counter.mila", "import lib; export let count = lib.double(1);"),
+        ],
+    );
+    let tests = vec![
+        ("import lib; lib.double(21)", "42"),
+        ("import \"lib.mila\" as l; l.name", "lib"),
+        ("import lib as l; l", "module lib"),
+        (
+            "import counter; import lib; counter.count + lib.double(2)",
+            "6",
+        ),
+        (
+            "import lib; lib.hidden()",
+            "module lib has no member 'hidden'",
+        ),
+        (
+            "import lib; lib.missing",
+            "module lib has no member 'missing'",
+        ),
+    ];
+    for (source, expected) in tests {
+        let evaluated = test_eval_file(&dir, source);
+        assert_eq!(expected, evaluated.to_string())
+    }
+}
+
+#[test]
+fn test_eval_import_once() {
+    let dir = write_modules("import_once", &[("state.mila", "export let items = [];")]);
+    let source = "import state; import state as other; other.items.push(1); state.items";
+    assert_eq!("[1]", test_eval_file(&dir, source).to_string());
+}
+
+#[test]
+fn test_eval_import_cycle() {
+    let dir = write_modules(
+        "import_cycle",
+        &[("a.mila", "import b;"), ("b.mila", "import a;")],
+    );
+    let evaluated = test_eval_file(&dir, "import a;");
+    let error = evaluated.as_error().unwrap();
+    assert!(error.message.starts_with("import cycle detected"));
+    assert!(error.message.ends_with("a.mila"));
+}
+
+#[test]
+fn test_eval_import_cycle_through_entry() {
+    let dir = write_modules(
+        "entry_cycle",
+        &[("a.mila", "import b;"), ("b.mila", "import a;")],
+    );
+    let path = dir.join("a.mila").canonicalize().unwrap();
+    let evaluated = test_eval_with("import b;", &path.display().to_string(), DEFAULT_MAX_DEPTH);
+    let error = evaluated.as_error().unwrap();
+    let cycle = error.message.split(": ").last().unwrap();
+    let a = path.display().to_string();
+    let b = dir
+        .join("b.mila")
+        .canonicalize()
+        .unwrap()
+        .display()
+        .to_string();
+    assert_eq!(format!("{} -> {} -> {}", a, b, a), cycle);
+}
+
+fn write_modules(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("mila_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, source) in files {
+        std::fs::write(dir.join(file), source).unwrap();
+    }
+    dir
+}
+
+fn test_eval_file(dir: &std::path::Path, source: &str) -> Value {
+    let path = dir.join("main.mila");
+    std::fs::write(&path, source).unwrap();
+    test_eval_with(source, &path.display().to_string(), DEFAULT_MAX_DEPTH)
+}
+
+fn test_eval(source: String) -> Value {
+    test_eval_with(&source, "foo.bzr", DEFAULT_MAX_DEPTH)
+}
+
+// runs the source on both the tree walker and the virtual machine, which must agree
+fn test_eval_with(source: &str, file: &str, max_depth: usize) -> Value {
+    let parse =
+        || Parser::new(Lexer::new(source.to_string(), Rc::new(file.to_string()))).parse_program();
+    let evaluated = Evaluator::with_max_depth(max_depth)
+        .eval(&parse(), Rc::new(RefCell::new(Default::default())));
+    let executed =
+        Vm::with_max_depth(max_depth).run(&parse(), Rc::new(RefCell::new(Default::default())));
+    assert_eq!(
+        evaluated.get_type().to_string(),
+        executed.get_type().to_string(),
+        "{}",
+        source
+    );
+    match evaluated.as_error() {
+        Some(error) => {
+            let executed = executed.as_error().unwrap();
+            assert_eq!(error.report(), executed.report(), "{}", source);
+            assert_eq!(error.kind, executed.kind, "{}", source);
+        }
+        None => assert_eq!(evaluated.to_string(), executed.to_string(), "{}", source),
+    }
+    evaluated
 }
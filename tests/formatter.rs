@@ -0,0 +1,79 @@
+use std::{fs, rc::Rc};
+
+use mila::formatter::format_source;
+
+#[test]
+fn test_format_source() {
+    let tests = vec![
+        ("let a=1+2", "let a = 1 + 2;\n"),
+        (
+            "let obj = |a: 1, b: [1,2],|",
+            "let obj = |\n    a: 1,\n    b: [1, 2],\n|;\n",
+        ),
+        (
+            "fn main() { putsln(1) putsln(2) }();",
+            "fn main() {\n    putsln(1);\n    putsln(2);\n}();\n",
+        ),
+        ("", ""),
+    ];
+    for (source, expected) in tests {
+        let formatted = format_source(source.to_string(), Rc::new("foo.mila".to_string()));
+        assert_eq!(Ok(expected.to_string()), formatted);
+    }
+}
+
+#[test]
+fn test_format_keeps_comments() {
+    let source = "// header
+let a = 1; // trailing
+
+
+// before b
+let b = [1, // inside
+    2];
+fn main() { // opening
+    ret a; // ret
+    // closing
+}
+// the end";
+    let expected = "// header
+let a = 1; // trailing
+
+// before b
+// inside
+let b = [1, 2];
+fn main() { // opening
+    ret a; // ret
+    // closing
+}
+// the end
+";
+    let formatted = format_source(source.to_string(), Rc::new("foo.mila".to_string()));
+    assert_eq!(Ok(expected.to_string()), formatted);
+}
+
+#[test]
+fn test_format_with_errors() {
+    let formatted = format_source(
+        "fn main() { let = 1; }".to_string(),
+        Rc::new("foo.mila".to_string()),
+    );
+    assert!(formatted.is_err(), "errors inside blocks must be reported");
+}
+
+#[test]
+fn test_format_is_idempotent_for_examples() {
+    for entry in fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "mila") {
+            continue;
+        }
+        let file = Rc::new(path.display().to_string());
+        let source = fs::read_to_string(&path).unwrap();
+        let comments = source.matches("//").count();
+        let formatted = format_source(source, Rc::clone(&file)).unwrap();
+        assert_eq!(comments, formatted.matches("//").count(), "{}", file);
+        let reformatted = format_source(formatted.clone(), file).unwrap();
+        assert_eq!(formatted, reformatted);
+    }
+}
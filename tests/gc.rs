@@ -0,0 +1,87 @@
+use std::rc::Rc;
+
+use mila::{
+    ast::Program,
+    evaluator::{
+        environment::{Environment, EnvironmentRef},
+        gc::{self, HeapStats},
+        Evaluator,
+    },
+    lexer::Lexer,
+    parser::Parser,
+    vm::Vm,
+};
+
+#[test]
+fn test_collect_recursive_functions() {
+    let source = "fn count(n) { if n == 0 { ret 0; } ret 1 + count(n - 1); } count(10);";
+    assert_collected(source, |program, environment| {
+        Evaluator::new().eval(program, environment);
+    });
+    assert_collected(source, |program, environment| {
+        Vm::new().run(program, environment);
+    });
+}
+
+#[test]
+fn test_collect_self_referencing_containers() {
+    let source = "let a = [1]; a.push(a); let b = [a]; fn f() { ret b; } let h = |f: f,|;";
+    assert_collected(source, |program, environment| {
+        Evaluator::new().eval(program, environment);
+    });
+    assert_collected(source, |program, environment| {
+        Vm::new().run(program, environment);
+    });
+}
+
+#[test]
+fn test_collect_keeps_reachable_values() {
+    gc::set_threshold(0);
+    let environment = Environment::shared(Environment::default());
+    let program = make_program(
+        "fn make() { let items = [1, 2]; items.push(items); ret fn() { ret items[1]; }; }
+        let get = make();",
+    );
+    Evaluator::new().eval(&program, Rc::clone(&environment));
+    gc::collect();
+    let program = make_program("get()");
+    let result = Evaluator::new().eval(&program, Rc::clone(&environment));
+    assert_eq!("2", result.to_string());
+    gc::set_threshold(gc::DEFAULT_THRESHOLD);
+}
+
+#[test]
+fn test_heap_stats() {
+    let before = gc::stats();
+    let environment = Environment::shared(Environment::default());
+    let program = make_program("let a = [[1], [2]];");
+    Evaluator::new().eval(&program, Rc::clone(&environment));
+    let after = gc::stats();
+    assert!(after.allocated >= before.allocated + 3);
+    assert!(after.live > before.live);
+    drop(environment);
+    gc::collect();
+    let collected = gc::stats();
+    assert_eq!(before.collections + 1, collected.collections);
+    assert_eq!(before.live, collected.live);
+}
+
+// runs the source, drops everything the host holds and checks that a
+// collection brings the heap back to where it started
+fn assert_collected(source: &str, run: impl Fn(&Program, EnvironmentRef)) {
+    gc::collect();
+    let before: HeapStats = gc::stats();
+    let environment = Environment::shared(Environment::default());
+    run(&make_program(source), Rc::clone(&environment));
+    drop(environment);
+    assert!(gc::stats().live > before.live, "{}", source);
+    assert!(gc::collect() > 0, "{}", source);
+    assert_eq!(before.live, gc::stats().live, "{}", source);
+}
+
+fn make_program(source: &str) -> Program {
+    let lexer = Lexer::new(source.to_string(), Rc::new("foo.mila".to_string()));
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!(0, program.errors.len(), "wrong number of errors");
+    program
+}
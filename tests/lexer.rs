@@ -1,6 +1,6 @@
-use std::rc::Rc;
+use std::{io::Cursor, rc::Rc};
 
-use mila::lexer::{Lexer, Location, Token, TokenType};
+use mila::lexer::{FileId, Lexer, Location, Span, TokenType};
 
 #[test]
 fn test_lexer_should_lexer_single_tokens() {
@@ -8,128 +8,33 @@ fn test_lexer_should_lexer_single_tokens() {
 ;{}()[]!%"
         .to_string();
     let filename = Rc::new("tokens.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::Colon,
-            Location::new(1, 1, Rc::clone(&filename)),
-            ":".to_string(),
-        ),
-        Token::new(
-            TokenType::Comma,
-            Location::new(1, 2, Rc::clone(&filename)),
-            ",".to_string(),
-        ),
-        Token::new(
-            TokenType::Dot,
-            Location::new(1, 3, Rc::clone(&filename)),
-            ".".to_string(),
-        ),
-        Token::new(
-            TokenType::Plus,
-            Location::new(1, 4, Rc::clone(&filename)),
-            "+".to_string(),
-        ),
-        Token::new(
-            TokenType::Minus,
-            Location::new(1, 5, Rc::clone(&filename)),
-            "-".to_string(),
-        ),
-        Token::new(
-            TokenType::Slash,
-            Location::new(1, 6, Rc::clone(&filename)),
-            "/".to_string(),
-        ),
-        Token::new(
-            TokenType::Asterisk,
-            Location::new(1, 7, Rc::clone(&filename)),
-            "*".to_string(),
-        ),
-        Token::new(
-            TokenType::Greater,
-            Location::new(1, 9, Rc::clone(&filename)),
-            ">".to_string(),
-        ),
-        Token::new(
-            TokenType::Less,
-            Location::new(1, 10, Rc::clone(&filename)),
-            "<".to_string(),
-        ),
-        Token::new(
-            TokenType::Assign,
-            Location::new(1, 12, Rc::clone(&filename)),
-            "=".to_string(),
-        ),
-        Token::new(
-            TokenType::Question,
-            Location::new(1, 13, Rc::clone(&filename)),
-            "?".to_string(),
-        ),
-        Token::new(
-            TokenType::Caret,
-            Location::new(1, 14, Rc::clone(&filename)),
-            "^".to_string(),
-        ),
-        Token::new(
-            TokenType::BitWiseAnd,
-            Location::new(1, 15, Rc::clone(&filename)),
-            "&".to_string(),
-        ),
-        Token::new(
-            TokenType::Pipe,
-            Location::new(1, 16, Rc::clone(&filename)),
-            "|".to_string(),
-        ),
-        Token::new(
-            TokenType::Semicolon,
-            Location::new(2, 1, Rc::clone(&filename)),
-            ";".to_string(),
-        ),
-        Token::new(
-            TokenType::LBrace,
-            Location::new(2, 2, Rc::clone(&filename)),
-            "{".to_string(),
-        ),
-        Token::new(
-            TokenType::RBrace,
-            Location::new(2, 3, Rc::clone(&filename)),
-            "}".to_string(),
-        ),
-        Token::new(
-            TokenType::LParen,
-            Location::new(2, 4, Rc::clone(&filename)),
-            "(".to_string(),
-        ),
-        Token::new(
-            TokenType::RParen,
-            Location::new(2, 5, Rc::clone(&filename)),
-            ")".to_string(),
-        ),
-        Token::new(
-            TokenType::LBracket,
-            Location::new(2, 6, Rc::clone(&filename)),
-            "[".to_string(),
-        ),
-        Token::new(
-            TokenType::RBracket,
-            Location::new(2, 7, Rc::clone(&filename)),
-            "]".to_string(),
-        ),
-        Token::new(
-            TokenType::Bang,
-            Location::new(2, 8, Rc::clone(&filename)),
-            "!".to_string(),
-        ),
-        Token::new(
-            TokenType::Mod,
-            Location::new(2, 9, Rc::clone(&filename)),
-            "%".to_string(),
-        ),
-        Token::new(
-            TokenType::Eof,
-            Location::new(2, 10, Rc::clone(&filename)),
-            "\0".to_string(),
-        ),
+        (TokenType::Colon, Location::new(1, 1, file), ":"),
+        (TokenType::Comma, Location::new(1, 2, file), ","),
+        (TokenType::Dot, Location::new(1, 3, file), "."),
+        (TokenType::Plus, Location::new(1, 4, file), "+"),
+        (TokenType::Minus, Location::new(1, 5, file), "-"),
+        (TokenType::Slash, Location::new(1, 6, file), "/"),
+        (TokenType::Asterisk, Location::new(1, 7, file), "*"),
+        (TokenType::Greater, Location::new(1, 9, file), ">"),
+        (TokenType::Less, Location::new(1, 10, file), "<"),
+        (TokenType::Assign, Location::new(1, 12, file), "="),
+        (TokenType::Question, Location::new(1, 13, file), "?"),
+        (TokenType::Caret, Location::new(1, 14, file), "^"),
+        (TokenType::BitWiseAnd, Location::new(1, 15, file), "&"),
+        (TokenType::Pipe, Location::new(1, 16, file), "|"),
+        (TokenType::Semicolon, Location::new(2, 1, file), ";"),
+        (TokenType::LBrace, Location::new(2, 2, file), "{"),
+        (TokenType::RBrace, Location::new(2, 3, file), "}"),
+        (TokenType::LParen, Location::new(2, 4, file), "("),
+        (TokenType::RParen, Location::new(2, 5, file), ")"),
+        (TokenType::LBracket, Location::new(2, 6, file), "["),
+        (TokenType::RBracket, Location::new(2, 7, file), "]"),
+        (TokenType::Bang, Location::new(2, 8, file), "!"),
+        (TokenType::Mod, Location::new(2, 9, file), "%"),
+        (TokenType::Eof, Location::new(2, 10, file), ""),
     ];
     test_tokens(lexer, &tokens)
 }
@@ -143,48 +48,33 @@ mila.lang
 #aaaa"
         .to_string();
     let filename = Rc::new("identififer.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
+        (
             TokenType::Identifier,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "mila_lang".to_string(),
+            Location::new(1, 1, file),
+            "mila_lang",
         ),
-        Token::new(
+        (
             TokenType::Identifier,
-            Location::new(2, 1, Rc::clone(&filename)),
-            "mila_lang2".to_string(),
+            Location::new(2, 1, file),
+            "mila_lang2",
         ),
-        Token::new(
+        (
             TokenType::Identifier,
-            Location::new(3, 1, Rc::clone(&filename)),
-            "_mila_lang".to_string(),
+            Location::new(3, 1, file),
+            "_mila_lang",
         ),
-        Token::new(
+        (
             TokenType::Identifier,
-            Location::new(3, 12, Rc::clone(&filename)),
-            "mila_lang".to_string(),
-        ),
-        Token::new(
-            TokenType::Identifier,
-            Location::new(4, 1, Rc::clone(&filename)),
-            "mila".to_string(),
-        ),
-        Token::new(
-            TokenType::Dot,
-            Location::new(4, 5, Rc::clone(&filename)),
-            ".".to_string(),
-        ),
-        Token::new(
-            TokenType::Identifier,
-            Location::new(4, 6, Rc::clone(&filename)),
-            "lang".to_string(),
-        ),
-        Token::new(
-            TokenType::Illegal,
-            Location::new(5, 1, Rc::clone(&filename)),
-            "#aaaa".to_string(),
+            Location::new(3, 12, file),
+            "mila_lang",
         ),
+        (TokenType::Identifier, Location::new(4, 1, file), "mila"),
+        (TokenType::Dot, Location::new(4, 5, file), "."),
+        (TokenType::Identifier, Location::new(4, 6, file), "lang"),
+        (TokenType::Illegal, Location::new(5, 1, file), "#aaaa"),
     ];
     test_tokens(lexer, &tokens)
 }
@@ -200,98 +90,27 @@ fn test_lexer_number_token() {
 1477a;"
         .to_string();
     let filename = Rc::new("number.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::Number,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "12345".to_string(),
-        ),
-        Token::new(
-            TokenType::Semicolon,
-            Location::new(1, 6, Rc::clone(&filename)),
-            ";".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(1, 7, Rc::clone(&filename)),
-            "123456".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(2, 1, Rc::clone(&filename)),
-            "123456789".to_string(),
-        ),
-        Token::new(
-            TokenType::Greater,
-            Location::new(2, 10, Rc::clone(&filename)),
-            ">".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(3, 1, Rc::clone(&filename)),
-            "123456".to_string(),
-        ),
-        Token::new(
-            TokenType::Less,
-            Location::new(3, 7, Rc::clone(&filename)),
-            "<".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(3, 8, Rc::clone(&filename)),
-            "458444".to_string(),
-        ),
-        Token::new(
-            TokenType::Pipe,
-            Location::new(3, 14, Rc::clone(&filename)),
-            "|".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(4, 1, Rc::clone(&filename)),
-            "121221".to_string(),
-        ),
-        Token::new(
-            TokenType::BitWiseAnd,
-            Location::new(4, 7, Rc::clone(&filename)),
-            "&".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(5, 1, Rc::clone(&filename)),
-            "125478".to_string(),
-        ),
-        Token::new(
-            TokenType::Caret,
-            Location::new(5, 7, Rc::clone(&filename)),
-            "^".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(6, 1, Rc::clone(&filename)),
-            "1".to_string(),
-        ),
-        Token::new(
-            TokenType::Comma,
-            Location::new(6, 2, Rc::clone(&filename)),
-            ",".to_string(),
-        ),
-        Token::new(
-            TokenType::Number,
-            Location::new(6, 3, Rc::clone(&filename)),
-            "2".to_string(),
-        ),
-        Token::new(
-            TokenType::Illegal,
-            Location::new(7, 1, Rc::clone(&filename)),
-            "1477a".to_string(),
-        ),
-        Token::new(
-            TokenType::Semicolon,
-            Location::new(7, 6, Rc::clone(&filename)),
-            ";".to_string(),
-        ),
+        (TokenType::Number, Location::new(1, 1, file), "12345"),
+        (TokenType::Semicolon, Location::new(1, 6, file), ";"),
+        (TokenType::Number, Location::new(1, 7, file), "123456"),
+        (TokenType::Number, Location::new(2, 1, file), "123456789"),
+        (TokenType::Greater, Location::new(2, 10, file), ">"),
+        (TokenType::Number, Location::new(3, 1, file), "123456"),
+        (TokenType::Less, Location::new(3, 7, file), "<"),
+        (TokenType::Number, Location::new(3, 8, file), "458444"),
+        (TokenType::Pipe, Location::new(3, 14, file), "|"),
+        (TokenType::Number, Location::new(4, 1, file), "121221"),
+        (TokenType::BitWiseAnd, Location::new(4, 7, file), "&"),
+        (TokenType::Number, Location::new(5, 1, file), "125478"),
+        (TokenType::Caret, Location::new(5, 7, file), "^"),
+        (TokenType::Number, Location::new(6, 1, file), "1"),
+        (TokenType::Comma, Location::new(6, 2, file), ","),
+        (TokenType::Number, Location::new(6, 3, file), "2"),
+        (TokenType::Illegal, Location::new(7, 1, file), "1477a"),
+        (TokenType::Semicolon, Location::new(7, 6, file), ";"),
     ];
     test_tokens(lexer, &tokens);
 }
@@ -305,152 +124,69 @@ fn test_lexer_floating_pointer_token() {
 100.0.0"
         .to_string();
     let filename = Rc::new("floating.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
+        (
             TokenType::FloatingPointNumber,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "100.0".to_string(),
+            Location::new(1, 1, file),
+            "100.0",
         ),
-        Token::new(
+        (
             TokenType::FloatingPointNumber,
-            Location::new(2, 1, Rc::clone(&filename)),
-            "100.0".to_string(),
-        ),
-        Token::new(
-            TokenType::Semicolon,
-            Location::new(2, 6, Rc::clone(&filename)),
-            ";".to_string(),
-        ),
-        Token::new(
-            TokenType::Illegal,
-            Location::new(3, 1, Rc::clone(&filename)),
-            "100.0a".to_string(),
+            Location::new(2, 1, file),
+            "100.0",
         ),
-        Token::new(
+        (TokenType::Semicolon, Location::new(2, 6, file), ";"),
+        (TokenType::Illegal, Location::new(3, 1, file), "100.0a"),
+        (
             TokenType::FloatingPointNumber,
-            Location::new(4, 1, Rc::clone(&filename)),
-            "100.1".to_string(),
-        ),
-        Token::new(
-            TokenType::Illegal,
-            Location::new(5, 1, Rc::clone(&filename)),
-            "100.0.0".to_string(),
+            Location::new(4, 1, file),
+            "100.1",
         ),
+        (TokenType::Illegal, Location::new(5, 1, file), "100.0.0"),
     ];
     test_tokens(lexer, &tokens);
 }
 
 #[test]
 fn test_word_token() {
-    let source = "let var while true false fn ret".to_string();
+    let source = "let var while true false fn ret nil".to_string();
     let filename = Rc::new("word_token.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::Let,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "let".to_string(),
-        ),
-        Token::new(
-            TokenType::Var,
-            Location::new(1, 5, Rc::clone(&filename)),
-            "var".to_string(),
-        ),
-        Token::new(
-            TokenType::While,
-            Location::new(1, 9, Rc::clone(&filename)),
-            "while".to_string(),
-        ),
-        Token::new(
-            TokenType::True,
-            Location::new(1, 15, Rc::clone(&filename)),
-            "true".to_string(),
-        ),
-        Token::new(
-            TokenType::False,
-            Location::new(1, 20, Rc::clone(&filename)),
-            "false".to_string(),
-        ),
-        Token::new(
-            TokenType::Fn,
-            Location::new(1, 26, Rc::clone(&filename)),
-            "fn".to_string(),
-        ),
-        Token::new(
-            TokenType::Ret,
-            Location::new(1, 29, Rc::clone(&filename)),
-            "ret".to_string(),
-        ),
+        (TokenType::Let, Location::new(1, 1, file), "let"),
+        (TokenType::Var, Location::new(1, 5, file), "var"),
+        (TokenType::While, Location::new(1, 9, file), "while"),
+        (TokenType::True, Location::new(1, 15, file), "true"),
+        (TokenType::False, Location::new(1, 20, file), "false"),
+        (TokenType::Fn, Location::new(1, 26, file), "fn"),
+        (TokenType::Ret, Location::new(1, 29, file), "ret"),
+        (TokenType::Nil, Location::new(1, 33, file), "nil"),
     ];
     test_tokens(lexer, &tokens);
 }
 #[test]
 fn test_two_char_token() {
-    let source = "== != <= >= >> << += -= *= /= && ||".to_string();
+    let source = "== != <= >= >> << += -= *= /= && || **".to_string();
     let filename = Rc::new("two_char.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::Eq,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "==".to_string(),
-        ),
-        Token::new(
-            TokenType::NotEq,
-            Location::new(1, 4, Rc::clone(&filename)),
-            "!=".to_string(),
-        ),
-        Token::new(
-            TokenType::LessThanOrEq,
-            Location::new(1, 7, Rc::clone(&filename)),
-            "<=".to_string(),
-        ),
-        Token::new(
-            TokenType::GreaterThanOrEq,
-            Location::new(1, 10, Rc::clone(&filename)),
-            ">=".to_string(),
-        ),
-        Token::new(
-            TokenType::ShiftRight,
-            Location::new(1, 13, Rc::clone(&filename)),
-            ">>".to_string(),
-        ),
-        Token::new(
-            TokenType::ShiftLeft,
-            Location::new(1, 16, Rc::clone(&filename)),
-            "<<".to_string(),
-        ),
-        Token::new(
-            TokenType::PlusAssign,
-            Location::new(1, 19, Rc::clone(&filename)),
-            "+=".to_string(),
-        ),
-        Token::new(
-            TokenType::MinusAssign,
-            Location::new(1, 22, Rc::clone(&filename)),
-            "-=".to_string(),
-        ),
-        Token::new(
-            TokenType::AsteriskAssign,
-            Location::new(1, 25, Rc::clone(&filename)),
-            "*=".to_string(),
-        ),
-        Token::new(
-            TokenType::SlashAssign,
-            Location::new(1, 28, Rc::clone(&filename)),
-            "/=".to_string(),
-        ),
-        Token::new(
-            TokenType::And,
-            Location::new(1, 31, Rc::clone(&filename)),
-            "&&".to_string(),
-        ),
-        Token::new(
-            TokenType::Or,
-            Location::new(1, 34, Rc::clone(&filename)),
-            "||".to_string(),
-        ),
+        (TokenType::Eq, Location::new(1, 1, file), "=="),
+        (TokenType::NotEq, Location::new(1, 4, file), "!="),
+        (TokenType::LessThanOrEq, Location::new(1, 7, file), "<="),
+        (TokenType::GreaterThanOrEq, Location::new(1, 10, file), ">="),
+        (TokenType::ShiftRight, Location::new(1, 13, file), ">>"),
+        (TokenType::ShiftLeft, Location::new(1, 16, file), "<<"),
+        (TokenType::PlusAssign, Location::new(1, 19, file), "+="),
+        (TokenType::MinusAssign, Location::new(1, 22, file), "-="),
+        (TokenType::AsteriskAssign, Location::new(1, 25, file), "*="),
+        (TokenType::SlashAssign, Location::new(1, 28, file), "/="),
+        (TokenType::And, Location::new(1, 31, file), "&&"),
+        (TokenType::Or, Location::new(1, 34, file), "||"),
+        (TokenType::Power, Location::new(1, 37, file), "**"),
     ];
     test_tokens(lexer, &tokens);
 }
@@ -463,28 +199,13 @@ fn test_string_token() {
 \"aaaaaa"
         .to_string();
     let filename = Rc::new("string.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::String,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "mila_lang".to_string(),
-        ),
-        Token::new(
-            TokenType::String,
-            Location::new(2, 1, Rc::clone(&filename)),
-            "banana\n".to_string(),
-        ),
-        Token::new(
-            TokenType::Semicolon,
-            Location::new(3, 2, Rc::clone(&filename)),
-            ";".to_string(),
-        ),
-        Token::new(
-            TokenType::Illegal,
-            Location::new(4, 1, Rc::clone(&filename)),
-            "\"aaaaaa".to_string(),
-        ),
+        (TokenType::String, Location::new(1, 1, file), "mila_lang"),
+        (TokenType::String, Location::new(2, 1, file), "banana\n"),
+        (TokenType::Semicolon, Location::new(3, 2, file), ";"),
+        (TokenType::Illegal, Location::new(4, 1, file), "\"aaaaaa"),
     ];
     test_tokens(lexer, &tokens);
 }
@@ -496,23 +217,31 @@ var//
 opa"
     .to_string();
     let filename = Rc::new("comments.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::Let,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "let".to_string(),
-        ),
-        Token::new(
-            TokenType::Var,
-            Location::new(2, 1, Rc::clone(&filename)),
-            "var".to_string(),
-        ),
-        Token::new(
-            TokenType::Identifier,
-            Location::new(3, 1, Rc::clone(&filename)),
-            "opa".to_string(),
-        ),
+        (TokenType::Let, Location::new(1, 1, file), "let"),
+        (TokenType::Var, Location::new(2, 1, file), "var"),
+        (TokenType::Identifier, Location::new(3, 1, file), "opa"),
+    ];
+    test_tokens(lexer, &tokens);
+}
+
+#[test]
+fn test_comments_token_with_trivia() {
+    let source = "let// coments
+var//
+opa"
+    .to_string();
+    let filename = Rc::new("comments.mil".to_string());
+    let file = FileId::new(&filename);
+    let lexer = Lexer::with_trivia(source, Rc::clone(&filename));
+    let tokens = vec![
+        (TokenType::Let, Location::new(1, 1, file), "let"),
+        (TokenType::Comment, Location::new(1, 4, file), "// coments"),
+        (TokenType::Var, Location::new(2, 1, file), "var"),
+        (TokenType::Comment, Location::new(2, 4, file), "//"),
+        (TokenType::Identifier, Location::new(3, 1, file), "opa"),
     ];
     test_tokens(lexer, &tokens);
 }
@@ -521,24 +250,86 @@ opa"
 fn test_if_else_token() {
     let source = "if else".to_string();
     let filename = Rc::new("comments.mil".to_string());
+    let file = FileId::new(&filename);
     let lexer = Lexer::new(source, Rc::clone(&filename));
     let tokens = vec![
-        Token::new(
-            TokenType::If,
-            Location::new(1, 1, Rc::clone(&filename)),
-            "if".to_string(),
-        ),
-        Token::new(
-            TokenType::Else,
-            Location::new(1, 4, Rc::clone(&filename)),
-            "else".to_string(),
-        ),
+        (TokenType::If, Location::new(1, 1, file), "if"),
+        (TokenType::Else, Location::new(1, 4, file), "else"),
+    ];
+    test_tokens(lexer, &tokens);
+}
+
+#[test]
+fn test_streaming_input() {
+    let source = "let a = 1.5; // one\nfn f(...xs) {\n    ret xs[-1] >= 2 && \"é\";\n}\nf(a)";
+    let filename = Rc::new("stream.mil".to_string());
+    let collect = |mut lexer: Lexer| {
+        let mut tokens = vec![];
+        loop {
+            let token = lexer.next_token();
+            if token.token_type == TokenType::Eof {
+                return tokens;
+            }
+            tokens.push(token);
+        }
+    };
+    let expected = collect(Lexer::new(source.to_string(), Rc::clone(&filename)));
+    assert_eq!(28, expected.len());
+    let reader = Cursor::new(source.as_bytes().to_vec());
+    assert_eq!(
+        expected,
+        collect(Lexer::from_reader(reader, Rc::clone(&filename)))
+    );
+    let chars: Vec<char> = source.chars().collect();
+    assert_eq!(
+        expected,
+        collect(Lexer::from_chars(chars.into_iter(), Rc::clone(&filename)))
+    );
+}
+
+#[test]
+fn test_token_spans() {
+    let source = "x = \"ab\" 1.5 1. 5 // c  ";
+    let filename = Rc::new("spans.mil".to_string());
+    let mut lexer = Lexer::with_trivia(source.to_string(), Rc::clone(&filename));
+    let expected = vec![
+        (TokenType::Identifier, Span::new(0, 1)),
+        (TokenType::Assign, Span::new(2, 3)),
+        (TokenType::String, Span::new(5, 7)),
+        (TokenType::FloatingPointNumber, Span::new(9, 12)),
+        (TokenType::Illegal, Span::new(13, 17)),
+        (TokenType::Comment, Span::new(18, 22)),
+        (TokenType::Eof, Span::new(24, 24)),
     ];
+    for (token_type, span) in expected {
+        let token = lexer.next_token();
+        assert_eq!((token_type, span), (token.token_type, token.span));
+    }
+    assert_eq!(FileId::new("spans.mil"), FileId::new(&filename));
+    assert_eq!("spans.mil", &*FileId::new(&filename).name());
+}
+
+#[test]
+fn test_non_ascii_input() {
+    let filename = Rc::new("utf8.mil".to_string());
+    let file = FileId::new(&filename);
+    let lexer = Lexer::new("\"olá\" ção".to_string(), Rc::clone(&filename));
+    let tokens = vec![
+        (TokenType::String, Location::new(1, 1, file), "olá"),
+        (TokenType::Illegal, Location::new(1, 7, file), "ção"),
+    ];
+    test_tokens(lexer, &tokens);
+    let reader = Cursor::new(b"\"a\xffb\"".to_vec());
+    let lexer = Lexer::from_reader(reader, Rc::clone(&filename));
+    let tokens = vec![(TokenType::String, Location::new(1, 1, file), "a\u{fffd}b")];
     test_tokens(lexer, &tokens);
 }
 
-fn test_tokens(mut lexer: Lexer, tokens: &[Token]) {
-    for token in tokens {
-        assert_eq!(*token, lexer.next_token())
+fn test_tokens(mut lexer: Lexer, tokens: &[(TokenType, Location, &str)]) {
+    for (token_type, location, value) in tokens {
+        let token = lexer.next_token();
+        assert_eq!(*token_type, token.token_type);
+        assert_eq!(*location, token.location);
+        assert_eq!(*value, lexer.text(token.span));
     }
 }
@@ -2,9 +2,9 @@ use std::rc::Rc;
 
 use mila::{
     ast::node::{
-        ArrayExpr, BlockStatement, BoolExpr, CallExpr, ExpressionStmt, FloatExpr, FnExpr, HashExpr,
+        ArrayExpr, BoolExpr, CallExpr, Expr, ExpressionStmt, FloatExpr, FnExpr, HashExpr,
         IdentifierExpr, IfExpr, IndexExpr, InfixExpr, IntExpr, LetStatement, PrefixExpr,
-        RetStatement, StringExpr, VarStatement, WhileExpr,
+        RetStatement, Stmt, StringExpr, VarStatement, WhileExpr,
     },
     lexer::Lexer,
     parser::Parser,
@@ -21,20 +21,12 @@ fn test_parse_bang_expr() {
     let prefix_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<PrefixExpr>()
-        .unwrap();
+        .cast::<PrefixExpr>();
     assert_eq!("!".to_string(), prefix_expr.operator);
-    let prefix_bool_value = prefix_expr
-        .right
-        .as_any()
-        .downcast_ref::<BoolExpr>()
-        .unwrap();
-    assert_eq!(true, prefix_bool_value.value);
+    let prefix_bool_value = prefix_expr.right.cast::<BoolExpr>();
+    assert!(prefix_bool_value.value);
 }
 
 #[test]
@@ -48,14 +40,10 @@ fn test_parse_bool_expr() {
     let bool_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<BoolExpr>()
-        .unwrap();
-    assert_eq!(true, bool_expr.value);
+        .cast::<BoolExpr>();
+    assert!(bool_expr.value);
 }
 
 #[test]
@@ -69,13 +57,9 @@ fn test_parse_int_expr() {
     let int_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IntExpr>()
-        .unwrap();
+        .cast::<IntExpr>();
     assert_eq!(10, int_expr.value);
 }
 
@@ -90,13 +74,9 @@ fn test_parse_float_expr() {
     let float_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<FloatExpr>()
-        .unwrap();
+        .cast::<FloatExpr>();
     assert_eq!(10.0, float_expr.value);
 }
 
@@ -111,13 +91,9 @@ fn test_parse_string_expr() {
     let float_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<StringExpr>()
-        .unwrap();
+        .cast::<StringExpr>();
     assert_eq!("10.0", float_expr.value);
 }
 
@@ -132,13 +108,9 @@ fn test_parse_identifier_expr() {
     let identifier = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+        .cast::<IdentifierExpr>();
     assert_eq!("mila", identifier.value);
 }
 
@@ -153,15 +125,11 @@ fn test_parse_plus_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("+", infix.operator, "wrong operator value");
@@ -178,15 +146,11 @@ fn test_parse_minus_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("-", infix.operator, "wrong operator value");
@@ -203,19 +167,11 @@ fn test_parse_plus_assign_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix
-        .left
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IdentifierExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!("a", left.value, "wrong left value");
     assert_eq!("+=", infix.operator, "wrong operator value");
@@ -232,15 +188,11 @@ fn test_parse_minus_assign_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("-=", infix.operator, "wrong operator value");
@@ -257,15 +209,11 @@ fn test_parse_asterisk_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("*", infix.operator, "wrong operator value");
@@ -282,15 +230,11 @@ fn test_parse_asterisk_assign_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("*=", infix.operator, "wrong operator value");
@@ -307,15 +251,11 @@ fn test_parse_slash_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("/", infix.operator, "wrong operator value");
@@ -332,15 +272,11 @@ fn test_parse_slash_assign_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("/=", infix.operator, "wrong operator value");
@@ -357,17 +293,13 @@ fn test_parse_and_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<BoolExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<BoolExpr>().unwrap();
-    assert_eq!(true, right.value, "wrong right value");
-    assert_eq!(true, left.value, "wrong left value");
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<BoolExpr>();
+    let right = infix.right.cast::<BoolExpr>();
+    assert!(right.value, "wrong right value");
+    assert!(left.value, "wrong left value");
     assert_eq!("&&", infix.operator, "wrong operator value");
 }
 
@@ -382,17 +314,13 @@ fn test_parse_or_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<BoolExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<BoolExpr>().unwrap();
-    assert_eq!(true, right.value, "wrong right value");
-    assert_eq!(true, left.value, "wrong left value");
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<BoolExpr>();
+    let right = infix.right.cast::<BoolExpr>();
+    assert!(right.value, "wrong right value");
+    assert!(left.value, "wrong left value");
     assert_eq!("||", infix.operator, "wrong operator value");
 }
 
@@ -407,15 +335,11 @@ fn test_parse_pipe_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("|", infix.operator, "wrong operator value");
@@ -432,15 +356,11 @@ fn test_parse_bitwiseand_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("&", infix.operator, "wrong operator value");
@@ -457,15 +377,11 @@ fn test_parse_shiftleft_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("<<", infix.operator, "wrong operator value");
@@ -482,15 +398,11 @@ fn test_parse_shiftrisght_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!(">>", infix.operator, "wrong operator value");
@@ -507,15 +419,11 @@ fn test_parse_xor_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("^", infix.operator, "wrong operator value");
@@ -532,15 +440,11 @@ fn test_parse_mod_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("%", infix.operator, "wrong operator value");
@@ -557,15 +461,11 @@ fn test_parse_eq_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("==", infix.operator, "wrong operator value");
@@ -582,15 +482,11 @@ fn test_parse_not_eq_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix.left.as_any().downcast_ref::<IntExpr>().unwrap();
-    let right = infix.right.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IntExpr>();
+    let right = infix.right.cast::<IntExpr>();
     assert_eq!(1, right.value, "wrong right value");
     assert_eq!(1, left.value, "wrong left value");
     assert_eq!("!=", infix.operator, "wrong operator value");
@@ -607,13 +503,9 @@ fn test_parse_group_expr() {
     let int = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IntExpr>()
-        .unwrap();
+        .cast::<IntExpr>();
     assert_eq!(1, int.value, "invalid int value");
 }
 
@@ -625,18 +517,9 @@ fn test_parse_let_stmt() {
     let errors = program.errors;
     assert_eq!(0, errors.len(), "wrong number of errors");
     assert_eq!(1, statemets.len(), "wrong number of statemets");
-    let let_stmt = statemets
-        .first()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<LetStatement>()
-        .unwrap();
-    let int = let_stmt.value.as_any().downcast_ref::<IntExpr>().unwrap();
-    let identifier = let_stmt
-        .name
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let let_stmt = statemets.first().unwrap().cast::<LetStatement>();
+    let int = let_stmt.value.cast::<IntExpr>();
+    let identifier = &let_stmt.name;
     assert_eq!(10, int.value, "invalid int value");
     assert_eq!("a", identifier.value, "invalid name value");
 }
@@ -649,18 +532,9 @@ fn test_parse_var_stmt() {
     let errors = program.errors;
     assert_eq!(0, errors.len(), "wrong number of errors");
     assert_eq!(1, statemets.len(), "wrong number of statemets");
-    let let_stmt = statemets
-        .first()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<VarStatement>()
-        .unwrap();
-    let int = let_stmt.value.as_any().downcast_ref::<IntExpr>().unwrap();
-    let identifier = let_stmt
-        .name
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let let_stmt = statemets.first().unwrap().cast::<VarStatement>();
+    let int = let_stmt.value.cast::<IntExpr>();
+    let identifier = &let_stmt.name;
     assert_eq!(10, int.value, "invalid int value");
     assert_eq!("a", identifier.value, "invalid name value");
 }
@@ -673,19 +547,8 @@ fn test_parse_ret_with_value_stmt() {
     let errors = program.errors;
     assert_eq!(0, errors.len(), "wrong number of errors");
     assert_eq!(1, statemets.len(), "wrong number of statemets");
-    let ret_stmt = statemets
-        .first()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<RetStatement>()
-        .unwrap();
-    let int = ret_stmt
-        .value
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<IntExpr>()
-        .unwrap();
+    let ret_stmt = statemets.first().unwrap().cast::<RetStatement>();
+    let int = ret_stmt.value.as_ref().unwrap().cast::<IntExpr>();
     assert_eq!(10, int.value, "invalid int value");
 }
 
@@ -697,12 +560,7 @@ fn test_parse_ret_without_value_stmt() {
     let errors = program.errors;
     assert_eq!(0, errors.len(), "wrong number of errors");
     assert_eq!(1, statemets.len(), "wrong number of statemets");
-    let ret_stmt = statemets
-        .first()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<RetStatement>()
-        .unwrap();
+    let ret_stmt = statemets.first().unwrap().cast::<RetStatement>();
     assert!(ret_stmt.value.is_none());
 }
 
@@ -717,25 +575,11 @@ fn test_parse_if_expr() {
     let if_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IfExpr>()
-        .unwrap();
-    let consequence = if_expr
-        .consequence
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
-    let alternative = if_expr
-        .alternative
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<IfExpr>();
+    let consequence = &if_expr.consequence;
+    let alternative = if_expr.alternative.as_ref().unwrap();
     assert_eq!(
         1,
         consequence.statements.len(),
@@ -759,30 +603,12 @@ fn test_parse_if_else_if_expr() {
     let if_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IfExpr>()
-        .unwrap();
-    let consequence = if_expr
-        .consequence
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
-    let el_if = if_expr
-        .el_if
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<IfExpr>()
-        .unwrap();
-    let el_if_consequence = el_if
-        .consequence
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<IfExpr>();
+    let consequence = &if_expr.consequence;
+    let el_if = if_expr.el_if.as_ref().unwrap();
+    let el_if_consequence = &el_if.consequence;
     assert_eq!(
         1,
         consequence.statements.len(),
@@ -806,23 +632,11 @@ fn test_dot_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix
-        .left
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
-    let right = infix
-        .right
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IdentifierExpr>();
+    let right = infix.right.cast::<IdentifierExpr>();
     assert_eq!("att", right.value, "wrong right value");
     assert_eq!("obj", left.value, "wrong left value");
     assert_eq!(".", infix.operator, "wrong operator value");
@@ -839,18 +653,10 @@ fn test_parse_while_expr() {
     let while_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<WhileExpr>()
-        .unwrap();
-    let consequence = while_expr
-        .consequence
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<WhileExpr>();
+    let consequence = &while_expr.consequence;
     assert_eq!(
         1,
         consequence.statements.len(),
@@ -861,7 +667,7 @@ fn test_parse_while_expr() {
 #[test]
 fn test_parse_fn_with_two_parameters_expr() {
     let mut parser =
-        make_parser("fn mila(param, param) { 10 } fn b (a, b, c) {} mila(1, 2)".to_string());
+        make_parser("fn mila(first, second) { 10 } fn b (a, b, c) {} mila(1, 2)".to_string());
     let program = parser.parse_program();
     let statemets = program.statements;
     let errors = program.errors;
@@ -870,26 +676,12 @@ fn test_parse_fn_with_two_parameters_expr() {
     let fn_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<FnExpr>()
-        .unwrap();
-    let body = fn_expr
-        .body
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<FnExpr>();
+    let body = &fn_expr.body;
     let paratemers_len = fn_expr.parameters.len();
-    let name = fn_expr
-        .name
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let name = fn_expr.name.as_ref().unwrap();
     assert_eq!(
         1,
         body.statements.len(),
@@ -910,26 +702,12 @@ fn test_parse_fn_with_one_parameters_expr() {
     let fn_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<FnExpr>()
-        .unwrap();
-    let body = fn_expr
-        .body
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<FnExpr>();
+    let body = &fn_expr.body;
     let paratemers_len = fn_expr.parameters.len();
-    let name = fn_expr
-        .name
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let name = fn_expr.name.as_ref().unwrap();
     assert_eq!(
         1,
         body.statements.len(),
@@ -939,6 +717,22 @@ fn test_parse_fn_with_one_parameters_expr() {
     assert_eq!("mila", name.value, "wrong name of function");
 }
 
+#[test]
+fn test_parse_fn_with_invalid_parameters_should_be_error() {
+    let tests = vec![
+        "fn f(...rest, a) {}",
+        "fn f(a = 1, b) {}",
+        "fn f(...) {}",
+        "fn f(a, a) {}",
+        "fn f(a, b = 1, a = 2) {}",
+        "fn f(a, ...a) {}",
+    ];
+    for source in tests {
+        let program = make_parser(source.to_string()).parse_program();
+        assert!(!program.errors.is_empty(), "expected errors for {}", source);
+    }
+}
+
 #[test]
 fn test_parse_fn_with_zero_parameters_expr() {
     let mut parser = make_parser("fn mila() { 10 }".to_string());
@@ -950,26 +744,12 @@ fn test_parse_fn_with_zero_parameters_expr() {
     let fn_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<FnExpr>()
-        .unwrap();
-    let body = fn_expr
-        .body
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<FnExpr>();
+    let body = &fn_expr.body;
     let paratemers_len = fn_expr.parameters.len();
-    let name = fn_expr
-        .name
-        .as_ref()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let name = fn_expr.name.as_ref().unwrap();
     assert_eq!(
         1,
         body.statements.len(),
@@ -990,18 +770,10 @@ fn test_parse_fn_without_name_expr() {
     let fn_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<FnExpr>()
-        .unwrap();
-    let body = fn_expr
-        .body
-        .as_any()
-        .downcast_ref::<BlockStatement>()
-        .unwrap();
+        .cast::<FnExpr>();
+    let body = &fn_expr.body;
     let paratemers_len = fn_expr.parameters.len();
     let name = fn_expr.name.is_none();
     assert_eq!(
@@ -1021,18 +793,9 @@ fn test_parse_let_with_function_stmt() {
     let errors = program.errors;
     assert_eq!(0, errors.len(), "wrong number of errors");
     assert_eq!(1, statemets.len(), "wrong number of statemets");
-    let let_stmt = statemets
-        .first()
-        .unwrap()
-        .as_any()
-        .downcast_ref::<LetStatement>()
-        .unwrap();
-    let is_function = let_stmt.value.as_any().downcast_ref::<FnExpr>().is_some();
-    let identifier = let_stmt
-        .name
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let let_stmt = statemets.first().unwrap().cast::<LetStatement>();
+    let is_function = matches!(let_stmt.value, Expr::Function(_));
+    let identifier = &let_stmt.name;
     assert!(is_function, "is not a function");
     assert_eq!("a", identifier.value, "invalid name value");
 }
@@ -1098,19 +861,11 @@ fn test_call_expr_with_zero_args() {
     let call_expr = statemets
         .get(1)
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<CallExpr>()
-        .unwrap();
+        .cast::<CallExpr>();
     let args_len = call_expr.arguments.len();
-    let function = call_expr
-        .function
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let function = call_expr.function.cast::<IdentifierExpr>();
     assert_eq!(0, args_len, "wrong number of arguments in function call");
     assert_eq!("mila", function.value, "wrong name of function");
 }
@@ -1126,19 +881,11 @@ fn test_call_expr_with_one_args() {
     let call_expr = statemets
         .get(1)
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<CallExpr>()
-        .unwrap();
+        .cast::<CallExpr>();
     let args_len = call_expr.arguments.len();
-    let function = call_expr
-        .function
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let function = call_expr.function.cast::<IdentifierExpr>();
     assert_eq!(1, args_len, "wrong number of arguments in function call");
     assert_eq!("mila", function.value, "wrong name of function");
 }
@@ -1154,19 +901,11 @@ fn test_call_expr_with_two_args() {
     let call_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<CallExpr>()
-        .unwrap();
+        .cast::<CallExpr>();
     let args_len = call_expr.arguments.len();
-    let function = call_expr
-        .function
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
+    let function = call_expr.function.cast::<IdentifierExpr>();
     assert_eq!(2, args_len, "wrong number of arguments in function call");
     assert_eq!("mila", function.value, "wrong name of function");
 }
@@ -1182,19 +921,11 @@ fn test_dot_expr_with_call_expr() {
     let infix = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<InfixExpr>()
-        .unwrap();
-    let left = infix
-        .left
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
-    let right = infix.right.as_any().downcast_ref::<CallExpr>().is_some();
+        .cast::<InfixExpr>();
+    let left = infix.left.cast::<IdentifierExpr>();
+    let right = matches!(*infix.right, Expr::Call(_));
     assert!(right, "right value has to be a call expr");
     assert_eq!("obj", left.value, "wrong left value");
     assert_eq!(".", infix.operator, "wrong operator value");
@@ -1211,19 +942,11 @@ fn test_index_expr() {
     let index_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<IndexExpr>()
-        .unwrap();
-    let left = index_expr
-        .left
-        .as_any()
-        .downcast_ref::<IdentifierExpr>()
-        .unwrap();
-    let index = index_expr.index.as_any().downcast_ref::<IntExpr>().unwrap();
+        .cast::<IndexExpr>();
+    let left = index_expr.left.cast::<IdentifierExpr>();
+    let index = index_expr.index.cast::<IntExpr>();
     assert_eq!("a", left.value, "wrong left value");
     assert_eq!(1, index.value, "wrong index value");
 }
@@ -1239,13 +962,9 @@ fn test_parse_array_with_zero_item() {
     let array_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<ArrayExpr>()
-        .unwrap();
+        .cast::<ArrayExpr>();
     let values_len = array_expr.values.len();
     assert_eq!(0, values_len, "wrong number os values value");
 }
@@ -1261,13 +980,9 @@ fn test_parse_array_with_one_item() {
     let array_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<ArrayExpr>()
-        .unwrap();
+        .cast::<ArrayExpr>();
     let values_len = array_expr.values.len();
     assert_eq!(1, values_len, "wrong number os values value");
 }
@@ -1283,13 +998,9 @@ fn test_parse_array_with_two_item() {
     let array_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<ArrayExpr>()
-        .unwrap();
+        .cast::<ArrayExpr>();
     let values_len = array_expr.values.len();
     assert_eq!(2, values_len, "wrong number os values value");
 }
@@ -1306,18 +1017,199 @@ fn test_parse_hash_object() {
     let hash_expr = statemets
         .first()
         .unwrap()
-        .as_any()
-        .downcast_ref::<ExpressionStmt>()
-        .unwrap()
+        .cast::<ExpressionStmt>()
         .expression
-        .as_any()
-        .downcast_ref::<HashExpr>()
-        .unwrap();
+        .cast::<HashExpr>();
     let values_len = hash_expr.pairs.keys().len();
     assert_eq!(4, values_len, "wrong number os values value");
 }
 
+trait Variant<T> {
+    fn variant(&self) -> Option<&T>;
+}
+
+// unwraps the node a test expects, failing it on any other variant
+trait Cast {
+    fn cast<T>(&self) -> &T
+    where
+        Self: Variant<T>,
+    {
+        self.variant().expect("unexpected node")
+    }
+}
+
+impl Cast for Stmt {}
+impl Cast for Expr {}
+
+macro_rules! variants {
+    ($($node:ident::$variant:ident => $ty:ty),*) => {
+        $(
+            impl Variant<$ty> for $node {
+                fn variant(&self) -> Option<&$ty> {
+                    match self {
+                        $node::$variant(inner) => Some(inner),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+variants![
+    Stmt::Let => LetStatement,
+    Stmt::Var => VarStatement,
+    Stmt::Ret => RetStatement,
+    Stmt::Expression => ExpressionStmt,
+    Expr::If => IfExpr,
+    Expr::Int => IntExpr,
+    Expr::Call => CallExpr,
+    Expr::Bool => BoolExpr,
+    Expr::Hash => HashExpr,
+    Expr::Array => ArrayExpr,
+    Expr::Index => IndexExpr,
+    Expr::Infix => InfixExpr,
+    Expr::Float => FloatExpr,
+    Expr::While => WhileExpr,
+    Expr::Prefix => PrefixExpr,
+    Expr::String => StringExpr,
+    Expr::Function => FnExpr,
+    Expr::Identifier => IdentifierExpr
+];
+
 fn make_parser(source: String) -> Parser {
     let lexer = Lexer::new(source, Rc::new("foo.bzr".to_string()));
     Parser::new(lexer)
 }
+
+#[test]
+fn test_display_program() {
+    let tests = vec![
+        ("let a = 1 + 2 * 3", "let a = 1 + 2 * 3;"),
+        ("var a = (1 + 2) * 3;", "var a = (1 + 2) * 3;"),
+        ("10 - (5 - 2)", "10 - (5 - 2);"),
+        ("-(1 + 2)", "-(1 + 2);"),
+        ("2 ** 3 ** 2", "2 ** 3 ** 2;"),
+        ("(2 ** 3) ** 2", "(2 ** 3) ** 2;"),
+        ("(-2) ** 2 * 3", "(-2) ** 2 * 3;"),
+        ("-2 ** -1", "-2 ** -1;"),
+        ("!true == false", "!true == false;"),
+        ("10.0 + 1.5", "10.0 + 1.5;"),
+        ("\"mila\"", "\"mila\";"),
+        ("[1,2,   3][0]", "[1, 2, 3][0];"),
+        ("arr.push(1,2)", "arr.push(1, 2);"),
+        ("(a.b)(1)", "(a.b)(1);"),
+        ("ret;", "ret;"),
+        ("fn sum(a,b){ret a+b;}", "fn sum(a, b) {\n    ret a + b;\n}"),
+        ("let f = fn(){}", "let f = fn() {};"),
+        (
+            "if a { 1 } else if b { 2 } else { 3 }",
+            "if a {\n    1;\n} else if b {\n    2;\n} else {\n    3;\n}",
+        ),
+        (
+            "while i < 10 { let i = i + 1; }",
+            "while i < 10 {\n    let i = i + 1;\n}",
+        ),
+        (
+            "let obj = | b: 1, a: fn(x) { x }, |",
+            "let obj = |\n    b: 1,\n    a: fn(x) {\n        x;\n    },\n|;",
+        ),
+        ("| |", "| |;"),
+        ("fn f(a,b=a*2,...rest){}", "fn f(a, b = a * 2, ...rest) {}"),
+        ("import lib", "import lib;"),
+        (
+            "import \"lib/sort.mila\" as s",
+            "import \"lib/sort.mila\" as s;",
+        ),
+        ("export let a = 1", "export let a = 1;"),
+        ("export fn f(){ret 1;}", "export fn f() {\n    ret 1;\n}"),
+        (
+            "throw |message: \"no\",|",
+            "throw |\n    message: \"no\",\n|;",
+        ),
+        (
+            "try { f() } catch e { g(e) } finally { h() }",
+            "try {\n    f();\n} catch e {\n    g(e);\n} finally {\n    h();\n}",
+        ),
+        (
+            "let a = try { 1 } finally {}",
+            "let a = try {\n    1;\n} finally {};",
+        ),
+        (
+            "test \"sums\" { assert(1 + 1 == 2) }",
+            "test \"sums\" {\n    assert(1 + 1 == 2);\n}",
+        ),
+        ("test(\"sums\")", "test(\"sums\");"),
+    ];
+    for (source, expected) in tests {
+        let program = make_parser(source.to_string()).parse_program();
+        assert_eq!(0, program.errors.len(), "wrong number of errors");
+        assert_eq!(expected, program.to_string());
+    }
+}
+
+#[test]
+fn test_parse_try_errors() {
+    let tests = vec![
+        (
+            "try { 1 }",
+            "expected catch or finally after try in foo.bzr:1:1",
+        ),
+        (
+            "try { 1 } catch { 2 }",
+            "expected 'identifier', got '{' in foo.bzr:1:17",
+        ),
+        (
+            "let a = (1",
+            "expected ')', got end of input in foo.bzr:1:11",
+        ),
+    ];
+    for (source, expected) in tests {
+        let program = make_parser(source.to_string()).parse_program();
+        assert_eq!(Some(&expected.to_string()), program.errors.first());
+    }
+}
+
+#[test]
+fn test_display_is_stable_for_examples() {
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "mila") {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).unwrap();
+        let program = make_parser(source).parse_program();
+        let printed = program.to_string();
+        let reparsed = make_parser(printed.clone()).parse_program();
+        assert_eq!(0, reparsed.errors.len(), "errors reparsing {:?}", path);
+        assert_eq!(
+            printed,
+            reparsed.to_string(),
+            "unstable print of {:?}",
+            path
+        );
+    }
+}
+
+#[test]
+fn test_parse_large_streamed_input() {
+    let source: String = (0..5000)
+        .map(|i| format!("let value_{} = \"text {}\"; // comment {}\n", i, i, i))
+        .collect();
+    assert!(source.len() > 128 * 1024);
+    let reader = std::io::Cursor::new(source.into_bytes());
+    let lexer = Lexer::from_reader(reader, Rc::new("large.mila".to_string()));
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!(0, program.errors.len());
+    assert_eq!(5000, program.statements.len());
+    assert_eq!(
+        "let value_4999 = \"text 4999\";",
+        program.statements[4999].to_string()
+    );
+    let lexer = Lexer::with_trivia(
+        "let a = 1; // one\n// two\nlet b = 2;".to_string(),
+        Rc::new("trivia.mila".to_string()),
+    );
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!("let a = 1; // one\n// two\nlet b = 2;", program.to_string());
+}
@@ -0,0 +1,89 @@
+use std::rc::Rc;
+
+use mila::{
+    ast::node::{Expr, FnExpr, LetStatement, Stmt},
+    evaluator::environment::Environment,
+    lexer::Lexer,
+    parser::Parser,
+    resolver::{resolve, Slot},
+};
+
+#[test]
+fn test_resolve_slots() {
+    let program =
+        make_program("let g = 1; fn f(a) { let b = a; fn inner() { ret b + g; } ret inner; }");
+    let unresolved = resolve(&program, &Environment::default());
+    assert!(unresolved.is_empty());
+    assert_eq!(
+        Slot::Global,
+        let_stmt(&program.statements[0]).name.slot.get()
+    );
+
+    let f = function(&program.statements[1]);
+    assert_eq!(3, f.slots.get(), "wrong number of slots");
+    assert_eq!(Slot::Global, f.name.as_ref().unwrap().slot.get());
+    let body = statements(f);
+    let b = let_stmt(&body[0]);
+    assert_eq!(Slot::Local(0, 1), b.name.slot.get());
+    assert_eq!(Slot::Local(0, 0), identifier(&b.value));
+
+    let inner = function(&body[1]);
+    assert_eq!(Slot::Local(0, 2), inner.name.as_ref().unwrap().slot.get());
+    let Stmt::Ret(ret) = &statements(inner)[0] else {
+        panic!("expected a ret statement");
+    };
+    let Some(Expr::Infix(sum)) = &ret.value else {
+        panic!("expected an infix expression");
+    };
+    assert_eq!(Slot::Local(1, 1), identifier(&sum.left));
+    assert_eq!(Slot::Global, identifier(&sum.right));
+}
+
+#[test]
+fn test_resolve_unknown_words() {
+    let program = make_program("fn f() { ret x; } fn h() { let y = 1; ret y; }");
+    let unresolved = resolve(&program, &Environment::default());
+    let names: Vec<String> = unresolved
+        .iter()
+        .map(|(name, location)| format!("{} {}", name, location))
+        .collect();
+    assert_eq!(vec!["x foo.mila:1:14"], names);
+    let h = function(&program.statements[1]);
+    let y = let_stmt(&statements(h)[0]);
+    assert_eq!(Slot::Local(0, 0), y.name.slot.get());
+}
+
+fn make_program(source: &str) -> mila::ast::Program {
+    let lexer = Lexer::new(source.to_string(), Rc::new("foo.mila".to_string()));
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!(0, program.errors.len(), "wrong number of errors");
+    program
+}
+
+fn identifier(expr: &Expr) -> Slot {
+    match expr {
+        Expr::Identifier(identifier) => identifier.slot.get(),
+        _ => panic!("expected an identifier"),
+    }
+}
+
+fn let_stmt(stmt: &Stmt) -> &LetStatement {
+    match stmt {
+        Stmt::Let(let_stmt) => let_stmt,
+        _ => panic!("expected a let statement"),
+    }
+}
+
+fn function(stmt: &Stmt) -> &FnExpr {
+    match stmt {
+        Stmt::Expression(expr) => match &expr.expression {
+            Expr::Function(function) => function,
+            _ => panic!("expected a function"),
+        },
+        _ => panic!("expected an expression statement"),
+    }
+}
+
+fn statements(function: &FnExpr) -> &[Stmt] {
+    &function.body.statements
+}
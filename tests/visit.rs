@@ -0,0 +1,108 @@
+use std::rc::Rc;
+
+use mila::{
+    ast::{
+        fold::{fold_expr, fold_program, Folder},
+        node::{Expr, IntExpr},
+        visit::{walk_expr, walk_program, Visitor},
+        Program,
+    },
+    lexer::Lexer,
+    parser::Parser,
+};
+
+struct Identifiers {
+    names: Vec<String>,
+}
+
+impl Visitor for Identifiers {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Identifier(identifier) = expr {
+            self.names.push(identifier.value.clone());
+        }
+        walk_expr(self, expr)
+    }
+}
+
+struct Double;
+
+impl Folder for Double {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::Int(int) => Expr::Int(IntExpr::new(int.value * 2)),
+            expr => fold_expr(self, expr),
+        }
+    }
+}
+
+struct Identity;
+
+impl Folder for Identity {}
+
+// identifiers in the order they are folded
+struct Folded {
+    names: Vec<String>,
+}
+
+impl Folder for Folded {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        if let Expr::Identifier(identifier) = &expr {
+            self.names.push(identifier.value.clone());
+        }
+        fold_expr(self, expr)
+    }
+}
+
+#[test]
+fn test_visitor_walks_in_evaluation_order() {
+    let program = make_program(
+        "let a = f(b, c[d]); fn g(x = y) { if x { ret z; } else if w { v; } } while u { t; }",
+    );
+    let mut visitor = Identifiers { names: Vec::new() };
+    walk_program(&mut visitor, &program);
+    let expected = vec!["f", "b", "c", "d", "y", "x", "z", "w", "v", "u", "t"];
+    assert_eq!(expected, visitor.names);
+}
+
+#[test]
+fn test_folder_rewrites_nodes() {
+    let program = make_program("let a = [1, 2]; fn f(x = 3) { ret x + 4; } let h = |k: 5,|;");
+    let folded = fold_program(&mut Double, program);
+    assert_eq!(
+        "let a = [2, 4];\nfn f(x = 6) {\n    ret x + 8;\n}\nlet h = |\n    k: 10,\n|;",
+        folded.to_string()
+    );
+}
+
+#[test]
+fn test_folder_folds_in_evaluation_order() {
+    let program = make_program("a[b][c + d[e]];");
+    let mut folder = Folded { names: Vec::new() };
+    fold_program(&mut folder, program);
+    assert_eq!(vec!["a", "b", "c", "d", "e"], folder.names);
+}
+
+#[test]
+fn test_identity_fold_keeps_examples() {
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "mila") {
+            continue;
+        }
+        let program = make_program(&std::fs::read_to_string(&path).unwrap());
+        let printed = program.to_string();
+        assert_eq!(
+            printed,
+            fold_program(&mut Identity, program).to_string(),
+            "{:?}",
+            path
+        );
+    }
+}
+
+fn make_program(source: &str) -> Program {
+    let lexer = Lexer::new(source.to_string(), Rc::new("foo.mila".to_string()));
+    let program = Parser::new(lexer).parse_program();
+    assert_eq!(0, program.errors.len(), "wrong number of errors");
+    program
+}